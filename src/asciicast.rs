@@ -0,0 +1,303 @@
+//! Terminal session recording in asciicast v2 format.
+//!
+//! A recording is newline-delimited JSON: a header object, then one
+//! `[seconds, code, data]` array per event. `"o"` events carry terminal
+//! output and `"r"` events a resize (`"COLSxROWS"`). Files are written to
+//! `<data>/recordings/` and play back with asciinema as well as with
+//! `bisque-computer play <file>`, which replays them into a read-only pane.
+//!
+//! Output is recorded on the pane's reader thread as bytes arrive, so event
+//! times reflect the program rather than the render loop.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc as tokio_mpsc;
+use tracing::warn;
+
+use crate::paths::BisquePaths;
+
+/// Written after the last event of a replay so the end is visible.
+const END_OF_RECORDING: &str = "\r\n\x1b[2m[end of recording]\x1b[0m\r\n";
+
+/// The header line of an asciicast v2 file.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Header {
+    pub version: u32,
+    pub width: usize,
+    pub height: usize,
+    /// Unix time the recording started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+/// One `[time, code, data]` line.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Event(pub f64, pub String, pub String);
+
+/// A recording read back from disk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cast {
+    pub header: Header,
+    pub events: Vec<Event>,
+}
+
+/// A new file path under `<data>/recordings/` for a recording of `label`.
+pub fn new_recording_path(label: &str) -> Option<PathBuf> {
+    let dir = BisquePaths::resolve()?.recordings;
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    Some(dir.join(format!("{}-{}.cast", stamp, label)))
+}
+
+// ---------------------------------------------------------------------------
+// Recording
+// ---------------------------------------------------------------------------
+
+/// Appends events to an open `.cast` file.
+pub struct Recorder {
+    out: BufWriter<File>,
+    path: PathBuf,
+    start: Instant,
+    /// Tail of a UTF-8 sequence split across reads, held for the next chunk.
+    pending: Vec<u8>,
+}
+
+impl Recorder {
+    /// Create `path` (and its directory) and write the header.
+    pub fn create(path: &Path, cols: usize, rows: usize, title: &str) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut env = BTreeMap::new();
+        env.insert("TERM".to_string(), "xterm-256color".to_string());
+        if let Ok(shell) = std::env::var("SHELL") {
+            env.insert("SHELL".to_string(), shell);
+        }
+        let header = Header {
+            version: 2,
+            width: cols,
+            height: rows,
+            timestamp: Some(chrono::Utc::now().timestamp()),
+            title: Some(title.to_string()),
+            env,
+        };
+        let mut out = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut out, &header)?;
+        out.write_all(b"\n")?;
+        out.flush()?;
+        Ok(Self {
+            out,
+            path: path.to_path_buf(),
+            start: Instant::now(),
+            pending: Vec::new(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record a chunk of terminal output.
+    pub fn output(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        self.pending.extend_from_slice(bytes);
+        let complete = match std::str::from_utf8(&self.pending) {
+            Ok(_) => self.pending.len(),
+            // Incomplete sequence at the end: keep it for the next chunk.
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            // Genuinely invalid bytes are written lossily.
+            Err(_) => self.pending.len(),
+        };
+        if complete == 0 {
+            return Ok(());
+        }
+        let rest = self.pending.split_off(complete);
+        let data = String::from_utf8_lossy(&self.pending).into_owned();
+        self.pending = rest;
+        self.event("o", &data)
+    }
+
+    /// Record a terminal resize.
+    pub fn resize(&mut self, cols: usize, rows: usize) -> std::io::Result<()> {
+        self.event("r", &format!("{}x{}", cols, rows))
+    }
+
+    fn event(&mut self, code: &str, data: &str) -> std::io::Result<()> {
+        let time = (self.start.elapsed().as_secs_f64() * 1e6).round() / 1e6;
+        serde_json::to_writer(&mut self.out, &Event(time, code.to_string(), data.to_string()))?;
+        self.out.write_all(b"\n")?;
+        // Flushed per event so a crash loses nothing already on screen.
+        self.out.flush()
+    }
+}
+
+/// A recorder slot shared between a pane and its reader thread.
+pub type SharedRecorder = Arc<Mutex<Option<Recorder>>>;
+
+/// Record `bytes` if the slot holds a recorder. A write failure stops the
+/// recording rather than retrying on every chunk.
+pub fn record_output(shared: &SharedRecorder, bytes: &[u8]) {
+    let Ok(mut slot) = shared.lock() else { return };
+    if let Some(recorder) = slot.as_mut() {
+        if let Err(e) = recorder.output(bytes) {
+            warn!(target: "asciicast", "Stopped recording {}: {}", recorder.path().display(), e);
+            *slot = None;
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Playback
+// ---------------------------------------------------------------------------
+
+impl Cast {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+        let (_, first) = lines.next().ok_or("empty recording")?;
+        let header: Header = serde_json::from_str(first).map_err(|e| format!("bad header: {}", e))?;
+        if header.version != 2 {
+            return Err(format!("unsupported asciicast version {}", header.version));
+        }
+        let events = lines
+            .map(|(i, line)| serde_json::from_str(line).map_err(|e| format!("line {}: {}", i + 1, e)))
+            .collect::<Result<Vec<Event>, _>>()?;
+        Ok(Self { header, events })
+    }
+
+    /// Output events with the delay to wait before each one.
+    ///
+    /// `speed` divides every gap; `idle_limit` caps a gap (after scaling),
+    /// so long pauses in an unattended session don't stall the replay.
+    pub fn output_schedule(&self, speed: f64, idle_limit: Option<f64>) -> Vec<(Duration, &str)> {
+        let speed = if speed > 0.0 { speed } else { 1.0 };
+        let mut last = 0.0;
+        self.events
+            .iter()
+            .filter(|Event(_, code, _)| code == "o")
+            .map(|Event(time, _, data)| {
+                let mut gap = ((time - last) / speed).max(0.0);
+                last = *time;
+                if let Some(limit) = idle_limit {
+                    gap = gap.min(limit);
+                }
+                (Duration::from_secs_f64(gap), data.as_str())
+            })
+            .collect()
+    }
+}
+
+/// Replay `cast` on a background thread, returning the output stream for
+/// `TerminalPane::from_streams`.
+pub fn spawn_player(cast: Cast, speed: f64, idle_limit: Option<f64>) -> tokio_mpsc::UnboundedReceiver<Vec<u8>> {
+    let (tx, rx) = tokio_mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        for (delay, data) in cast.output_schedule(speed, idle_limit) {
+            std::thread::sleep(delay);
+            if tx.send(data.as_bytes().to_vec()).is_err() {
+                return;
+            }
+        }
+        let _ = tx.send(END_OF_RECORDING.as_bytes().to_vec());
+    });
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!(
+                "bisque_asciicast_test_{}_{}",
+                std::process::id(),
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_nanos()
+            ))
+            .join(name)
+    }
+
+    #[test]
+    fn recording_roundtrips() {
+        let path = temp_path("session.cast");
+        {
+            let mut rec = Recorder::create(&path, 80, 24, "test").unwrap();
+            rec.output(b"hello\r\n").unwrap();
+            rec.resize(100, 30).unwrap();
+            rec.output(b"\x1b[1mbold\x1b[0m").unwrap();
+        }
+        let cast = Cast::load(&path).unwrap();
+        assert_eq!(cast.header.width, 80);
+        assert_eq!(cast.header.height, 24);
+        assert_eq!(cast.header.title.as_deref(), Some("test"));
+        let codes: Vec<&str> = cast.events.iter().map(|e| e.1.as_str()).collect();
+        assert_eq!(codes, ["o", "r", "o"]);
+        assert_eq!(cast.events[1].2, "100x30");
+        assert_eq!(cast.events[2].2, "\x1b[1mbold\x1b[0m");
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn split_utf8_sequences_are_joined() {
+        let path = temp_path("utf8.cast");
+        {
+            let mut rec = Recorder::create(&path, 80, 24, "test").unwrap();
+            let bytes = "é─".as_bytes();
+            rec.output(&bytes[..1]).unwrap();
+            rec.output(&bytes[1..3]).unwrap();
+            rec.output(&bytes[3..]).unwrap();
+        }
+        let cast = Cast::load(&path).unwrap();
+        let text: String = cast.events.iter().map(|e| e.2.as_str()).collect();
+        assert_eq!(text, "é─");
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn parses_asciinema_files() {
+        let text = concat!(
+            r#"{"version": 2, "width": 120, "height": 40, "timestamp": 1700000000, "env": {"TERM": "xterm"}}"#,
+            "\n",
+            r#"[0.25, "o", "$ ls\r\n"]"#,
+            "\n",
+            r#"[0.5, "i", "q"]"#,
+            "\n",
+        );
+        let cast = Cast::parse(text).unwrap();
+        assert_eq!(cast.header.width, 120);
+        assert_eq!(cast.events.len(), 2);
+        assert!(Cast::parse(r#"{"version": 1, "width": 80, "height": 24}"#).is_err());
+        assert!(Cast::parse("").is_err());
+    }
+
+    #[test]
+    fn schedule_scales_and_caps_gaps() {
+        let cast = Cast {
+            header: Header { version: 2, width: 80, height: 24, timestamp: None, title: None, env: BTreeMap::new() },
+            events: vec![
+                Event(1.0, "o".into(), "a".into()),
+                Event(1.5, "r".into(), "90x30".into()),
+                Event(2.0, "o".into(), "b".into()),
+                Event(62.0, "o".into(), "c".into()),
+            ],
+        };
+        let delays: Vec<f64> = cast.output_schedule(2.0, Some(3.0)).iter().map(|(d, _)| d.as_secs_f64()).collect();
+        assert_eq!(delays, [0.5, 0.5, 3.0]);
+        let data: Vec<&str> = cast.output_schedule(1.0, None).iter().map(|(_, s)| *s).collect();
+        assert_eq!(data, ["a", "b", "c"]);
+    }
+}
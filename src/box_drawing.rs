@@ -0,0 +1,390 @@
+//! Box-drawing, block-element and powerline glyphs drawn as geometry.
+//!
+//! Font glyphs for these characters rarely fill the cell exactly, so TUI
+//! borders show gaps between rows and shading blocks don't meet. Drawing
+//! them from the cell rectangle makes adjacent cells join pixel-perfectly
+//! at any font size. Covered: U+2500–257F (box drawing), U+2580–259F
+//! (block elements) and the powerline separators U+E0B0–E0B3.
+
+use vello::Scene;
+use vello::kurbo::{Affine, BezPath, Cap, Point, Rect, Stroke};
+use vello::peniko::{Color, Fill};
+
+/// Stroke weight of one arm of a box-drawing character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Weight {
+    None,
+    Light,
+    Heavy,
+    Double,
+}
+
+/// Arms of a box-drawing character, in `[left, right, up, down]` order.
+type Arms = [Weight; 4];
+const LEFT: usize = 0;
+const RIGHT: usize = 1;
+const UP: usize = 2;
+const DOWN: usize = 3;
+
+/// U+2500–254B, one entry per character: left, right, up, down, with
+/// `-` none, `l` light, `h` heavy. Dashed lines (U+2504–250B) are listed
+/// with their solid weight and drawn by `dashes`.
+const LINES_2500: [&str; 0x4C] = [
+    "ll--", "hh--", "--ll", "--hh", "ll--", "hh--", "--ll", "--hh", // 2500
+    "ll--", "hh--", "--ll", "--hh", "-l-l", "-h-l", "-l-h", "-h-h", // 2508
+    "l--l", "h--l", "l--h", "h--h", "-ll-", "-hl-", "-lh-", "-hh-", // 2510
+    "l-l-", "h-l-", "l-h-", "h-h-", "-lll", "-hll", "-lhl", "-llh", // 2518
+    "-lhh", "-hhl", "-hlh", "-hhh", "l-ll", "h-ll", "l-hl", "l-lh", // 2520
+    "l-hh", "h-hl", "h-lh", "h-hh", "ll-l", "hl-l", "lh-l", "hh-l", // 2528
+    "ll-h", "hl-h", "lh-h", "hh-h", "lll-", "hll-", "lhl-", "hhl-", // 2530
+    "llh-", "hlh-", "lhh-", "hhh-", "llll", "hlll", "lhll", "hhll", // 2538
+    "llhl", "lllh", "llhh", "hlhl", "lhhl", "hllh", "lhlh", "hhhl", // 2540
+    "hhlh", "hlhh", "lhhh", "hhhh", // 2548
+];
+
+/// U+2550–256C: `d` is a double line.
+const LINES_2550: [&str; 0x1D] = [
+    "dd--", "--dd", "-d-l", "-l-d", "-d-d", "d--l", "l--d", "d--d", // 2550
+    "-dl-", "-ld-", "-dd-", "d-l-", "l-d-", "d-d-", "-dll", "-ldd", // 2558
+    "-ddd", "d-ll", "l-dd", "d-dd", "dd-l", "ll-d", "dd-d", "ddl-", // 2560
+    "lld-", "ddd-", "ddll", "lldd", "dddd", // 2568
+];
+
+/// U+2574–257F: half lines and mixed-weight lines.
+const LINES_2574: [&str; 0xC] = [
+    "l---", "--l-", "-l--", "---l", "h---", "--h-", "-h--", "---h", "lh--", "--lh", "hl--", "--hl",
+];
+
+fn parse_arms(spec: &str) -> Arms {
+    let mut arms = [Weight::None; 4];
+    for (arm, b) in arms.iter_mut().zip(spec.bytes()) {
+        *arm = match b {
+            b'l' => Weight::Light,
+            b'h' => Weight::Heavy,
+            b'd' => Weight::Double,
+            _ => Weight::None,
+        };
+    }
+    arms
+}
+
+/// Straight-line arms for `c`, if it is a plain line character.
+fn line_arms(c: char) -> Option<Arms> {
+    let spec = match c as u32 {
+        n @ 0x2500..=0x254B => LINES_2500[(n - 0x2500) as usize],
+        n @ 0x2550..=0x256C => LINES_2550[(n - 0x2550) as usize],
+        n @ 0x2574..=0x257F => LINES_2574[(n - 0x2574) as usize],
+        _ => return None,
+    };
+    Some(parse_arms(spec))
+}
+
+/// Dash count and orientation for the dashed lines.
+fn dashes(c: char) -> Option<(usize, bool)> {
+    // (count, horizontal)
+    Some(match c {
+        '┄' | '┅' => (3, true),
+        '┆' | '┇' => (3, false),
+        '┈' | '┉' => (4, true),
+        '┊' | '┋' => (4, false),
+        '╌' | '╍' => (2, true),
+        '╎' | '╏' => (2, false),
+        _ => return None,
+    })
+}
+
+/// Filled rectangles for a block element, as `(x0, y0, x1, y1, alpha)` in
+/// fractions of the cell.
+fn block_rects(c: char) -> Option<Vec<(f64, f64, f64, f64, f32)>> {
+    const UL: (f64, f64, f64, f64, f32) = (0.0, 0.0, 0.5, 0.5, 1.0);
+    const UR: (f64, f64, f64, f64, f32) = (0.5, 0.0, 1.0, 0.5, 1.0);
+    const LL: (f64, f64, f64, f64, f32) = (0.0, 0.5, 0.5, 1.0, 1.0);
+    const LR: (f64, f64, f64, f64, f32) = (0.5, 0.5, 1.0, 1.0, 1.0);
+    let n = c as u32;
+    Some(match n {
+        0x2580 => vec![(0.0, 0.0, 1.0, 0.5, 1.0)],
+        0x2581..=0x2588 => {
+            let eighths = (n - 0x2580) as f64;
+            vec![(0.0, 1.0 - eighths / 8.0, 1.0, 1.0, 1.0)]
+        }
+        0x2589..=0x258F => {
+            let eighths = (0x2590 - n) as f64;
+            vec![(0.0, 0.0, eighths / 8.0, 1.0, 1.0)]
+        }
+        0x2590 => vec![(0.5, 0.0, 1.0, 1.0, 1.0)],
+        0x2591..=0x2593 => vec![(0.0, 0.0, 1.0, 1.0, (n - 0x2590) as f32 * 0.25)],
+        0x2594 => vec![(0.0, 0.0, 1.0, 0.125, 1.0)],
+        0x2595 => vec![(0.875, 0.0, 1.0, 1.0, 1.0)],
+        0x2596 => vec![LL],
+        0x2597 => vec![LR],
+        0x2598 => vec![UL],
+        0x2599 => vec![UL, LL, LR],
+        0x259A => vec![UL, LR],
+        0x259B => vec![UL, UR, LL],
+        0x259C => vec![UL, UR, LR],
+        0x259D => vec![UR],
+        0x259E => vec![UR, LL],
+        0x259F => vec![UR, LL, LR],
+        _ => return None,
+    })
+}
+
+/// Whether `c` is drawn here rather than from a font.
+pub fn is_drawn(c: char) -> bool {
+    matches!(c as u32, 0x2500..=0x259F | 0xE0B0..=0xE0B3)
+}
+
+/// Draw `c` into the cell at `(x, y)` of size `w`×`h`. Returns `false` if
+/// `c` is not one of the characters this module draws.
+pub fn draw(scene: &mut Scene, c: char, x: f64, y: f64, w: f64, h: f64, color: Color) -> bool {
+    if !is_drawn(c) {
+        return false;
+    }
+    let light = (w / 8.0).round().max(1.0);
+    let cell = CellGeom { x, y, w, h, light };
+
+    if let Some(rects) = block_rects(c) {
+        for (x0, y0, x1, y1, alpha) in rects {
+            let r = cell.snap(x + x0 * w, y + y0 * h, x + x1 * w, y + y1 * h);
+            scene.fill(Fill::NonZero, Affine::IDENTITY, color.multiply_alpha(alpha), None, &r);
+        }
+        return true;
+    }
+
+    if let Some((count, horizontal)) = dashes(c) {
+        let arms = line_arms(c).unwrap_or([Weight::Light; 4]);
+        let t = cell.thickness(arms[if horizontal { LEFT } else { UP }]);
+        let span = if horizontal { w } else { h };
+        let step = span / count as f64;
+        let gap = (step * 0.35).max(1.0);
+        for i in 0..count {
+            let a = i as f64 * step + gap / 2.0;
+            let b = (i + 1) as f64 * step - gap / 2.0;
+            let r = if horizontal {
+                cell.snap(x + a, cell.cy() - t / 2.0, x + b, cell.cy() + t / 2.0)
+            } else {
+                cell.snap(cell.cx() - t / 2.0, y + a, cell.cx() + t / 2.0, y + b)
+            };
+            scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &r);
+        }
+        return true;
+    }
+
+    if let Some(arms) = line_arms(c) {
+        draw_arms(scene, &cell, arms, color);
+        return true;
+    }
+
+    match c {
+        '╭' | '╮' | '╯' | '╰' => draw_arc(scene, &cell, c, color),
+        '╱' | '╲' | '╳' => {
+            let stroke = Stroke::new(light).with_caps(Cap::Butt);
+            if c != '╲' {
+                let line = vello::kurbo::Line::new((x + w, y), (x, y + h));
+                scene.stroke(&stroke, Affine::IDENTITY, color, None, &line);
+            }
+            if c != '╱' {
+                let line = vello::kurbo::Line::new((x, y), (x + w, y + h));
+                scene.stroke(&stroke, Affine::IDENTITY, color, None, &line);
+            }
+        }
+        '\u{E0B0}' | '\u{E0B2}' => {
+            // Solid powerline separators: triangles pointing right / left.
+            let (base, tip) = if c == '\u{E0B0}' { (x, x + w) } else { (x + w, x) };
+            let mut path = BezPath::new();
+            path.move_to((base, y));
+            path.line_to((tip, cell.cy()));
+            path.line_to((base, y + h));
+            path.close_path();
+            scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &path);
+        }
+        '\u{E0B1}' | '\u{E0B3}' => {
+            let (base, tip) = if c == '\u{E0B1}' { (x, x + w) } else { (x + w, x) };
+            let mut path = BezPath::new();
+            path.move_to((base, y));
+            path.line_to((tip, cell.cy()));
+            path.line_to((base, y + h));
+            scene.stroke(&Stroke::new(light), Affine::IDENTITY, color, None, &path);
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Cell rectangle and stroke metrics for one character.
+struct CellGeom {
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+    light: f64,
+}
+
+impl CellGeom {
+    /// Horizontal center, on a pixel boundary so light lines stay crisp.
+    fn cx(&self) -> f64 {
+        (self.x + self.w / 2.0).floor()
+    }
+
+    fn cy(&self) -> f64 {
+        (self.y + self.h / 2.0).floor()
+    }
+
+    fn thickness(&self, weight: Weight) -> f64 {
+        match weight {
+            Weight::Heavy => self.light * 2.0,
+            _ => self.light,
+        }
+    }
+
+    /// Half the gap between the two strokes of a double line.
+    fn double_gap(&self) -> f64 {
+        self.light.max(1.0) * 1.5
+    }
+
+    /// Round to whole pixels so neighbouring cells meet without seams.
+    fn snap(&self, x0: f64, y0: f64, x1: f64, y1: f64) -> Rect {
+        Rect::new(x0.round(), y0.round(), x1.round(), y1.round())
+    }
+}
+
+fn draw_arms(scene: &mut Scene, cell: &CellGeom, arms: Arms, color: Color) {
+    let (cx, cy) = (cell.cx(), cell.cy());
+    let (x0, y0, x1, y1) = (cell.x, cell.y, cell.x + cell.w, cell.y + cell.h);
+    let g = cell.double_gap();
+    let horizontal_double = arms[LEFT] == Weight::Double || arms[RIGHT] == Weight::Double;
+    let vertical_double = arms[UP] == Weight::Double || arms[DOWN] == Weight::Double;
+    // Half the thickness of the widest perpendicular stroke, so single
+    // arms overlap at the center instead of leaving a notch.
+    let reach_h = [arms[UP], arms[DOWN]].iter().map(|w| cell.thickness(*w)).fold(0.0, f64::max) / 2.0;
+    let reach_v = [arms[LEFT], arms[RIGHT]].iter().map(|w| cell.thickness(*w)).fold(0.0, f64::max) / 2.0;
+    let has = |arm: usize| arms[arm] != Weight::None;
+    let mut fill = |r: Rect| scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &r);
+
+    for arm in [LEFT, RIGHT, UP, DOWN] {
+        let weight = arms[arm];
+        if weight == Weight::None {
+            continue;
+        }
+        let horizontal = arm == LEFT || arm == RIGHT;
+        let toward_start = arm == LEFT || arm == UP;
+
+        if weight == Weight::Double {
+            // Two strokes at ±g. Where a double line crosses, each stroke
+            // stops at the nearer stroke of the other line; against a
+            // single line they stop at its center.
+            for side in [-1.0, 1.0] {
+                let perp_double = if horizontal { vertical_double } else { horizontal_double };
+                let (near_a, near_b) = if horizontal { (UP, DOWN) } else { (LEFT, RIGHT) };
+                // The perpendicular arm on this stroke's side.
+                let side_arm = if side < 0.0 { near_a } else { near_b };
+                let inner = if perp_double {
+                    if has(side_arm) { g } else { -g }
+                } else {
+                    0.0
+                };
+                let t = cell.light;
+                if horizontal {
+                    let yy = cy + side * g;
+                    let (a, b) = if toward_start { (x0, cx - inner) } else { (cx + inner, x1) };
+                    fill(cell.snap(a, yy - t / 2.0, b.max(a), yy + t / 2.0));
+                } else {
+                    let xx = cx + side * g;
+                    let (a, b) = if toward_start { (y0, cy - inner) } else { (cy + inner, y1) };
+                    fill(cell.snap(xx - t / 2.0, a, xx + t / 2.0, b.max(a)));
+                }
+            }
+            continue;
+        }
+
+        let t = cell.thickness(weight);
+        let perp_double = if horizontal { vertical_double } else { horizontal_double };
+        let reach = if perp_double {
+            // A single line ending on a double line that runs straight
+            // through (╤) stops at its near stroke; one that meets a double
+            // corner (╒) reaches the far stroke.
+            let through = if horizontal { has(UP) && has(DOWN) } else { has(LEFT) && has(RIGHT) };
+            let opposite = match arm {
+                LEFT => RIGHT,
+                RIGHT => LEFT,
+                UP => DOWN,
+                _ => UP,
+            };
+            if through && !has(opposite) { -g } else { g }
+        } else if horizontal {
+            reach_h
+        } else {
+            reach_v
+        };
+        if horizontal {
+            let (a, b) = if toward_start { (x0, cx + reach) } else { (cx - reach, x1) };
+            fill(cell.snap(a, cy - t / 2.0, b, cy + t / 2.0));
+        } else {
+            let (a, b) = if toward_start { (y0, cy + reach) } else { (cy - reach, y1) };
+            fill(cell.snap(cx - t / 2.0, a, cx + t / 2.0, b));
+        }
+    }
+}
+
+/// Rounded corners: a straight run from each edge into a quarter curve.
+fn draw_arc(scene: &mut Scene, cell: &CellGeom, c: char, color: Color) {
+    let (cx, cy) = (cell.cx(), cell.cy());
+    let r = (cell.w.min(cell.h) / 2.0).floor();
+    // Edge the vertical arm runs to, and edge the horizontal arm runs to.
+    let (vy, hx) = match c {
+        '╭' => (cell.y + cell.h, cell.x + cell.w),
+        '╮' => (cell.y + cell.h, cell.x),
+        '╯' => (cell.y, cell.x),
+        _ => (cell.y, cell.x + cell.w),
+    };
+    let dy = (vy - cy).signum();
+    let dx = (hx - cx).signum();
+    let mut path = BezPath::new();
+    path.move_to(Point::new(cx, vy));
+    path.line_to(Point::new(cx, cy + dy * r));
+    path.quad_to(Point::new(cx, cy), Point::new(cx + dx * r, cy));
+    path.line_to(Point::new(hx, cy));
+    scene.stroke(&Stroke::new(cell.light), Affine::IDENTITY, color, None, &path);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_tables_match_the_characters() {
+        use Weight::*;
+        assert_eq!(line_arms('─'), Some([Light, Light, None, None]));
+        assert_eq!(line_arms('┃'), Some([None, None, Heavy, Heavy]));
+        assert_eq!(line_arms('┌'), Some([None, Light, None, Light]));
+        assert_eq!(line_arms('┛'), Some([Heavy, None, Heavy, None]));
+        assert_eq!(line_arms('┼'), Some([Light, Light, Light, Light]));
+        assert_eq!(line_arms('╋'), Some([Heavy, Heavy, Heavy, Heavy]));
+        assert_eq!(line_arms('╔'), Some([None, Double, None, Double]));
+        assert_eq!(line_arms('╡'), Some([Double, None, Light, Light]));
+        assert_eq!(line_arms('╬'), Some([Double, Double, Double, Double]));
+        assert_eq!(line_arms('╼'), Some([Light, Heavy, None, None]));
+        assert_eq!(line_arms('a'), None);
+    }
+
+    #[test]
+    fn block_elements_cover_the_right_fraction() {
+        assert_eq!(block_rects('█'), Some(vec![(0.0, 0.0, 1.0, 1.0, 1.0)]));
+        assert_eq!(block_rects('▄'), Some(vec![(0.0, 0.5, 1.0, 1.0, 1.0)]));
+        assert_eq!(block_rects('▌'), Some(vec![(0.0, 0.0, 0.5, 1.0, 1.0)]));
+        assert_eq!(block_rects('▒'), Some(vec![(0.0, 0.0, 1.0, 1.0, 0.5)]));
+        assert_eq!(block_rects('▚').map(|r| r.len()), Some(2));
+        assert_eq!(block_rects('─'), None);
+    }
+
+    #[test]
+    fn every_covered_character_is_drawn() {
+        let mut scene = Scene::new();
+        let black = Color::new([0.0, 0.0, 0.0, 1.0]);
+        for n in (0x2500..=0x259F).chain(0xE0B0..=0xE0B3) {
+            let c = char::from_u32(n).unwrap();
+            assert!(draw(&mut scene, c, 0.0, 0.0, 10.0, 20.0, black), "U+{:04X}", n);
+        }
+        assert!(!draw(&mut scene, 'x', 0.0, 0.0, 10.0, 20.0, black));
+    }
+}
@@ -0,0 +1,425 @@
+//! Terminal color schemes.
+//!
+//! `ColorScheme` is the palette a terminal pane draws with: default
+//! foreground and background, cursor, selection and the 16 ANSI colors.
+//! It is built from `DesignTokens.terminal`, optionally overlaid with a
+//! scheme file imported from iTerm2 (`.itermcolors`), Alacritty (`.toml`)
+//! or base16 (`.yaml`).
+//!
+//! Colors an application sets with OSC 4/10/11/12 live in alacritty's
+//! `Term::colors()` and take precedence over the scheme; `terminal.rs`
+//! falls back to the scheme for everything else.
+
+use std::path::Path;
+
+use alacritty_terminal::vte::ansi::{NamedColor, Rgb};
+use tracing::warn;
+use vello::peniko::Color;
+
+use crate::design::TerminalTokens;
+
+/// Brightness of the dim (SGR 2) variant of a color, as in xterm.
+const DIM_FACTOR: f32 = 0.66;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorScheme {
+    pub foreground: Color,
+    pub background: Color,
+    pub cursor: Color,
+    pub selection: Color,
+    /// ANSI colors 0-7, then their bright variants 8-15.
+    pub ansi: [Color; 16],
+    /// Draw bold text in colors 0-7 with the bright variant.
+    pub bold_is_bright: bool,
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        Self::from_tokens(&TerminalTokens::default())
+    }
+}
+
+impl ColorScheme {
+    /// Resolve the token colors, then apply `tokens.scheme` if one is set.
+    /// Invalid values are logged and replaced by the built-in defaults.
+    pub fn from_tokens(tokens: &TerminalTokens) -> Self {
+        let defaults = TerminalTokens::default();
+        let color = |name: &str, value: &str, default: &str| {
+            parse_hex(value).unwrap_or_else(|| {
+                warn!(target: "terminal", "Invalid terminal.{} color {:?}", name, value);
+                parse_hex(default).unwrap_or(Color::BLACK)
+            })
+        };
+        let ansi = std::array::from_fn(|i| {
+            let default = &defaults.palette[i];
+            let value = tokens.palette.get(i).unwrap_or(default);
+            color(&format!("palette.{}", i), value, default)
+        });
+        let mut scheme = Self {
+            foreground: color("foreground", &tokens.foreground, &defaults.foreground),
+            background: color("background", &tokens.background, &defaults.background),
+            cursor: color("cursor", &tokens.cursor, &defaults.cursor),
+            selection: color("selection", &tokens.selection, &defaults.selection),
+            ansi,
+            bold_is_bright: tokens.bold_is_bright,
+        };
+        if !tokens.scheme.is_empty() {
+            let path = expand_home(&tokens.scheme);
+            match import(&path) {
+                Ok(imported) => scheme.apply(&imported),
+                Err(e) => warn!(target: "terminal", "Ignoring color scheme: {}", e),
+            }
+        }
+        scheme
+    }
+
+    /// Replace every color the imported scheme defines.
+    pub fn apply(&mut self, imported: &ImportedScheme) {
+        let set = |slot: &mut Color, value: Option<Color>| {
+            if let Some(color) = value {
+                *slot = color;
+            }
+        };
+        set(&mut self.foreground, imported.foreground);
+        set(&mut self.background, imported.background);
+        set(&mut self.cursor, imported.cursor);
+        set(&mut self.selection, imported.selection);
+        for (slot, value) in self.ansi.iter_mut().zip(imported.ansi) {
+            set(slot, value);
+        }
+    }
+
+    pub fn named(&self, named: NamedColor) -> Color {
+        match named {
+            NamedColor::Foreground | NamedColor::BrightForeground => self.foreground,
+            NamedColor::Background => self.background,
+            NamedColor::Cursor => self.cursor,
+            NamedColor::DimForeground => dim(self.foreground),
+            n if (n as usize) < 16 => self.ansi[n as usize],
+            n => dim(self.ansi[(n as usize - NamedColor::DimBlack as usize) % 8]),
+        }
+    }
+
+    /// A color of the 256-color palette: the scheme's ANSI colors, then
+    /// the xterm 6x6x6 cube and grayscale ramp.
+    pub fn indexed(&self, idx: u8) -> Color {
+        if idx < 16 {
+            return self.ansi[idx as usize];
+        }
+        if idx < 232 {
+            // 6x6x6 color cube: indices 16..=231
+            let idx = (idx - 16) as u32;
+            let b = idx % 6;
+            let g = (idx / 6) % 6;
+            let r = idx / 36;
+            let to_f = |v: u32| -> f32 { if v == 0 { 0.0 } else { (55 + v * 40) as f32 / 255.0 } };
+            return Color::new([to_f(r), to_f(g), to_f(b), 1.0]);
+        }
+        // Grayscale ramp: indices 232..=255
+        let level = (idx - 232) as f32;
+        let v = (8.0 + level * 10.0) / 255.0;
+        Color::new([v, v, v, 1.0])
+    }
+
+    /// The color at alacritty color `index` (0-255 palette, then the
+    /// `NamedColor` slots), for answering OSC 4/10/11/12 queries.
+    pub fn rgb(&self, index: usize) -> Rgb {
+        let color = match u8::try_from(index) {
+            Ok(idx) => self.indexed(idx),
+            Err(_) if index == NamedColor::Background as usize => self.background,
+            Err(_) if index == NamedColor::Cursor as usize => self.cursor,
+            Err(_) => self.foreground,
+        };
+        let [r, g, b, _] = color.components.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+        Rgb { r, g, b }
+    }
+}
+
+fn dim(color: Color) -> Color {
+    let [r, g, b, a] = color.components;
+    Color::new([r * DIM_FACTOR, g * DIM_FACTOR, b * DIM_FACTOR, a])
+}
+
+/// Parse `#rrggbb`, `#rrggbbaa` or `0xrrggbb`.
+pub fn parse_hex(value: &str) -> Option<Color> {
+    let value = value.trim();
+    let hex = value
+        .strip_prefix('#')
+        .or_else(|| value.strip_prefix("0x"))
+        .or_else(|| value.strip_prefix("0X"))
+        .unwrap_or(value);
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok().map(|v| v as f32 / 255.0);
+    let alpha = if hex.len() == 8 { channel(6)? } else { 1.0 };
+    Some(Color::new([channel(0)?, channel(2)?, channel(4)?, alpha]))
+}
+
+/// Expand a leading `~/` to the home directory.
+pub fn expand_home(path: &str) -> std::path::PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => crate::home_dir().join(rest),
+        None => path.into(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Import
+// ---------------------------------------------------------------------------
+
+/// Colors read from a scheme file; `None` where the file sets none.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportedScheme {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub cursor: Option<Color>,
+    pub selection: Option<Color>,
+    pub ansi: [Option<Color>; 16],
+}
+
+impl ImportedScheme {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Read a scheme file, choosing the format by extension.
+pub fn import(path: &Path) -> Result<ImportedScheme, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+    let imported = match ext.as_str() {
+        "itermcolors" => parse_itermcolors(&text),
+        "toml" => parse_alacritty(&text)?,
+        "yaml" | "yml" => parse_base16(&text),
+        _ => {
+            return Err(format!(
+                "{}: unknown scheme format (expected .itermcolors, .toml or .yaml)",
+                path.display()
+            ));
+        }
+    };
+    if imported.is_empty() {
+        return Err(format!("{}: no colors found", path.display()));
+    }
+    Ok(imported)
+}
+
+/// iTerm2 `.itermcolors`: a plist mapping names like `Ansi 4 Color` to
+/// dicts of `Red/Green/Blue Component` reals.
+fn parse_itermcolors(text: &str) -> ImportedScheme {
+    let mut scheme = ImportedScheme::default();
+    let mut rest = text;
+    while let Some((name, after)) = element(rest, "key") {
+        rest = after;
+        let Some(body) = after.trim_start().strip_prefix("<dict>") else { continue };
+        let Some(end) = body.find("</dict>") else { break };
+        rest = &body[end..];
+        let color = iterm_color(&body[..end]);
+        match name {
+            "Foreground Color" => scheme.foreground = color,
+            "Background Color" => scheme.background = color,
+            "Cursor Color" => scheme.cursor = color,
+            "Selection Color" => scheme.selection = color,
+            _ => {
+                let index = name
+                    .strip_prefix("Ansi ")
+                    .and_then(|n| n.strip_suffix(" Color"))
+                    .and_then(|n| n.parse::<usize>().ok());
+                if let Some(slot) = index.and_then(|i| scheme.ansi.get_mut(i)) {
+                    *slot = color;
+                }
+            }
+        }
+    }
+    scheme
+}
+
+fn iterm_color(dict: &str) -> Option<Color> {
+    let mut rgba = [None, None, None, Some(1.0)];
+    let mut rest = dict;
+    while let Some((key, after)) = element(rest, "key") {
+        rest = after;
+        let channel = match key {
+            "Red Component" => 0,
+            "Green Component" => 1,
+            "Blue Component" => 2,
+            "Alpha Component" => 3,
+            _ => continue,
+        };
+        let (value, after) = element(rest, "real")?;
+        rest = after;
+        rgba[channel] = value.parse::<f32>().ok();
+    }
+    Some(Color::new([rgba[0]?, rgba[1]?, rgba[2]?, rgba[3]?]))
+}
+
+/// The text of the first `<tag>...</tag>` in `s`, and what follows it.
+fn element<'a>(s: &'a str, tag: &str) -> Option<(&'a str, &'a str)> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = s.find(&open)? + open.len();
+    let len = s[start..].find(&close)?;
+    Some((s[start..start + len].trim(), &s[start + len + close.len()..]))
+}
+
+/// Alacritty TOML: `[colors.primary]`, `[colors.normal]`,
+/// `[colors.bright]`, `[colors.cursor]` and `[colors.selection]`.
+fn parse_alacritty(text: &str) -> Result<ImportedScheme, String> {
+    const NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+    let value: toml::Value = toml::from_str(text).map_err(|e| e.to_string())?;
+    let colors = value.get("colors");
+    let get = |table: &str, key: &str| {
+        colors
+            .and_then(|c| c.get(table))
+            .and_then(|t| t.get(key))
+            .and_then(|v| v.as_str())
+            .and_then(parse_hex)
+    };
+    let mut scheme = ImportedScheme {
+        foreground: get("primary", "foreground"),
+        background: get("primary", "background"),
+        cursor: get("cursor", "cursor"),
+        selection: get("selection", "background"),
+        ..Default::default()
+    };
+    for (i, name) in NAMES.iter().enumerate() {
+        scheme.ansi[i] = get("normal", name);
+        scheme.ansi[i + 8] = get("bright", name);
+    }
+    Ok(scheme)
+}
+
+/// base16 YAML: `baseXX: "rrggbb"` entries, mapped to ANSI slots the way
+/// base16-shell does.
+fn parse_base16(text: &str) -> ImportedScheme {
+    const ANSI_BASES: [usize; 16] = [
+        0x00, 0x08, 0x0B, 0x0A, 0x0D, 0x0E, 0x0C, 0x05,
+        0x03, 0x08, 0x0B, 0x0A, 0x0D, 0x0E, 0x0C, 0x07,
+    ];
+    let mut bases = [None; 16];
+    for line in text.lines() {
+        let Some((key, value)) = line.trim().split_once(':') else { continue };
+        let Some(index) = key.trim().strip_prefix("base").and_then(|n| usize::from_str_radix(n, 16).ok()) else {
+            continue;
+        };
+        if let Some(slot) = bases.get_mut(index) {
+            // Drop a trailing comment, then the quotes.
+            let value = value.split(" #").next().unwrap_or("");
+            *slot = parse_hex(value.trim().trim_matches(|c| c == '"' || c == '\''));
+        }
+    }
+    ImportedScheme {
+        foreground: bases[0x05],
+        background: bases[0x00],
+        cursor: bases[0x05],
+        selection: bases[0x02],
+        ansi: ANSI_BASES.map(|b| bases[b]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_colors_parse() {
+        assert_eq!(parse_hex("#ffffff"), Some(Color::WHITE));
+        assert_eq!(parse_hex("0x000000"), Some(Color::BLACK));
+        let translucent = parse_hex("#3380ff59").unwrap();
+        assert!((translucent.components[3] - 0.349).abs() < 0.01);
+        assert_eq!(parse_hex("#fff"), None);
+        assert_eq!(parse_hex("CellForeground"), None);
+    }
+
+    #[test]
+    fn default_scheme_matches_built_in_colors() {
+        let scheme = ColorScheme::default();
+        let close = |a: Color, b: [f32; 4]| {
+            a.components.iter().zip(b).all(|(x, y)| (x - y).abs() < 0.01)
+        };
+        assert!(close(scheme.background, [1.0, 0.894, 0.769, 1.0]));
+        assert!(close(scheme.foreground, [0.0, 0.0, 0.0, 1.0]));
+        assert!(close(scheme.cursor, [0.40, 0.26, 0.13, 0.85]));
+        assert!(close(scheme.ansi[1], [0.80, 0.11, 0.11, 1.0]));
+        assert!(close(scheme.indexed(196), [1.0, 0.0, 0.0, 1.0]));
+        assert_eq!(scheme.rgb(NamedColor::Background as usize), Rgb { r: 255, g: 228, b: 196 });
+        assert!(!scheme.bold_is_bright);
+    }
+
+    #[test]
+    fn imports_itermcolors() {
+        let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+	<key>Ansi 1 Color</key>
+	<dict>
+		<key>Color Space</key>
+		<string>sRGB</string>
+		<key>Blue Component</key>
+		<real>0.0</real>
+		<key>Green Component</key>
+		<real>0.0</real>
+		<key>Red Component</key>
+		<real>1</real>
+	</dict>
+	<key>Background Color</key>
+	<dict>
+		<key>Blue Component</key>
+		<real>0.2</real>
+		<key>Green Component</key>
+		<real>0.2</real>
+		<key>Red Component</key>
+		<real>0.2</real>
+	</dict>
+</dict>
+</plist>"#;
+        let scheme = parse_itermcolors(text);
+        assert_eq!(scheme.ansi[1], Some(Color::new([1.0, 0.0, 0.0, 1.0])));
+        assert_eq!(scheme.background, Some(Color::new([0.2, 0.2, 0.2, 1.0])));
+        assert_eq!(scheme.foreground, None);
+    }
+
+    #[test]
+    fn imports_alacritty_toml() {
+        let text = r##"
+[colors.primary]
+background = "#1d1f21"
+foreground = "0xc5c8c6"
+
+[colors.cursor]
+cursor = "CellForeground"
+
+[colors.bright]
+red = "#ff3334"
+"##;
+        let scheme = parse_alacritty(text).unwrap();
+        assert_eq!(scheme.background, parse_hex("1d1f21"));
+        assert_eq!(scheme.foreground, parse_hex("c5c8c6"));
+        assert_eq!(scheme.cursor, None);
+        assert_eq!(scheme.ansi[9], parse_hex("ff3334"));
+        assert_eq!(scheme.ansi[1], None);
+    }
+
+    #[test]
+    fn imports_base16_yaml() {
+        let text = r#"
+scheme: "Default Dark"
+author: "Chris Kempson"
+base00: "181818" # background
+base02: "383838"
+base05: "d8d8d8"
+base08: "ab4642"
+palette:
+  base0D: "#7cafc2"
+"#;
+        let scheme = parse_base16(text);
+        assert_eq!(scheme.background, parse_hex("181818"));
+        assert_eq!(scheme.foreground, parse_hex("d8d8d8"));
+        assert_eq!(scheme.selection, parse_hex("383838"));
+        assert_eq!(scheme.ansi[1], parse_hex("ab4642"));
+        assert_eq!(scheme.ansi[9], parse_hex("ab4642"));
+        assert_eq!(scheme.ansi[4], parse_hex("7cafc2"));
+        assert_eq!(scheme.ansi[15], None);
+    }
+}
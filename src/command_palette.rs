@@ -0,0 +1,204 @@
+//! Command palette — fuzzy search over the command registry.
+//!
+//! Open with the `command_palette` binding (Cmd+K on macOS). Typing filters
+//! `commands::COMMANDS` (only commands available in the current context are
+//! listed), Up/Down move the selection,
+//! Enter runs the selected command, Escape closes. While open, the palette
+//! captures all keyboard input.
+
+use vello::Scene;
+use vello::kurbo::{Affine, Rect};
+use vello::peniko::{Color, Fill, FontData};
+use winit::keyboard::{Key, NamedKey};
+
+use crate::commands::{self, CommandContext, CommandId};
+use crate::design::DesignTokens;
+use crate::keybindings::Keymap;
+
+/// Maximum number of results drawn at once.
+const MAX_VISIBLE: usize = 10;
+const QUERY_SIZE: f64 = 28.0;
+const ROW_SIZE: f64 = 20.0;
+const HINT_SIZE: f64 = 16.0;
+const COLUMN_MAX_WIDTH: f64 = 720.0;
+
+/// What the app should do after the palette handled a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteOutcome {
+    /// Key consumed; palette stays open.
+    Consumed,
+    /// Palette closed without running anything.
+    Closed,
+    /// Palette closed; run this command.
+    Run(CommandId),
+}
+
+pub struct CommandPalette {
+    active: bool,
+    query: String,
+    selected: usize,
+    matches: Vec<CommandId>,
+}
+
+impl CommandPalette {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            query: String::new(),
+            selected: 0,
+            matches: Vec::new(),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Open with an empty query, listing every available command.
+    pub fn open(&mut self, ctx: &CommandContext) {
+        self.active = true;
+        self.query.clear();
+        self.refresh(ctx);
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    fn refresh(&mut self, ctx: &CommandContext) {
+        self.matches = commands::search(&self.query, ctx);
+        self.selected = 0;
+    }
+
+    /// Handle a key press while the palette is open.
+    pub fn handle_key(&mut self, key: &Key, ctx: &CommandContext) -> PaletteOutcome {
+        match key {
+            Key::Named(NamedKey::Escape) => {
+                self.close();
+                PaletteOutcome::Closed
+            }
+            Key::Named(NamedKey::Enter) => {
+                let chosen = self.matches.get(self.selected).copied();
+                self.close();
+                match chosen {
+                    Some(id) => PaletteOutcome::Run(id),
+                    None => PaletteOutcome::Closed,
+                }
+            }
+            Key::Named(NamedKey::ArrowDown) | Key::Named(NamedKey::Tab) => {
+                if !self.matches.is_empty() {
+                    self.selected = (self.selected + 1) % self.matches.len();
+                }
+                PaletteOutcome::Consumed
+            }
+            Key::Named(NamedKey::ArrowUp) => {
+                if !self.matches.is_empty() {
+                    self.selected = (self.selected + self.matches.len() - 1) % self.matches.len();
+                }
+                PaletteOutcome::Consumed
+            }
+            Key::Named(NamedKey::Backspace) => {
+                self.query.pop();
+                self.refresh(ctx);
+                PaletteOutcome::Consumed
+            }
+            Key::Named(NamedKey::Space) => {
+                self.query.push(' ');
+                self.refresh(ctx);
+                PaletteOutcome::Consumed
+            }
+            Key::Character(c) => {
+                self.query.push_str(c.as_str());
+                self.refresh(ctx);
+                PaletteOutcome::Consumed
+            }
+            _ => PaletteOutcome::Consumed,
+        }
+    }
+
+    /// Draw the palette over whatever screen is showing.
+    ///
+    /// A bisque wash, the query in large type, and the matching commands
+    /// below it with their keybindings right-aligned. The selected row is
+    /// drawn in primary ink with a hairline beneath; the rest are secondary.
+    pub fn render(
+        &self,
+        scene: &mut Scene,
+        width: f64,
+        height: f64,
+        font_data: Option<&FontData>,
+        tokens: &DesignTokens,
+        keymap: &Keymap,
+    ) {
+        if !self.active {
+            return;
+        }
+
+        let wash = Color::new([
+            tokens.background.r as f32,
+            tokens.background.g as f32,
+            tokens.background.b as f32,
+            0.95,
+        ]);
+        scene.fill(Fill::NonZero, Affine::IDENTITY, wash, None, &Rect::new(0.0, 0.0, width, height));
+
+        let primary = tokens.ink_color(tokens.ink.primary);
+        let secondary = tokens.ink_color(tokens.ink.secondary);
+        let annotation = tokens.ink_color(tokens.ink.annotation);
+        let rule = tokens.ink_color(tokens.ink.rule);
+
+        let col_w = (width - 96.0).min(COLUMN_MAX_WIDTH);
+        let x = (width - col_w) / 2.0;
+        let mut y = height * 0.22;
+
+        // Query line (placeholder when empty) with a thin caret.
+        let (query_text, query_color) = if self.query.is_empty() {
+            ("Type a command", annotation)
+        } else {
+            (self.query.as_str(), primary)
+        };
+        crate::dashboard::draw_text_pub(scene, x, y, query_text, query_color, QUERY_SIZE, font_data);
+        let caret_x = if self.query.is_empty() {
+            x
+        } else {
+            x + self.query.chars().count() as f64 * QUERY_SIZE * 0.52
+        };
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            primary,
+            None,
+            &Rect::new(caret_x, y - QUERY_SIZE * 0.8, caret_x + 1.5, y + QUERY_SIZE * 0.2),
+        );
+        y += 14.0;
+        scene.fill(Fill::NonZero, Affine::IDENTITY, rule, None, &Rect::new(x, y, x + col_w, y + 0.5));
+        y += ROW_SIZE * 1.8;
+
+        if self.matches.is_empty() {
+            crate::dashboard::draw_text_pub(scene, x, y, "No matching commands", annotation, ROW_SIZE, font_data);
+            return;
+        }
+
+        // Keep the selection in view when the list is longer than MAX_VISIBLE.
+        let first = self.selected.saturating_sub(MAX_VISIBLE - 1);
+        for (i, id) in self.matches.iter().enumerate().skip(first).take(MAX_VISIBLE) {
+            let spec = commands::spec(*id);
+            let is_selected = i == self.selected;
+            let color = if is_selected { primary } else { secondary };
+            crate::dashboard::draw_text_pub(scene, x, y, spec.name, color, ROW_SIZE, font_data);
+            if let Some(binding) = keymap.chord_for(*id) {
+                let bw = binding.len() as f64 * HINT_SIZE * 0.6;
+                crate::dashboard::draw_text_pub(scene, x + col_w - bw, y, &binding, annotation, HINT_SIZE, font_data);
+            }
+            if is_selected {
+                let ry = y + 6.0;
+                scene.fill(Fill::NonZero, Affine::IDENTITY, primary, None, &Rect::new(x, ry, x + col_w, ry + 1.0));
+            }
+            y += ROW_SIZE * 1.6;
+        }
+        if self.matches.len() > first + MAX_VISIBLE {
+            let more = format!("+{} more", self.matches.len() - first - MAX_VISIBLE);
+            crate::dashboard::draw_text_pub(scene, x, y, &more, annotation, HINT_SIZE, font_data);
+        }
+    }
+}
@@ -0,0 +1,327 @@
+//! Central command registry.
+//!
+//! Every user-facing action is a `CommandId`. The registry (`COMMANDS`) gives
+//! each one a display name, a snake_case action name (as used in
+//! `keybindings.toml`), and an availability predicate evaluated against a
+//! `CommandContext` snapshot of the app. The command palette searches this
+//! table, `keybindings::Keymap` maps chords onto it, and `App::run_command`
+//! in `main.rs` is the single place where commands are executed, whichever
+//! way they were triggered.
+//!
+//! Push-to-talk (hold Shift+Enter) is not a command: it reacts to both press
+//! and release, so it stays a dedicated input path.
+
+use crate::ScreenIndex;
+
+/// Identifier for every action the app can perform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandId {
+    CommandPalette,
+    NextScreen,
+    PrevScreen,
+    ShowDashboard,
+    ShowInfo,
+    ShowTerminal,
+    SplitVertical,
+    SplitHorizontal,
+    SplitVerticalWith,
+    SplitHorizontalWith,
+    OpenProject,
+    ClosePane,
+    FocusNextPane,
+    FocusPrevPane,
+    FocusPaneLeft,
+    FocusPaneRight,
+    FocusPaneUp,
+    FocusPaneDown,
+    SwapPaneNext,
+    SwapPanePrev,
+    RotatePanes,
+    GrowPaneWidth,
+    ShrinkPaneWidth,
+    GrowPaneHeight,
+    ShrinkPaneHeight,
+    EqualizePanes,
+    ToggleZoomPane,
+    ToggleSyncInput,
+    ToggleSyncMark,
+    NewWorkspace,
+    NewClaudeWorkspace,
+    CloseWorkspace,
+    RenameWorkspace,
+    NextWorkspace,
+    PrevWorkspace,
+    MoveWorkspaceLeft,
+    MoveWorkspaceRight,
+    MovePaneToNextWorkspace,
+    IncreaseFontSize,
+    DecreaseFontSize,
+    ResetFontSize,
+    ToggleVoice,
+    ToggleDesignRepl,
+    ReconnectEndpoints,
+    TakeScreenshot,
+    CopySelection,
+    Paste,
+    Find,
+    FindNext,
+    FindPrevious,
+    ToggleRecording,
+    JumpToPreviousCommand,
+    JumpToNextCommand,
+    SelectLastOutput,
+    ScrollPageUp,
+    ScrollPageDown,
+    Quit,
+}
+
+/// Snapshot of the app state that availability predicates look at.
+#[derive(Debug, Clone, Copy)]
+pub struct CommandContext {
+    pub screen: ScreenIndex,
+    /// First-run URL entry is showing (no dashboard yet).
+    pub setup_mode: bool,
+    /// Number of terminal panes (0 when every pane has been closed).
+    pub pane_count: usize,
+    /// Number of terminal workspaces (always at least one).
+    pub workspace_count: usize,
+}
+
+/// Registry entry for one command.
+pub struct CommandSpec {
+    pub id: CommandId,
+    pub name: &'static str,
+    /// Identifier used in `keybindings.toml`.
+    pub action: &'static str,
+    pub available: fn(&CommandContext) -> bool,
+}
+
+fn always(_: &CommandContext) -> bool {
+    true
+}
+
+fn not_setup(ctx: &CommandContext) -> bool {
+    !ctx.setup_mode
+}
+
+fn on_terminal(ctx: &CommandContext) -> bool {
+    !ctx.setup_mode && ctx.screen == ScreenIndex::Terminal
+}
+
+fn has_panes(ctx: &CommandContext) -> bool {
+    on_terminal(ctx) && ctx.pane_count > 0
+}
+
+fn has_several_panes(ctx: &CommandContext) -> bool {
+    on_terminal(ctx) && ctx.pane_count > 1
+}
+
+fn has_several_workspaces(ctx: &CommandContext) -> bool {
+    on_terminal(ctx) && ctx.workspace_count > 1
+}
+
+fn on_dashboard(ctx: &CommandContext) -> bool {
+    !ctx.setup_mode && ctx.screen == ScreenIndex::Dashboard
+}
+
+fn scrollable(ctx: &CommandContext) -> bool {
+    !ctx.setup_mode && ctx.screen != ScreenIndex::Terminal
+}
+
+/// All commands, in the order the palette lists them for an empty query.
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec { id: CommandId::CommandPalette, name: "Show Command Palette", action: "command_palette", available: always },
+    CommandSpec { id: CommandId::ShowDashboard, name: "Go to Dashboard", action: "show_dashboard", available: not_setup },
+    CommandSpec { id: CommandId::ShowInfo, name: "Go to Memory & Agents", action: "show_info", available: not_setup },
+    CommandSpec { id: CommandId::ShowTerminal, name: "Go to Terminal", action: "show_terminal", available: not_setup },
+    CommandSpec { id: CommandId::NextScreen, name: "Next Screen", action: "next_screen", available: not_setup },
+    CommandSpec { id: CommandId::PrevScreen, name: "Previous Screen", action: "prev_screen", available: not_setup },
+    CommandSpec { id: CommandId::SplitVertical, name: "Split Pane Vertically", action: "split_vertical", available: has_panes },
+    CommandSpec { id: CommandId::SplitHorizontal, name: "Split Pane Horizontally", action: "split_horizontal", available: has_panes },
+    CommandSpec { id: CommandId::SplitVerticalWith, name: "Split Vertically With…", action: "split_vertical_with", available: has_panes },
+    CommandSpec { id: CommandId::SplitHorizontalWith, name: "Split Horizontally With…", action: "split_horizontal_with", available: has_panes },
+    CommandSpec { id: CommandId::OpenProject, name: "New Pane for Project…", action: "open_project", available: has_panes },
+    CommandSpec { id: CommandId::ClosePane, name: "Close Pane", action: "close_pane", available: has_panes },
+    CommandSpec { id: CommandId::FocusNextPane, name: "Focus Next Pane", action: "focus_next_pane", available: has_several_panes },
+    CommandSpec { id: CommandId::FocusPrevPane, name: "Focus Previous Pane", action: "focus_prev_pane", available: has_several_panes },
+    CommandSpec { id: CommandId::FocusPaneLeft, name: "Focus Pane on the Left", action: "focus_pane_left", available: has_several_panes },
+    CommandSpec { id: CommandId::FocusPaneRight, name: "Focus Pane on the Right", action: "focus_pane_right", available: has_several_panes },
+    CommandSpec { id: CommandId::FocusPaneUp, name: "Focus Pane Above", action: "focus_pane_up", available: has_several_panes },
+    CommandSpec { id: CommandId::FocusPaneDown, name: "Focus Pane Below", action: "focus_pane_down", available: has_several_panes },
+    CommandSpec { id: CommandId::SwapPaneNext, name: "Swap Pane with Next", action: "swap_pane_next", available: has_several_panes },
+    CommandSpec { id: CommandId::SwapPanePrev, name: "Swap Pane with Previous", action: "swap_pane_prev", available: has_several_panes },
+    CommandSpec { id: CommandId::RotatePanes, name: "Rotate Panes", action: "rotate_panes", available: has_several_panes },
+    CommandSpec { id: CommandId::GrowPaneWidth, name: "Make Pane Wider", action: "grow_pane_width", available: has_several_panes },
+    CommandSpec { id: CommandId::ShrinkPaneWidth, name: "Make Pane Narrower", action: "shrink_pane_width", available: has_several_panes },
+    CommandSpec { id: CommandId::GrowPaneHeight, name: "Make Pane Taller", action: "grow_pane_height", available: has_several_panes },
+    CommandSpec { id: CommandId::ShrinkPaneHeight, name: "Make Pane Shorter", action: "shrink_pane_height", available: has_several_panes },
+    CommandSpec { id: CommandId::EqualizePanes, name: "Equalize Pane Sizes", action: "equalize_panes", available: has_several_panes },
+    CommandSpec { id: CommandId::ToggleZoomPane, name: "Zoom Pane", action: "toggle_zoom_pane", available: has_several_panes },
+    CommandSpec { id: CommandId::ToggleSyncInput, name: "Synchronize Input to All Panes", action: "toggle_sync_input", available: has_several_panes },
+    CommandSpec { id: CommandId::ToggleSyncMark, name: "Mark Pane for Synchronized Input", action: "toggle_sync_mark", available: has_several_panes },
+    CommandSpec { id: CommandId::NewWorkspace, name: "New Workspace", action: "new_workspace", available: on_terminal },
+    CommandSpec { id: CommandId::NewClaudeWorkspace, name: "New Claude Workspace", action: "new_claude_workspace", available: on_terminal },
+    CommandSpec { id: CommandId::CloseWorkspace, name: "Close Workspace", action: "close_workspace", available: on_terminal },
+    CommandSpec { id: CommandId::RenameWorkspace, name: "Rename Workspace", action: "rename_workspace", available: on_terminal },
+    CommandSpec { id: CommandId::NextWorkspace, name: "Next Workspace", action: "next_workspace", available: has_several_workspaces },
+    CommandSpec { id: CommandId::PrevWorkspace, name: "Previous Workspace", action: "prev_workspace", available: has_several_workspaces },
+    CommandSpec { id: CommandId::MoveWorkspaceLeft, name: "Move Workspace Left", action: "move_workspace_left", available: has_several_workspaces },
+    CommandSpec { id: CommandId::MoveWorkspaceRight, name: "Move Workspace Right", action: "move_workspace_right", available: has_several_workspaces },
+    CommandSpec { id: CommandId::MovePaneToNextWorkspace, name: "Move Pane to Next Workspace", action: "move_pane_to_next_workspace", available: has_panes },
+    CommandSpec { id: CommandId::IncreaseFontSize, name: "Increase Text Size", action: "increase_font_size", available: always },
+    CommandSpec { id: CommandId::DecreaseFontSize, name: "Decrease Text Size", action: "decrease_font_size", available: always },
+    CommandSpec { id: CommandId::ResetFontSize, name: "Reset Text Size", action: "reset_font_size", available: always },
+    CommandSpec { id: CommandId::ToggleVoice, name: "Toggle Voice Input", action: "toggle_voice", available: on_dashboard },
+    CommandSpec { id: CommandId::ToggleDesignRepl, name: "Toggle Design REPL", action: "toggle_design_repl", available: always },
+    CommandSpec { id: CommandId::ReconnectEndpoints, name: "Reconnect Lobster Endpoints", action: "reconnect_endpoints", available: not_setup },
+    CommandSpec { id: CommandId::TakeScreenshot, name: "Take Screenshot", action: "take_screenshot", available: always },
+    CommandSpec { id: CommandId::CopySelection, name: "Copy Selection", action: "copy_selection", available: not_setup },
+    CommandSpec { id: CommandId::Paste, name: "Paste into Terminal", action: "paste", available: has_panes },
+    CommandSpec { id: CommandId::Find, name: "Find in Terminal", action: "find", available: has_panes },
+    CommandSpec { id: CommandId::FindNext, name: "Find Next (Older) Match", action: "find_next", available: has_panes },
+    CommandSpec { id: CommandId::FindPrevious, name: "Find Previous (Newer) Match", action: "find_previous", available: has_panes },
+    CommandSpec { id: CommandId::ToggleRecording, name: "Start/Stop Recording Pane", action: "toggle_recording", available: has_panes },
+    CommandSpec { id: CommandId::JumpToPreviousCommand, name: "Jump to Previous Command", action: "jump_to_previous_command", available: has_panes },
+    CommandSpec { id: CommandId::JumpToNextCommand, name: "Jump to Next Command", action: "jump_to_next_command", available: has_panes },
+    CommandSpec { id: CommandId::SelectLastOutput, name: "Select Last Command Output", action: "select_last_output", available: has_panes },
+    CommandSpec { id: CommandId::ScrollPageUp, name: "Scroll Page Up", action: "scroll_page_up", available: scrollable },
+    CommandSpec { id: CommandId::ScrollPageDown, name: "Scroll Page Down", action: "scroll_page_down", available: scrollable },
+    CommandSpec { id: CommandId::Quit, name: "Quit", action: "quit", available: always },
+];
+
+/// Look up the registry entry for `id`.
+pub fn spec(id: CommandId) -> &'static CommandSpec {
+    COMMANDS
+        .iter()
+        .find(|c| c.id == id)
+        .expect("every CommandId has a registry entry")
+}
+
+/// Look up a command by its `action` name.
+pub fn from_action(action: &str) -> Option<CommandId> {
+    COMMANDS.iter().find(|c| c.action == action).map(|c| c.id)
+}
+
+/// Whether `id` can run in the given context.
+pub fn is_available(id: CommandId, ctx: &CommandContext) -> bool {
+    (spec(id).available)(ctx)
+}
+
+// ---------------------------------------------------------------------------
+// Fuzzy matching
+// ---------------------------------------------------------------------------
+
+/// Score `candidate` against `query` as a case-insensitive subsequence match.
+///
+/// Returns `None` if the query characters do not all appear in order.
+/// Higher scores are better: consecutive runs and matches at word starts
+/// are rewarded, gaps and long candidates cost a little.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let cand: Vec<char> = candidate.chars().collect();
+
+    let mut score = 0i32;
+    let mut qi = 0;
+    let mut prev_match: Option<usize> = None;
+    for (ci, ch) in cand.iter().enumerate() {
+        if qi == query.len() {
+            break;
+        }
+        if ch.to_lowercase().eq(std::iter::once(query[qi])) {
+            let word_start = ci == 0 || !cand[ci - 1].is_alphanumeric();
+            score += 1;
+            if word_start {
+                score += 8;
+            }
+            match prev_match {
+                Some(p) if p + 1 == ci => score += 5,
+                Some(p) => score -= (ci - p - 1).min(5) as i32,
+                None => score -= ci.min(5) as i32,
+            }
+            prev_match = Some(ci);
+            qi += 1;
+        }
+    }
+    if qi < query.len() {
+        return None;
+    }
+    Some(score - (cand.len() as i32 / 8))
+}
+
+/// Available commands matching `query`, best first.
+///
+/// An empty query lists every available command in registry order.
+/// The palette command itself is never listed.
+pub fn search(query: &str, ctx: &CommandContext) -> Vec<CommandId> {
+    let mut scored: Vec<(i32, usize, CommandId)> = COMMANDS
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.id != CommandId::CommandPalette && (c.available)(ctx))
+        .filter_map(|(i, c)| fuzzy_score(query, c.name).map(|s| (s, i, c.id)))
+        .collect();
+    // Stable on registry order for equal scores.
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    scored.into_iter().map(|(_, _, id)| id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx(screen: ScreenIndex, pane_count: usize) -> CommandContext {
+        CommandContext { screen, setup_mode: false, pane_count, workspace_count: 1 }
+    }
+
+    #[test]
+    fn every_command_is_registered_once() {
+        for (i, a) in COMMANDS.iter().enumerate() {
+            for b in &COMMANDS[i + 1..] {
+                assert_ne!(a.id, b.id, "{} registered twice", a.name);
+                assert_ne!(a.action, b.action, "action `{}` used twice", a.action);
+            }
+        }
+        // spec() must not panic for any id reachable from the table.
+        for c in COMMANDS {
+            assert_eq!(spec(c.id).name, c.name);
+            assert_eq!(from_action(c.action), Some(c.id));
+        }
+    }
+
+    #[test]
+    fn fuzzy_requires_ordered_subsequence() {
+        assert!(fuzzy_score("spv", "Split Pane Vertically").is_some());
+        assert!(fuzzy_score("vps", "Split Pane Vertically").is_none());
+        assert_eq!(fuzzy_score("", "Anything"), Some(0));
+    }
+
+    #[test]
+    fn fuzzy_prefers_word_starts_and_runs() {
+        let word_starts = fuzzy_score("tv", "Toggle Voice Input").unwrap();
+        let scattered = fuzzy_score("tv", "Next Screen Vertically").unwrap();
+        assert!(word_starts > scattered);
+        let run = fuzzy_score("split", "Split Pane Vertically").unwrap();
+        let spread = fuzzy_score("split", "Show Previous Line Item Text").unwrap_or(i32::MIN);
+        assert!(run > spread);
+    }
+
+    #[test]
+    fn search_respects_availability() {
+        let dash = ctx(ScreenIndex::Dashboard, 1);
+        let results = search("split", &dash);
+        assert!(!results.contains(&CommandId::SplitVertical));
+
+        let term = ctx(ScreenIndex::Terminal, 1);
+        let results = search("split", &term);
+        assert_eq!(results.first(), Some(&CommandId::SplitVertical));
+        assert!(!search("", &term).contains(&CommandId::FocusNextPane));
+    }
+
+    #[test]
+    fn search_never_lists_the_palette_itself() {
+        let dash = ctx(ScreenIndex::Dashboard, 1);
+        assert!(!search("palette", &dash).contains(&CommandId::CommandPalette));
+    }
+}
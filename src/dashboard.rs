@@ -13,7 +13,9 @@ use vello::peniko::{Color, Fill, FontData};
 use vello::{Glyph, Scene};
 
 use crate::design::DesignTokens;
+use crate::detail_overlay::DetailTarget;
 use crate::protocol::{ConnectionStatus, LobsterInstance};
+use crate::scroll::{self, ScrollState};
 use crate::voice::VoiceUiState;
 use crate::ws_client::SharedInstances;
 
@@ -40,6 +42,10 @@ const LEFT_MARGIN: f64 = 48.0;
 const SECTION_SPACING: f64 = 32.0;
 const LINE_HEIGHT_FACTOR: f64 = 1.4;
 const RULE_THICKNESS: f64 = 0.5;
+const TITLE_Y: f64 = 56.0;
+/// Top of the scrollable content area (just below the title row).
+const CONTENT_TOP: f64 = TITLE_Y + 32.0;
+const PANEL_COL_GAP: f64 = 48.0;
 
 // --- Splash quote from James Joyce's Ulysses ---
 
@@ -68,6 +74,10 @@ const TOTAL_SPLASH_DURATION: f64 = FADE_IN_DURATION + HOLD_DURATION + FADE_OUT_D
 /// `voice_ui` carries the current push-to-talk state so the renderer can
 /// show a recording indicator, "Transcribing..." overlay, and the result text.
 /// `voice_enabled` controls whether the Shift+Enter hint is shown in the header.
+/// The title row stays pinned while the instance panels scroll beneath it;
+/// `scroll` receives the laid-out content height each frame. `rows` is
+/// refilled with the on-screen rectangle of every clickable row (for hover
+/// and drill-down hit testing).
 pub fn render_dashboard(
     scene: &mut Scene,
     width: f64,
@@ -78,7 +88,11 @@ pub fn render_dashboard(
     voice_ui: &VoiceUiState,
     voice_enabled: bool,
     tokens: &DesignTokens,
+    scroll: &mut ScrollState,
+    rows: &mut Vec<RowTarget>,
 ) {
+    rows.clear();
+
     // Background fill - bisque beige (from design tokens)
     let bg_rect = Rect::new(0.0, 0.0, width, height);
     scene.fill(Fill::NonZero, Affine::IDENTITY, tokens.bg_color(), None, &bg_rect);
@@ -100,13 +114,14 @@ pub fn render_dashboard(
 
     let instances = instances.lock().unwrap();
 
+    update_scroll(&instances, width, height, scale, scroll);
     if instances.is_empty() {
         draw_centered_text(scene, width, height, "No Lobster instances configured", TEXT_SECONDARY, 40.0 * scale, font_data);
         return;
     }
 
     // Page title — large Optima text with generous whitespace
-    let title_y = 56.0;
+    let title_y = TITLE_Y;
     draw_text_with_font(scene, LEFT_MARGIN, title_y, "Lobster Dashboard", TEXT_PRIMARY, title_size, font_data);
 
     // Connection status as secondary text next to title
@@ -135,35 +150,24 @@ pub fn render_dashboard(
         draw_text_with_font(scene, width - 300.0, title_y + 28.0, hint, hint_color, annotation_size, font_data);
     }
 
-    // Content area starts below the title
-    let content_top = title_y + 32.0;
-    let content_width = width - LEFT_MARGIN * 2.0;
-    let content_height = height - content_top - LEFT_MARGIN;
+    // Panels are drawn at unscrolled coordinates into their own scene and
+    // composited through a clip covering everything below the title row.
+    let (panels, _) = panel_layout(&instances, width, scale);
+    let mut content = Scene::new();
+    for (idx, (instance, &(x, y, w))) in instances.iter().zip(panels.iter()).enumerate() {
+        draw_instance_panel(&mut content, x, y, w, idx, instance, font_data, scale, rows);
+    }
 
-    let num_instances = instances.len();
+    let viewport = content_viewport(width, height);
+    scroll::append_scrolled(scene, &content, &viewport, scroll);
+    scroll::draw_overflow_indicator(scene, &viewport, scroll);
 
-    if num_instances == 1 {
-        // Single instance: full width
-        draw_instance_panel(scene, LEFT_MARGIN, content_top, content_width, content_height, &instances[0], font_data, scale);
-    } else {
-        // Multiple instances: vertical flow or two-column
-        let cols = if num_instances <= 2 { num_instances } else if num_instances <= 4 { 2 } else { 3 };
-        let rows = (num_instances + cols - 1) / cols;
-        let col_gap = 48.0;
-        let row_gap = SECTION_SPACING;
-
-        let panel_width = (content_width - (cols as f64 - 1.0) * col_gap) / cols as f64;
-        let panel_height = (content_height - (rows as f64 - 1.0) * row_gap) / rows as f64;
-
-        for (idx, instance) in instances.iter().enumerate() {
-            let col = idx % cols;
-            let row = idx / cols;
-            let x = LEFT_MARGIN + col as f64 * (panel_width + col_gap);
-            let y = content_top + row as f64 * (panel_height + row_gap);
-
-            draw_instance_panel(scene, x, y, panel_width, panel_height, instance, font_data, scale);
-        }
-    }
+    // Row rectangles were recorded in content coordinates; move them on
+    // screen and drop the ones scrolled out of the viewport.
+    rows.retain_mut(|row| {
+        row.rect = row.rect + vello::kurbo::Vec2::new(0.0, -scroll.offset);
+        row.rect.y1 > viewport.y0 && row.rect.y0 < viewport.y1
+    });
 
     // Voice input overlay — drawn on top of everything else
     drop(instances);
@@ -277,25 +281,63 @@ fn draw_voice_indicator(
     }
 }
 
-/// Draw a single Lobster instance using typography-first layout.
+/// Screen area the instance panels scroll within: everything below the
+/// title row. Highlights drawn over the panels are clipped to it as well.
+pub fn content_viewport(width: f64, height: f64) -> Rect {
+    Rect::new(0.0, CONTENT_TOP, width, height)
+}
+
+/// Report the instance panels' laid-out height to `scroll`, clamping its
+/// offset. Done before anything is placed at that offset, so the panels
+/// and the selection regions over them agree on it.
+pub fn update_scroll(instances: &[LobsterInstance], width: f64, height: f64, scale: f64, scroll: &mut ScrollState) {
+    if instances.is_empty() {
+        scroll.set_extents(0.0, height);
+        return;
+    }
+    let (_, content_bottom) = panel_layout(instances, width, scale);
+    scroll.set_extents(content_bottom - CONTENT_TOP + LEFT_MARGIN, content_viewport(width, height).height());
+}
+
+/// A clickable dashboard row: its on-screen bounds and what it refers to.
+#[derive(Debug, Clone)]
+pub struct RowTarget {
+    pub rect: Rect,
+    pub target: DetailTarget,
+}
+
+/// Bounds of a text row whose baseline is at `baseline`, spanning the panel width.
+fn row_rect(x: f64, baseline: f64, w: f64, size: f64) -> Rect {
+    Rect::new(x, baseline - size * 0.8, x + w, baseline + size * 0.3)
+}
+
+/// Draw the hover cue for a clickable row: a hairline under the text.
+pub fn draw_row_hover(scene: &mut Scene, rect: &Rect) {
+    let rule = Rect::new(rect.x0, rect.y1, rect.x1, rect.y1 + 1.0);
+    scene.fill(Fill::NonZero, Affine::IDENTITY, TEXT_ANNOTATION, None, &rule);
+}
+
+/// Lay out a single Lobster instance using typography-first layout,
+/// returning the panel's height from its top edge to below its last line.
 ///
 /// No panels, no borders. Just text with hierarchy created through
-/// font size, opacity, and whitespace.
-fn draw_instance_panel(
-    scene: &mut Scene,
+/// font size, opacity, and whitespace. Every section is laid out; the
+/// caller clips and scrolls. Sessions, queues, agents and memory events are
+/// clickable: `pen` records their bounds when it draws.
+fn instance_panel(
+    pen: &mut PanelPen,
     x: f64,
     y: f64,
     w: f64,
-    h: f64,
+    instance_idx: usize,
     instance: &LobsterInstance,
-    font_data: Option<&FontData>,
     scale: f64,
-) {
+) -> f64 {
     let data_primary_size = DATA_PRIMARY_SIZE * scale;
     let data_secondary_size = DATA_SECONDARY_SIZE * scale;
     let section_size = SECTION_SIZE * scale;
     let annotation_size = ANNOTATION_SIZE * scale;
-    let _ = (section_size, annotation_size);
+    let _ = section_size;
     let mut cursor_y = y;
 
     // --- Hostname in large text ---
@@ -305,7 +347,7 @@ fn draw_instance_panel(
         &instance.url
     };
     cursor_y += 36.0;
-    draw_text_with_font(scene, x, cursor_y, hostname, TEXT_PRIMARY, 36.0, font_data);
+    pen.text(x, cursor_y, hostname, TEXT_PRIMARY, 36.0);
 
     // Connection status as small secondary text below
     let status_str = match &instance.status {
@@ -315,211 +357,307 @@ fn draw_instance_panel(
         ConnectionStatus::Error(e) => format!("error: {}", &e[..e.len().min(30)]),
     };
     cursor_y += data_secondary_size * LINE_HEIGHT_FACTOR;
-    draw_text_with_font(scene, x, cursor_y, &status_str, TEXT_SECONDARY, data_secondary_size, font_data);
+    pen.text(x, cursor_y, &status_str, TEXT_SECONDARY, data_secondary_size);
 
     if instance.status != ConnectionStatus::Connected {
-        return;
+        return cursor_y + annotation_size * LINE_HEIGHT_FACTOR - y;
     }
 
     let state = &instance.state;
     cursor_y += SECTION_SPACING;
 
     // --- System section ---
-    cursor_y = draw_section_header_with_font(scene, x, cursor_y, w, "System", font_data, scale);
+    cursor_y = pen.header(x, cursor_y, w, "System", scale);
 
     // Uptime
     let uptime_str = format_uptime(state.system.uptime_seconds);
-    draw_label_value_with_font(scene, x, cursor_y, "Uptime", &uptime_str, w, font_data, scale);
+    pen.label_value(x, cursor_y, "Uptime", &uptime_str, w, scale);
     cursor_y += data_primary_size * LINE_HEIGHT_FACTOR;
 
     // CPU, Memory, Disk as text-only: "CPU  42%"
     let cpu_str = format!("{:.0}%", state.system.cpu.percent);
-    draw_label_value_with_font(scene, x, cursor_y, "CPU", &cpu_str, w, font_data, scale);
+    pen.label_value(x, cursor_y, "CPU", &cpu_str, w, scale);
     cursor_y += data_primary_size * LINE_HEIGHT_FACTOR;
 
     let mem_str = format!("{:.0}%", state.system.memory.percent);
-    draw_label_value_with_font(scene, x, cursor_y, "Memory", &mem_str, w, font_data, scale);
+    pen.label_value(x, cursor_y, "Memory", &mem_str, w, scale);
     cursor_y += data_primary_size * LINE_HEIGHT_FACTOR;
 
     let disk_str = format!("{:.0}%", state.system.disk.percent);
-    draw_label_value_with_font(scene, x, cursor_y, "Disk", &disk_str, w, font_data, scale);
+    pen.label_value(x, cursor_y, "Disk", &disk_str, w, scale);
     cursor_y += SECTION_SPACING;
 
     // --- Sessions section ---
-    cursor_y = draw_section_header_with_font(scene, x, cursor_y, w, "Sessions", font_data, scale);
+    cursor_y = pen.header(x, cursor_y, w, "Sessions", scale);
 
     let claude_sessions: Vec<_> = state.sessions.iter()
         .filter(|s| s.name == "claude")
         .collect();
     let session_count = claude_sessions.len();
-    draw_label_value_with_font(scene, x, cursor_y, "Active", &format!("{}", session_count), w, font_data, scale);
+    pen.label_value(x, cursor_y, "Active", &format!("{}", session_count), w, scale);
     cursor_y += data_primary_size * LINE_HEIGHT_FACTOR;
 
     // Show each session as text
     for session in claude_sessions.iter().take(3) {
         let label = format!("PID {}", session.pid);
         let value = format!("{:.0} MB", session.memory_mb);
-        draw_text_with_font(scene, x + 16.0, cursor_y, &label, TEXT_ANNOTATION, data_secondary_size, font_data);
+        pen.text(x + 16.0, cursor_y, &label, TEXT_ANNOTATION, data_secondary_size);
         let value_width = value.len() as f64 * 12.0;
-        draw_text_with_font(scene, (x + w - value_width).max(x + 100.0), cursor_y, &value, TEXT_PRIMARY, data_secondary_size, font_data);
+        pen.text((x + w - value_width).max(x + 100.0), cursor_y, &value, TEXT_PRIMARY, data_secondary_size);
+        pen.row(RowTarget {
+            rect: row_rect(x, cursor_y, w, data_secondary_size),
+            target: DetailTarget::Session { instance: instance_idx, pid: session.pid },
+        });
         cursor_y += data_secondary_size * LINE_HEIGHT_FACTOR;
     }
     if session_count > 3 {
-        draw_text_with_font(scene, x + 16.0, cursor_y, &format!("+{} more", session_count - 3), TEXT_ANNOTATION, annotation_size, font_data);
+        pen.text(x + 16.0, cursor_y, &format!("+{} more", session_count - 3), TEXT_ANNOTATION, annotation_size);
         cursor_y += annotation_size * LINE_HEIGHT_FACTOR;
     }
     cursor_y += SECTION_SPACING - data_secondary_size * LINE_HEIGHT_FACTOR;
 
     // --- Messages section ---
-    if cursor_y + 100.0 < y + h {
-        cursor_y = draw_section_header_with_font(scene, x, cursor_y, w, "Messages", font_data, scale);
-
-        let queues = &state.message_queues;
-        let queue_items = [
-            ("Inbox", queues.inbox.count),
-            ("Processed", queues.processed.count),
-            ("Sent", queues.sent.count),
-            ("Failed", queues.failed.count),
-        ];
+    cursor_y = pen.header(x, cursor_y, w, "Messages", scale);
+
+    let queues = &state.message_queues;
+    let queue_items = [
+        ("Inbox", queues.inbox.count),
+        ("Processed", queues.processed.count),
+        ("Sent", queues.sent.count),
+        ("Failed", queues.failed.count),
+    ];
 
-        for (label, count) in &queue_items {
-            draw_label_value_with_font(scene, x, cursor_y, label, &count.to_string(), w, font_data, scale);
-            cursor_y += data_primary_size * LINE_HEIGHT_FACTOR;
-        }
-        cursor_y += SECTION_SPACING - data_primary_size * LINE_HEIGHT_FACTOR;
+    for (label, count) in &queue_items {
+        pen.label_value(x, cursor_y, label, &count.to_string(), w, scale);
+        pen.row(RowTarget {
+            rect: row_rect(x, cursor_y, w, data_primary_size),
+            target: DetailTarget::Queue { instance: instance_idx, queue: *label },
+        });
+        cursor_y += data_primary_size * LINE_HEIGHT_FACTOR;
     }
+    cursor_y += SECTION_SPACING - data_primary_size * LINE_HEIGHT_FACTOR;
 
     // --- Activity section ---
-    if cursor_y + 80.0 < y + h {
-        cursor_y = draw_section_header_with_font(scene, x, cursor_y, w, "Activity (24h)", font_data, scale);
+    cursor_y = pen.header(x, cursor_y, w, "Activity (24h)", scale);
 
-        let activity = &state.conversation_activity;
-        draw_label_value_with_font(scene, x, cursor_y, "Received", &activity.messages_received_24h.to_string(), w, font_data, scale);
-        cursor_y += data_primary_size * LINE_HEIGHT_FACTOR;
-        draw_label_value_with_font(scene, x, cursor_y, "Replied", &activity.replies_sent_24h.to_string(), w, font_data, scale);
-        cursor_y += SECTION_SPACING;
-    }
+    let activity = &state.conversation_activity;
+    pen.label_value(x, cursor_y, "Received", &activity.messages_received_24h.to_string(), w, scale);
+    cursor_y += data_primary_size * LINE_HEIGHT_FACTOR;
+    pen.label_value(x, cursor_y, "Replied", &activity.replies_sent_24h.to_string(), w, scale);
+    cursor_y += SECTION_SPACING;
 
     // --- Health section ---
-    if cursor_y + 80.0 < y + h {
-        cursor_y = draw_section_header_with_font(scene, x, cursor_y, w, "Health", font_data, scale);
-
-        let health = &state.health;
-        let hb_status = match health.heartbeat_age_seconds {
-            Some(age) if age < 300 => format!("{}s ago", age),
-            Some(age) => format!("stale ({}s)", age),
-            None => "unknown".to_string(),
-        };
-        draw_label_value_with_font(scene, x, cursor_y, "Heartbeat", &hb_status, w, font_data, scale);
-        cursor_y += data_primary_size * LINE_HEIGHT_FACTOR;
+    cursor_y = pen.header(x, cursor_y, w, "Health", scale);
 
-        let bot_status = if health.telegram_bot_running { "running" } else { "stopped" };
-        draw_label_value_with_font(scene, x, cursor_y, "Telegram Bot", bot_status, w, font_data, scale);
-        cursor_y += SECTION_SPACING;
-    }
+    let health = &state.health;
+    let hb_status = match health.heartbeat_age_seconds {
+        Some(age) if age < 300 => format!("{}s ago", age),
+        Some(age) => format!("stale ({}s)", age),
+        None => "unknown".to_string(),
+    };
+    pen.label_value(x, cursor_y, "Heartbeat", &hb_status, w, scale);
+    cursor_y += data_primary_size * LINE_HEIGHT_FACTOR;
+
+    let bot_status = if health.telegram_bot_running { "running" } else { "stopped" };
+    pen.label_value(x, cursor_y, "Telegram Bot", bot_status, w, scale);
+    cursor_y += SECTION_SPACING;
 
     // --- Agents section ---
-    if cursor_y + 60.0 < y + h {
-        cursor_y = draw_section_header_with_font(scene, x, cursor_y, w, "Agents", font_data, scale);
+    cursor_y = pen.header(x, cursor_y, w, "Agents", scale);
 
-        let agents = &state.subagent_list.agents;
-        if agents.is_empty() {
-            draw_text_with_font(scene, x, cursor_y, "No agents running", TEXT_ANNOTATION, data_secondary_size, font_data);
+    let agents = &state.subagent_list.agents;
+    if agents.is_empty() {
+        pen.text(x, cursor_y, "No agents running", TEXT_ANNOTATION, data_secondary_size);
+        cursor_y += data_secondary_size * LINE_HEIGHT_FACTOR;
+    } else {
+        for agent in agents.iter().take(4) {
+            // Description
+            let desc = if agent.description.len() > 52 {
+                format!("{}...", &agent.description[..52])
+            } else {
+                agent.description.clone()
+            };
+            pen.text(x, cursor_y, &desc, TEXT_PRIMARY, data_secondary_size);
+
+            // Elapsed time and stats on the right
+            let elapsed_str = match agent.elapsed_seconds {
+                Some(s) if s < 60 => format!("{}s", s),
+                Some(s) if s < 3600 => format!("{}m", s / 60),
+                Some(s) => format!("{}h{}m", s / 3600, (s % 3600) / 60),
+                None => "?".to_string(),
+            };
+            let turns_str = agent.runtime.as_ref().map_or("".to_string(), |r| {
+                format!("{} turns/{} tools", r.turns, r.tool_uses)
+            });
+            let stats_str = if turns_str.is_empty() {
+                elapsed_str
+            } else {
+                format!("{} | {}", elapsed_str, turns_str)
+            };
+            let stats_w = stats_str.len() as f64 * 10.0;
+            pen.text((x + w - stats_w).max(x + 20.0), cursor_y, &stats_str, TEXT_ANNOTATION, annotation_size);
+            pen.row(RowTarget {
+                rect: row_rect(x, cursor_y, w, data_secondary_size),
+                target: DetailTarget::Agent { instance: instance_idx, id: agent.id.clone() },
+            });
             cursor_y += data_secondary_size * LINE_HEIGHT_FACTOR;
-        } else {
-            for agent in agents.iter().take(4) {
-                if cursor_y + data_secondary_size * LINE_HEIGHT_FACTOR > y + h {
-                    break;
-                }
-                // Description
-                let desc = if agent.description.len() > 52 {
-                    format!("{}...", &agent.description[..52])
-                } else {
-                    agent.description.clone()
-                };
-                draw_text_with_font(scene, x, cursor_y, &desc, TEXT_PRIMARY, data_secondary_size, font_data);
-
-                // Elapsed time and stats on the right
-                let elapsed_str = match agent.elapsed_seconds {
-                    Some(s) if s < 60 => format!("{}s", s),
-                    Some(s) if s < 3600 => format!("{}m", s / 60),
-                    Some(s) => format!("{}h{}m", s / 3600, (s % 3600) / 60),
-                    None => "?".to_string(),
-                };
-                let turns_str = agent.runtime.as_ref().map_or("".to_string(), |r| {
-                    format!("{} turns/{} tools", r.turns, r.tool_uses)
-                });
-                let stats_str = if turns_str.is_empty() {
-                    elapsed_str
-                } else {
-                    format!("{} | {}", elapsed_str, turns_str)
-                };
-                let stats_w = stats_str.len() as f64 * 10.0;
-                draw_text_with_font(scene, (x + w - stats_w).max(x + 20.0), cursor_y, &stats_str, TEXT_ANNOTATION, annotation_size, font_data);
-                cursor_y += data_secondary_size * LINE_HEIGHT_FACTOR;
-            }
-            if agents.len() > 4 {
-                draw_text_with_font(scene, x, cursor_y, &format!("+{} more", agents.len() - 4), TEXT_ANNOTATION, annotation_size, font_data);
-                cursor_y += annotation_size * LINE_HEIGHT_FACTOR;
-            }
         }
-        cursor_y += SECTION_SPACING - data_secondary_size * LINE_HEIGHT_FACTOR;
+        if agents.len() > 4 {
+            pen.text(x, cursor_y, &format!("+{} more", agents.len() - 4), TEXT_ANNOTATION, annotation_size);
+            cursor_y += annotation_size * LINE_HEIGHT_FACTOR;
+        }
     }
+    cursor_y += SECTION_SPACING - data_secondary_size * LINE_HEIGHT_FACTOR;
 
     // --- Memory section ---
-    if cursor_y + 60.0 < y + h {
-        cursor_y = draw_section_header_with_font(scene, x, cursor_y, w, "Memory", font_data, scale);
+    cursor_y = pen.header(x, cursor_y, w, "Memory", scale);
+
+    let mem = &state.memory;
+    pen.label_value(x, cursor_y, "Events", &mem.total_events.to_string(), w, scale);
+    cursor_y += data_primary_size * LINE_HEIGHT_FACTOR;
 
-        let mem = &state.memory;
-        draw_label_value_with_font(scene, x, cursor_y, "Events", &mem.total_events.to_string(), w, font_data, scale);
+    // Projects list
+    if !mem.projects.is_empty() {
+        let projects_str = if mem.projects.len() > 4 {
+            format!("{} (+{})", mem.projects[..4].join(", "), mem.projects.len() - 4)
+        } else {
+            mem.projects.join(", ")
+        };
+        pen.label_value(x, cursor_y, "Projects", &projects_str, w, scale);
         cursor_y += data_primary_size * LINE_HEIGHT_FACTOR;
+    }
 
-        // Projects list
-        if !mem.projects.is_empty() && cursor_y + data_secondary_size * LINE_HEIGHT_FACTOR < y + h {
-            let projects_str = if mem.projects.len() > 4 {
-                format!("{} (+{})", mem.projects[..4].join(", "), mem.projects.len() - 4)
-            } else {
-                mem.projects.join(", ")
-            };
-            draw_label_value_with_font(scene, x, cursor_y, "Projects", &projects_str, w, font_data, scale);
-            cursor_y += data_primary_size * LINE_HEIGHT_FACTOR;
+    // Recent events
+    for event in mem.recent_events.iter().take(4) {
+        let type_tag = match event.event_type.as_str() {
+            "decision" => "[D]",
+            "note" => "[N]",
+            "link" => "[L]",
+            "task" => "[T]",
+            _ => "[?]",
+        };
+        let content_max = 50usize;
+        let snippet = if event.content.len() > content_max {
+            format!("{}...", &event.content[..content_max])
+        } else {
+            event.content.clone()
+        };
+        let line = format!("{} {}", type_tag, snippet);
+        pen.text(x, cursor_y, &line, TEXT_ANNOTATION, data_secondary_size);
+        pen.row(RowTarget {
+            rect: row_rect(x, cursor_y, w, data_secondary_size),
+            target: DetailTarget::MemoryEvent {
+                instance: instance_idx,
+                id: event.id,
+                timestamp: event.timestamp.clone(),
+            },
+        });
+        cursor_y += data_secondary_size * LINE_HEIGHT_FACTOR;
+    }
+
+    // Last consolidation
+    let consol_str = match &mem.consolidations.last_consolidation_at {
+        Some(ts) => {
+            let short = if ts.len() > 16 { &ts[..16] } else { ts.as_str() };
+            format!("Last consolidation: {}", short)
         }
+        None => "Last consolidation: never".to_string(),
+    };
+    pen.text(x, cursor_y, &consol_str, TEXT_ANNOTATION, annotation_size);
+    cursor_y + annotation_size * LINE_HEIGHT_FACTOR - y
+}
 
-        // Recent events
-        for event in mem.recent_events.iter().take(4) {
-            if cursor_y + data_secondary_size * LINE_HEIGHT_FACTOR > y + h {
-                break;
-            }
-            let type_tag = match event.event_type.as_str() {
-                "decision" => "[D]",
-                "note" => "[N]",
-                "link" => "[L]",
-                "task" => "[T]",
-                _ => "[?]",
-            };
-            let content_max = 50usize;
-            let snippet = if event.content.len() > content_max {
-                format!("{}...", &event.content[..content_max])
-            } else {
-                event.content.clone()
-            };
-            let line = format!("{} {}", type_tag, snippet);
-            draw_text_with_font(scene, x, cursor_y, &line, TEXT_ANNOTATION, data_secondary_size, font_data);
-            cursor_y += data_secondary_size * LINE_HEIGHT_FACTOR;
+/// Draw a single instance panel into `scene`, appending its clickable rows
+/// to `rows`.
+fn draw_instance_panel(
+    scene: &mut Scene,
+    x: f64,
+    y: f64,
+    w: f64,
+    instance_idx: usize,
+    instance: &LobsterInstance,
+    font_data: Option<&FontData>,
+    scale: f64,
+    rows: &mut Vec<RowTarget>,
+) {
+    let mut pen = PanelPen { canvas: Some((scene, rows)), font_data };
+    instance_panel(&mut pen, x, y, w, instance_idx, instance, scale);
+}
+
+/// Height of the panel `draw_instance_panel` produces for `instance`: the
+/// same layout, run without drawing (grid rows, scroll extents, selectable
+/// regions).
+fn instance_panel_height(instance: &LobsterInstance, scale: f64) -> f64 {
+    let mut pen = PanelPen { canvas: None, font_data: None };
+    instance_panel(&mut pen, 0.0, 0.0, 0.0, 0, instance, scale)
+}
+
+/// Where `instance_panel` puts its text and clickable rows. Without a
+/// canvas it only advances through the layout, so measuring a panel and
+/// drawing it cannot disagree.
+struct PanelPen<'a> {
+    canvas: Option<(&'a mut Scene, &'a mut Vec<RowTarget>)>,
+    font_data: Option<&'a FontData>,
+}
+
+impl PanelPen<'_> {
+    fn text(&mut self, x: f64, y: f64, text: &str, color: Color, size: f64) {
+        if let Some((scene, _)) = self.canvas.as_mut() {
+            draw_text_with_font(scene, x, y, text, color, size, self.font_data);
         }
+    }
 
-        // Last consolidation
-        if cursor_y + data_secondary_size * LINE_HEIGHT_FACTOR < y + h {
-            let consol_str = match &mem.consolidations.last_consolidation_at {
-                Some(ts) => {
-                    let short = if ts.len() > 16 { &ts[..16] } else { ts.as_str() };
-                    format!("Last consolidation: {}", short)
-                }
-                None => "Last consolidation: never".to_string(),
-            };
-            draw_text_with_font(scene, x, cursor_y, &consol_str, TEXT_ANNOTATION, annotation_size, font_data);
+    fn label_value(&mut self, x: f64, y: f64, label: &str, value: &str, w: f64, scale: f64) {
+        if let Some((scene, _)) = self.canvas.as_mut() {
+            draw_label_value_with_font(scene, x, y, label, value, w, self.font_data, scale);
+        }
+    }
+
+    /// Returns the y position below the header, like
+    /// `draw_section_header_with_font`.
+    fn header(&mut self, x: f64, y: f64, w: f64, title: &str, scale: f64) -> f64 {
+        if let Some((scene, _)) = self.canvas.as_mut() {
+            draw_section_header_with_font(scene, x, y, w, title, self.font_data, scale);
+        }
+        y + section_header_height(scale)
+    }
+
+    fn row(&mut self, row: RowTarget) {
+        if let Some((_, rows)) = self.canvas.as_mut() {
+            rows.push(row);
+        }
+    }
+}
+
+/// Position every instance panel in unscrolled content coordinates.
+///
+/// Returns `(x, y, width)` per instance plus the y coordinate of the bottom of
+/// the lowest panel. A single instance gets the full width; several are
+/// arranged in a grid whose rows are as tall as their tallest panel.
+fn panel_layout(instances: &[LobsterInstance], width: f64, scale: f64) -> (Vec<(f64, f64, f64)>, f64) {
+    let content_width = width - LEFT_MARGIN * 2.0;
+    let num_instances = instances.len();
+    if num_instances == 0 {
+        return (Vec::new(), CONTENT_TOP);
+    }
+
+    let cols = if num_instances <= 2 { num_instances } else if num_instances <= 4 { 2 } else { 3 };
+    let panel_width = (content_width - (cols as f64 - 1.0) * PANEL_COL_GAP) / cols as f64;
+
+    let mut positions = Vec::with_capacity(num_instances);
+    let mut row_top = CONTENT_TOP;
+    let mut bottom = CONTENT_TOP;
+    for (row, chunk) in instances.chunks(cols).enumerate() {
+        if row > 0 {
+            row_top = bottom + SECTION_SPACING;
+        }
+        for (col, instance) in chunk.iter().enumerate() {
+            let x = LEFT_MARGIN + col as f64 * (panel_width + PANEL_COL_GAP);
+            positions.push((x, row_top, panel_width));
+            bottom = bottom.max(row_top + instance_panel_height(instance, scale));
         }
     }
+    (positions, bottom)
 }
 
 // --- Drawing primitives ---
@@ -770,7 +908,13 @@ fn draw_section_header_with_font(scene: &mut Scene, x: f64, y: f64, w: f64, titl
     scene.fill(Fill::NonZero, Affine::IDENTITY, RULE_COLOR, None, &rule_rect);
 
     // Return the y position for content below
-    y + section_size * LINE_HEIGHT_FACTOR + 4.0
+    y + section_header_height(scale)
+}
+
+/// Vertical space a section header takes, from its baseline to the first
+/// line of content.
+fn section_header_height(scale: f64) -> f64 {
+    SECTION_SIZE * scale * LINE_HEIGHT_FACTOR + 4.0
 }
 
 /// Draw a label: value pair. Label in secondary weight, value right-aligned in primary weight.
@@ -1000,11 +1144,16 @@ pub struct SelectableRegionSpec {
 /// Compute selectable text regions for all instance panels.
 ///
 /// This replicates the layout math from `draw_instance_panel` so that
-/// `SelectableText` regions align with the rendered text.
+/// `SelectableText` regions align with the rendered text. Origins are in
+/// screen coordinates: `scroll_offset` is subtracted, and regions scrolled
+/// out of the content viewport are omitted so they can't be hit-tested
+/// through the pinned title row.
 pub fn compute_selectable_regions(
     instances: &[LobsterInstance],
     width: f64,
-    _height: f64,
+    height: f64,
+    scale: f64,
+    scroll_offset: f64,
 ) -> Vec<SelectableRegionSpec> {
     let mut regions = Vec::new();
 
@@ -1012,31 +1161,28 @@ pub fn compute_selectable_regions(
         return regions;
     }
 
-    let title_y = 56.0;
-    let content_top = title_y + 32.0;
-    let content_width = width - LEFT_MARGIN * 2.0;
-    let num_instances = instances.len();
+    let dp = DATA_PRIMARY_SIZE * scale * LINE_HEIGHT_FACTOR;
+    let ds_size = DATA_SECONDARY_SIZE * scale;
+    let ds = ds_size * LINE_HEIGHT_FACTOR;
+    let ann = ANNOTATION_SIZE * scale * LINE_HEIGHT_FACTOR;
+    let header = SECTION_SIZE * scale * LINE_HEIGHT_FACTOR + 4.0;
 
-    // Compute panel positions (same logic as render_dashboard).
-    let panel_positions: Vec<(f64, f64, f64, f64)> = if num_instances == 1 {
-        vec![(LEFT_MARGIN, content_top, content_width, 9999.0)]
-    } else {
-        let cols = if num_instances <= 2 { num_instances } else if num_instances <= 4 { 2 } else { 3 };
-        let col_gap = 48.0;
-        let panel_width = (content_width - (cols as f64 - 1.0) * col_gap) / cols as f64;
-
-        instances.iter().enumerate().map(|(idx, _)| {
-            let col = idx % cols;
-            let row = idx / cols;
-            let x = LEFT_MARGIN + col as f64 * (panel_width + col_gap);
-            let y = content_top + row as f64 * 600.0; // approximate
-            (x, y, panel_width, 600.0)
-        }).collect()
-    };
+    let (panel_positions, _) = panel_layout(instances, width, scale);
 
-    for (idx, instance) in instances.iter().enumerate() {
-        let (x, y, w, _h) = panel_positions[idx];
+    for (instance, &(x, y, w)) in instances.iter().zip(panel_positions.iter()) {
         let max_w = Some(w as f32);
+        let mut push = |text: String, font_size: f64, ox: f64, baseline: f64| {
+            let top = baseline - scroll_offset - font_size * 0.8;
+            if top + font_size < CONTENT_TOP || top > height {
+                return;
+            }
+            regions.push(SelectableRegionSpec {
+                text,
+                font_size: font_size as f32,
+                origin: (ox, top),
+                max_width: max_w,
+            });
+        };
         let mut cursor_y = y;
 
         // Hostname
@@ -1046,13 +1192,7 @@ pub fn compute_selectable_regions(
         } else {
             instance.url.clone()
         };
-        let hostname_font = 36.0_f32;
-        regions.push(SelectableRegionSpec {
-            text: hostname,
-            font_size: hostname_font,
-            origin: (x, cursor_y - hostname_font as f64 * 0.8),
-            max_width: max_w,
-        });
+        push(hostname, 36.0, x, cursor_y);
 
         // Status text
         let status_str = match &instance.status {
@@ -1061,14 +1201,8 @@ pub fn compute_selectable_regions(
             ConnectionStatus::Disconnected => "disconnected".to_string(),
             ConnectionStatus::Error(e) => format!("error: {}", &e[..e.len().min(30)]),
         };
-        cursor_y += DATA_SECONDARY_SIZE as f64 * LINE_HEIGHT_FACTOR;
-        let status_font = DATA_SECONDARY_SIZE as f32;
-        regions.push(SelectableRegionSpec {
-            text: status_str,
-            font_size: status_font,
-            origin: (x, cursor_y - status_font as f64 * 0.8),
-            max_width: max_w,
-        });
+        cursor_y += ds;
+        push(status_str, ds_size, x, cursor_y);
 
         if instance.status != ConnectionStatus::Connected {
             continue;
@@ -1078,13 +1212,10 @@ pub fn compute_selectable_regions(
 
         // Skip System section header + 4 data lines
         cursor_y += SECTION_SPACING;
-        cursor_y += SECTION_SIZE * LINE_HEIGHT_FACTOR + 4.0; // section header
-        cursor_y += DATA_PRIMARY_SIZE * LINE_HEIGHT_FACTOR * 4.0; // uptime, cpu, mem, disk
-        cursor_y += SECTION_SPACING;
+        cursor_y += header + dp * 3.0 + SECTION_SPACING;
 
         // Sessions section
-        cursor_y += SECTION_SIZE * LINE_HEIGHT_FACTOR + 4.0; // header
-        cursor_y += DATA_PRIMARY_SIZE * LINE_HEIGHT_FACTOR; // "Active: N"
+        cursor_y += header + dp; // header, "Active: N"
 
         let claude_sessions: Vec<_> = state.sessions.iter()
             .filter(|s| s.name == "claude")
@@ -1093,43 +1224,30 @@ pub fn compute_selectable_regions(
 
         for session in claude_sessions.iter().take(3) {
             let label = format!("PID {} -- {:.0} MB", session.pid, session.memory_mb);
-            let session_font = DATA_SECONDARY_SIZE as f32;
-            regions.push(SelectableRegionSpec {
-                text: label,
-                font_size: session_font,
-                origin: (x + 16.0, cursor_y - session_font as f64 * 0.8),
-                max_width: max_w,
-            });
-            cursor_y += DATA_SECONDARY_SIZE * LINE_HEIGHT_FACTOR;
+            push(label, ds_size, x + 16.0, cursor_y);
+            cursor_y += ds;
         }
         if session_count > 3 {
-            cursor_y += ANNOTATION_SIZE * LINE_HEIGHT_FACTOR;
+            cursor_y += ann;
         }
+        cursor_y += SECTION_SPACING - ds;
 
         // Skip Messages, Activity, Health sections (decorative stats)
-        // ... agents are the interesting selectable content
-
-        // Approximate skip to agents section
-        cursor_y += SECTION_SPACING * 4.0 + DATA_PRIMARY_SIZE * LINE_HEIGHT_FACTOR * 8.0;
+        cursor_y += header + dp * 4.0 + SECTION_SPACING - dp;
+        cursor_y += (header + dp + SECTION_SPACING) * 2.0;
 
         // AGENTS section
         let agents = &state.subagent_list.agents;
         if !agents.is_empty() {
-            cursor_y += SECTION_SIZE * LINE_HEIGHT_FACTOR + 4.0; // header
+            cursor_y += header;
             for agent in agents.iter().take(4) {
                 let desc = if agent.description.len() > 52 {
                     format!("{}...", &agent.description[..52])
                 } else {
                     agent.description.clone()
                 };
-                let agent_font = DATA_SECONDARY_SIZE as f32;
-                regions.push(SelectableRegionSpec {
-                    text: desc,
-                    font_size: agent_font,
-                    origin: (x, cursor_y - agent_font as f64 * 0.8),
-                    max_width: max_w,
-                });
-                cursor_y += DATA_SECONDARY_SIZE * LINE_HEIGHT_FACTOR;
+                push(desc, ds_size, x, cursor_y);
+                cursor_y += ds;
             }
         }
     }
@@ -1455,7 +1573,7 @@ mod tests {
     fn test_render_dashboard_no_instances() {
         let mut scene = Scene::new();
         let instances = Arc::new(Mutex::new(Vec::new()));
-        render_dashboard(&mut scene, 1280.0, 800.0, &instances, 0.0, None, &VoiceUiState::Idle, false, &DesignTokens::default());
+        render_dashboard(&mut scene, 1280.0, 800.0, &instances, 0.0, None, &VoiceUiState::Idle, false, &DesignTokens::default(), &mut ScrollState::new(), &mut Vec::new());
     }
 
     #[test]
@@ -1463,7 +1581,7 @@ mod tests {
         let mut scene = Scene::new();
         let instance = crate::protocol::LobsterInstance::new("ws://localhost:9100".to_string());
         let instances = Arc::new(Mutex::new(vec![instance]));
-        render_dashboard(&mut scene, 1280.0, 800.0, &instances, 0.0, None, &VoiceUiState::Idle, false, &DesignTokens::default());
+        render_dashboard(&mut scene, 1280.0, 800.0, &instances, 0.0, None, &VoiceUiState::Idle, false, &DesignTokens::default(), &mut ScrollState::new(), &mut Vec::new());
     }
 
     #[test]
@@ -1476,7 +1594,7 @@ mod tests {
         instance.state.system.memory.percent = 60.0;
         instance.state.system.disk.percent = 30.0;
         let instances = Arc::new(Mutex::new(vec![instance]));
-        render_dashboard(&mut scene, 1280.0, 800.0, &instances, 0.0, None, &VoiceUiState::Idle, false, &DesignTokens::default());
+        render_dashboard(&mut scene, 1280.0, 800.0, &instances, 0.0, None, &VoiceUiState::Idle, false, &DesignTokens::default(), &mut ScrollState::new(), &mut Vec::new());
     }
 
     #[test]
@@ -1487,23 +1605,116 @@ mod tests {
         inst1.state.system.hostname = "host1".to_string();
         let inst2 = crate::protocol::LobsterInstance::new("ws://host2:9100".to_string());
         let instances = Arc::new(Mutex::new(vec![inst1, inst2]));
-        render_dashboard(&mut scene, 1920.0, 1080.0, &instances, 5.0, None, &VoiceUiState::Idle, false, &DesignTokens::default());
+        render_dashboard(&mut scene, 1920.0, 1080.0, &instances, 5.0, None, &VoiceUiState::Idle, false, &DesignTokens::default(), &mut ScrollState::new(), &mut Vec::new());
     }
 
     #[test]
     fn test_render_dashboard_during_splash() {
         let mut scene = Scene::new();
         let instances = Arc::new(Mutex::new(Vec::new()));
-        render_dashboard(&mut scene, 1280.0, 800.0, &instances, 1.0, None, &VoiceUiState::Idle, false, &DesignTokens::default());
-        render_dashboard(&mut scene, 1280.0, 800.0, &instances, 4.0, None, &VoiceUiState::Idle, false, &DesignTokens::default());
-        render_dashboard(&mut scene, 1280.0, 800.0, &instances, 7.0, None, &VoiceUiState::Idle, false, &DesignTokens::default());
+        render_dashboard(&mut scene, 1280.0, 800.0, &instances, 1.0, None, &VoiceUiState::Idle, false, &DesignTokens::default(), &mut ScrollState::new(), &mut Vec::new());
+        render_dashboard(&mut scene, 1280.0, 800.0, &instances, 4.0, None, &VoiceUiState::Idle, false, &DesignTokens::default(), &mut ScrollState::new(), &mut Vec::new());
+        render_dashboard(&mut scene, 1280.0, 800.0, &instances, 7.0, None, &VoiceUiState::Idle, false, &DesignTokens::default(), &mut ScrollState::new(), &mut Vec::new());
     }
 
     #[test]
     fn test_render_dashboard_after_splash() {
         let mut scene = Scene::new();
         let instances = Arc::new(Mutex::new(Vec::new()));
-        render_dashboard(&mut scene, 1280.0, 800.0, &instances, 100.0, None, &VoiceUiState::Idle, false, &DesignTokens::default());
+        render_dashboard(&mut scene, 1280.0, 800.0, &instances, 100.0, None, &VoiceUiState::Idle, false, &DesignTokens::default(), &mut ScrollState::new(), &mut Vec::new());
+    }
+
+    // --- Layout / scrolling tests ---
+
+    #[test]
+    fn test_connected_panel_overflows_short_window() {
+        let mut inst = crate::protocol::LobsterInstance::new("ws://host1:9100".to_string());
+        inst.status = crate::protocol::ConnectionStatus::Connected;
+        let instances = Arc::new(Mutex::new(vec![inst]));
+        let mut scroll = ScrollState::new();
+        let mut scene = Scene::new();
+        render_dashboard(&mut scene, 1280.0, 400.0, &instances, 100.0, None, &VoiceUiState::Idle, false, &DesignTokens::default(), &mut scroll, &mut Vec::new());
+        assert!(scroll.is_overflowing(), "a full panel should not fit in 400px");
+        scroll.scroll_by(1.0e6);
+        assert_eq!(scroll.offset, scroll.max_offset());
+    }
+
+    #[test]
+    fn test_panel_layout_rows_do_not_overlap() {
+        let mut connected = crate::protocol::LobsterInstance::new("ws://a:9100".to_string());
+        connected.status = crate::protocol::ConnectionStatus::Connected;
+        let instances = vec![
+            connected.clone(),
+            crate::protocol::LobsterInstance::new("ws://b:9100".to_string()),
+            connected,
+        ];
+        let (positions, bottom) = panel_layout(&instances, 1280.0, 1.0);
+        assert_eq!(positions.len(), 3);
+        // Third panel starts a new row below the tallest panel of the first row.
+        let first_row_bottom = positions[0].1 + instance_panel_height(&instances[0], 1.0);
+        assert!(positions[2].1 >= first_row_bottom);
+        assert!(bottom >= positions[2].1 + instance_panel_height(&instances[2], 1.0));
+    }
+
+    #[test]
+    fn test_clickable_rows_recorded_and_scrolled() {
+        let mut inst = crate::protocol::LobsterInstance::new("ws://host1:9100".to_string());
+        inst.status = crate::protocol::ConnectionStatus::Connected;
+        let instances = Arc::new(Mutex::new(vec![inst]));
+        let mut scroll = ScrollState::new();
+        let mut rows = Vec::new();
+        let mut scene = Scene::new();
+        render_dashboard(&mut scene, 1280.0, 2000.0, &instances, 100.0, None, &VoiceUiState::Idle, false, &DesignTokens::default(), &mut scroll, &mut rows);
+        // Four queue rows are always present for a connected instance.
+        let inbox = rows
+            .iter()
+            .find(|r| r.target == DetailTarget::Queue { instance: 0, queue: "Inbox" })
+            .expect("inbox row");
+        let inbox_y = inbox.rect.y0;
+
+        // Same layout in a short window, scrolled: rows move up by the offset.
+        let mut scroll = ScrollState::new();
+        let mut scene = Scene::new();
+        render_dashboard(&mut scene, 1280.0, 600.0, &instances, 100.0, None, &VoiceUiState::Idle, false, &DesignTokens::default(), &mut scroll, &mut rows);
+        scroll.scroll_by(50.0);
+        render_dashboard(&mut scene, 1280.0, 600.0, &instances, 100.0, None, &VoiceUiState::Idle, false, &DesignTokens::default(), &mut scroll, &mut rows);
+        let inbox = rows
+            .iter()
+            .find(|r| r.target == DetailTarget::Queue { instance: 0, queue: "Inbox" })
+            .expect("inbox row still visible");
+        assert!((inbox.rect.y0 - (inbox_y - 50.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_selectable_regions_follow_scroll_offset() {
+        let mut inst = crate::protocol::LobsterInstance::new("ws://host1:9100".to_string());
+        inst.status = crate::protocol::ConnectionStatus::Connected;
+        inst.state.system.hostname = "host1".to_string();
+        let instances = vec![inst];
+        let top = compute_selectable_regions(&instances, 1280.0, 800.0, 1.0, 0.0);
+        let scrolled = compute_selectable_regions(&instances, 1280.0, 800.0, 1.0, 10.0);
+        assert_eq!(top[0].text, "host1");
+        assert!((top[0].origin.1 - scrolled[0].origin.1 - 10.0).abs() < 1e-9);
+        // Scrolled far enough, the hostname disappears under the title row.
+        let hidden = compute_selectable_regions(&instances, 1280.0, 800.0, 1.0, 200.0);
+        assert!(hidden.iter().all(|r| r.text != "host1"));
+    }
+
+    #[test]
+    fn test_update_scroll_clamps_before_regions_are_placed() {
+        let mut inst = crate::protocol::LobsterInstance::new("ws://host1:9100".to_string());
+        inst.status = crate::protocol::ConnectionStatus::Connected;
+        let instances = vec![inst];
+        let mut scroll = ScrollState::new();
+        update_scroll(&instances, 1280.0, 600.0, 1.0, &mut scroll);
+        scroll.scroll_by(10_000.0);
+        let scrolled = scroll.offset;
+        assert!(scrolled > 0.0);
+        // A taller window has less to scroll; the offset the selection
+        // regions use is already the clamped one the panels are drawn at.
+        update_scroll(&instances, 1280.0, 2000.0, 1.0, &mut scroll);
+        assert!(scroll.offset < scrolled);
+        assert_eq!(scroll.offset, scroll.max_offset());
     }
 
     // --- Ulysses quote test ---
@@ -123,11 +123,38 @@ pub struct AnimationTokens {
     pub cursor_blink_ms: u64,
 }
 
-#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 #[serde(default)]
 pub struct TerminalTokens {
     pub font_size: f32,
     pub pad_cells: usize,
+    /// Colors are `#rrggbb`, or `#rrggbbaa` with alpha.
+    pub foreground: String,
+    pub background: String,
+    pub cursor: String,
+    pub selection: String,
+    /// ANSI colors 0-7, then bright 8-15.
+    pub palette: Vec<String>,
+    /// Draw bold text in colors 0-7 with their bright variants.
+    pub bold_is_bright: bool,
+    /// iTerm2 (`.itermcolors`), Alacritty (`.toml`) or base16 (`.yaml`)
+    /// scheme file whose colors override the ones above; empty for none.
+    pub scheme: String,
+    /// `block`, `underline` or `beam`; applications may change it (DECSCUSR).
+    pub cursor_shape: String,
+    /// Blink the cursor (at `animation.cursor_blink_ms`) unless the
+    /// application asks otherwise.
+    pub cursor_blink: bool,
+    /// Let applications copy to the system clipboard with OSC 52 (as
+    /// `tmux` and `nvim` do over SSH). Reading the clipboard is never allowed.
+    pub clipboard_write: bool,
+    /// Command that opens a clicked `path:line:col` reference, with
+    /// `{file}`, `{line}` and `{column}` placeholders, e.g.
+    /// `code --goto {file}:{line}:{column}`. Empty: the system opener.
+    pub editor: String,
+    /// What happens when a pane's process exits: `keep` it with an exit
+    /// banner (Enter restarts), `close` it, or `restart` it.
+    pub on_exit: String,
 }
 
 // ---------------------------------------------------------------------------
@@ -251,6 +278,23 @@ impl Default for TerminalTokens {
         Self {
             font_size: 28.0,
             pad_cells: 1,
+            foreground: "#000000".into(),
+            background: "#ffe4c4".into(),
+            cursor: "#664221d9".into(),
+            selection: "#3380ff59".into(),
+            palette: [
+                "#000000", "#cc1c1c", "#21b04d", "#ccb01c", "#3366e6", "#ab1ccc", "#1cb0cc", "#bfbfbf",
+                "#1a1f21", "#f04d4d", "#4dde4d", "#f0f033", "#6b96f0", "#de4df0", "#4ddef0", "#f0f0f0",
+            ]
+            .map(String::from)
+            .to_vec(),
+            bold_is_bright: false,
+            scheme: String::new(),
+            cursor_shape: "block".into(),
+            cursor_blink: true,
+            clipboard_write: true,
+            editor: String::new(),
+            on_exit: "keep".into(),
         }
     }
 }
@@ -392,6 +436,14 @@ mod tests {
         // Terminal
         assert!((t.terminal.font_size - 28.0).abs() < f32::EPSILON);
         assert_eq!(t.terminal.pad_cells, 1);
+        assert_eq!(t.terminal.background, "#ffe4c4");
+        assert_eq!(t.terminal.palette.len(), 16);
+        assert!(t.terminal.scheme.is_empty());
+        assert_eq!(t.terminal.cursor_shape, "block");
+        assert!(t.terminal.cursor_blink);
+        assert!(t.terminal.clipboard_write);
+        assert!(t.terminal.editor.is_empty());
+        assert_eq!(t.terminal.on_exit, "keep");
     }
 
     #[test]
@@ -405,6 +457,7 @@ mod tests {
         assert!((parsed.type_scale.base - original.type_scale.base).abs() < f64::EPSILON);
         assert_eq!(parsed.animation.cursor_blink_ms, original.animation.cursor_blink_ms);
         assert!((parsed.terminal.font_size - original.terminal.font_size).abs() < f32::EPSILON);
+        assert_eq!(parsed.terminal, original.terminal);
     }
 
     #[test]
@@ -1,8 +1,8 @@
 //! Design REPL — interactive token editor rendered as a vello overlay.
 //!
-//! Toggle with Cmd+Shift+I. When active, keyboard input routes here instead
-//! of the PTY / dashboard. Commands: set, get, list, save, reset, defaults,
-//! help, exit.
+//! Toggle with Cmd+Shift+I (Ctrl+Shift+I on Linux). When active, keyboard
+//! input routes here instead of the PTY / dashboard. Commands: set, get,
+//! list, save, reset, defaults, help, exit.
 
 use std::sync::{Arc, RwLock};
 
@@ -69,6 +69,17 @@ impl DesignRepl {
         self.active = !self.active;
     }
 
+    /// Submitted lines, oldest first (for session persistence).
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Replace the history, e.g. with one restored from the last session.
+    pub fn set_history(&mut self, history: Vec<String>) {
+        self.history = history;
+        self.history_index = None;
+    }
+
     /// Handle a key press while the REPL is active.
     pub fn handle_key(&mut self, key: &Key, ctrl: bool, tokens: &Arc<RwLock<DesignTokens>>) {
         match key {
@@ -593,8 +604,19 @@ fn resolve_get(t: &DesignTokens, path: &str) -> Option<String> {
         // terminal
         "terminal.font_size" => format!("{:.1}", t.terminal.font_size),
         "terminal.pad_cells" => format!("{}", t.terminal.pad_cells),
-
-        _ => return None,
+        "terminal.foreground" => t.terminal.foreground.clone(),
+        "terminal.background" => t.terminal.background.clone(),
+        "terminal.cursor" => t.terminal.cursor.clone(),
+        "terminal.selection" => t.terminal.selection.clone(),
+        "terminal.bold_is_bright" => format!("{}", t.terminal.bold_is_bright),
+        "terminal.scheme" => t.terminal.scheme.clone(),
+        "terminal.cursor_shape" => t.terminal.cursor_shape.clone(),
+        "terminal.cursor_blink" => format!("{}", t.terminal.cursor_blink),
+        "terminal.clipboard_write" => format!("{}", t.terminal.clipboard_write),
+        "terminal.editor" => t.terminal.editor.clone(),
+        "terminal.on_exit" => t.terminal.on_exit.clone(),
+
+        _ => return palette_index(path).and_then(|i| t.terminal.palette.get(i).cloned()),
     })
 }
 
@@ -612,6 +634,14 @@ fn resolve_set(t: &mut DesignTokens, path: &str, value: &str) -> Result<String,
     let parse_usize = |v: &str| -> Result<usize, String> {
         v.parse::<usize>().map_err(|_| format!("Invalid integer: `{}`", v))
     };
+    let parse_bool = |v: &str| -> Result<bool, String> {
+        v.parse::<bool>().map_err(|_| format!("Invalid boolean: `{}`", v))
+    };
+    let parse_color = |v: &str| -> Result<String, String> {
+        crate::color_scheme::parse_hex(v)
+            .map(|_| v.to_string())
+            .ok_or_else(|| format!("Invalid color: `{}` (use #RRGGBB or #RRGGBBAA)", v))
+    };
 
     match path {
         // background — accept single value as RGB shorthand or dotted path
@@ -672,8 +702,37 @@ fn resolve_set(t: &mut DesignTokens, path: &str, value: &str) -> Result<String,
 
         "terminal.font_size" => { t.terminal.font_size = parse_f32(value)?; }
         "terminal.pad_cells" => { t.terminal.pad_cells = parse_usize(value)?; }
+        "terminal.foreground" => { t.terminal.foreground = parse_color(value)?; }
+        "terminal.background" => { t.terminal.background = parse_color(value)?; }
+        "terminal.cursor" => { t.terminal.cursor = parse_color(value)?; }
+        "terminal.selection" => { t.terminal.selection = parse_color(value)?; }
+        "terminal.bold_is_bright" => { t.terminal.bold_is_bright = parse_bool(value)?; }
+        "terminal.scheme" => {
+            if !value.is_empty() {
+                crate::color_scheme::import(&crate::color_scheme::expand_home(value))?;
+            }
+            t.terminal.scheme = value.to_string();
+        }
+        "terminal.cursor_shape" => {
+            if !matches!(value, "block" | "underline" | "beam") {
+                return Err(format!("Invalid cursor shape: `{}` (block, underline or beam)", value));
+            }
+            t.terminal.cursor_shape = value.to_string();
+        }
+        "terminal.cursor_blink" => { t.terminal.cursor_blink = parse_bool(value)?; }
+        "terminal.clipboard_write" => { t.terminal.clipboard_write = parse_bool(value)?; }
+        "terminal.editor" => { t.terminal.editor = value.to_string(); }
+        "terminal.on_exit" => {
+            if !matches!(value, "keep" | "close" | "restart") {
+                return Err(format!("Invalid on_exit: `{}` (keep, close or restart)", value));
+            }
+            t.terminal.on_exit = value.to_string();
+        }
 
-        _ => return Err(format!("Unknown path: `{}`. Type `list` to see all paths.", path)),
+        _ => match palette_index(path).filter(|i| *i < t.terminal.palette.len()) {
+            Some(i) => { t.terminal.palette[i] = parse_color(value)?; }
+            None => return Err(format!("Unknown path: `{}`. Type `list` to see all paths.", path)),
+        },
     }
 
     // For paths that don't return early, report the new value.
@@ -749,11 +808,30 @@ fn list_tokens(t: &DesignTokens, section: Option<&str>) -> Vec<(String, String)>
     if all || section == Some("terminal") {
         out.push(("terminal.font_size".into(), format!("{:.1}", t.terminal.font_size)));
         out.push(("terminal.pad_cells".into(), format!("{}", t.terminal.pad_cells)));
+        out.push(("terminal.foreground".into(), t.terminal.foreground.clone()));
+        out.push(("terminal.background".into(), t.terminal.background.clone()));
+        out.push(("terminal.cursor".into(), t.terminal.cursor.clone()));
+        out.push(("terminal.selection".into(), t.terminal.selection.clone()));
+        for (i, color) in t.terminal.palette.iter().enumerate() {
+            out.push((format!("terminal.palette.{}", i), color.clone()));
+        }
+        out.push(("terminal.bold_is_bright".into(), format!("{}", t.terminal.bold_is_bright)));
+        out.push(("terminal.scheme".into(), t.terminal.scheme.clone()));
+        out.push(("terminal.cursor_shape".into(), t.terminal.cursor_shape.clone()));
+        out.push(("terminal.cursor_blink".into(), format!("{}", t.terminal.cursor_blink)));
+        out.push(("terminal.clipboard_write".into(), format!("{}", t.terminal.clipboard_write)));
+        out.push(("terminal.editor".into(), t.terminal.editor.clone()));
+        out.push(("terminal.on_exit".into(), t.terminal.on_exit.clone()));
     }
 
     out
 }
 
+/// `N` from a `terminal.palette.N` path.
+fn palette_index(path: &str) -> Option<usize> {
+    path.strip_prefix("terminal.palette.")?.parse().ok()
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -813,6 +891,17 @@ mod tests {
         assert!((t.background.r - 0xFA as f64 / 255.0).abs() < 0.01);
     }
 
+    #[test]
+    fn resolve_set_terminal_colors() {
+        let mut t = DesignTokens::default();
+        assert!(resolve_set(&mut t, "terminal.palette.1", "#ff0000").is_ok());
+        assert_eq!(resolve_get(&t, "terminal.palette.1").as_deref(), Some("#ff0000"));
+        assert!(resolve_set(&mut t, "terminal.palette.16", "#ff0000").is_err());
+        assert!(resolve_set(&mut t, "terminal.background", "bisque").is_err());
+        assert!(resolve_set(&mut t, "terminal.bold_is_bright", "true").is_ok());
+        assert!(t.terminal.bold_is_bright);
+    }
+
     #[test]
     fn resolve_set_invalid_number() {
         let mut t = DesignTokens::default();
@@ -0,0 +1,444 @@
+//! Detail overlay for dashboard drill-down.
+//!
+//! Clicking a row on the dashboard (a session, a message queue, an agent or a
+//! memory event) opens this overlay with everything the server reports about
+//! that entity: the full `Session.cmdline`, the `AgentRuntime.top_tools`
+//! breakdown, full memory content with tags, queue `recent` payloads.
+//!
+//! The overlay keeps a `DetailTarget` rather than a copy of the data, so it
+//! stays live as new snapshots arrive. Body text is rendered through
+//! `SelectableText`, so values can be selected and copied with Cmd+C like any
+//! other dashboard text.
+//!
+//! Typography-first: a near-opaque bisque wash over the dashboard, a title,
+//! and label/value pairs in a single centered column. Escape closes it.
+
+use vello::Scene;
+use vello::kurbo::{Affine, Rect};
+use vello::peniko::{Color, Fill, FontData};
+
+use crate::protocol::{LobsterInstance, SubagentInfo};
+use crate::scroll::{self, ScrollState};
+use crate::text_selection::{ParleyCtx, SelectableText};
+use crate::ws_client::SharedInstances;
+
+// --- Palette (matches the dashboard) ---
+const WASH_COLOR: Color = Color::new([1.0, 0.894, 0.769, 0.97]);
+const TEXT_PRIMARY: Color = Color::new([0.0, 0.0, 0.0, 1.0]);
+const TEXT_ANNOTATION: Color = Color::new([0.0, 0.0, 0.0, 0.75]);
+const SELECTION_COLOR: Color = Color::new([0.2, 0.5, 1.0, 0.35]);
+
+// --- Typography / layout ---
+const TITLE_SIZE: f64 = 36.0;
+const LABEL_SIZE: f64 = 16.0;
+const BODY_SIZE: f32 = 20.0;
+const LINE_HEIGHT_FACTOR: f64 = 1.4;
+const MARGIN: f64 = 48.0;
+const TITLE_Y: f64 = 56.0;
+const CONTENT_TOP: f64 = TITLE_Y + 32.0;
+const COLUMN_MAX_WIDTH: f64 = 880.0;
+const FIELD_SPACING: f64 = 20.0;
+
+// ---------------------------------------------------------------------------
+// Targets and their descriptions
+// ---------------------------------------------------------------------------
+
+/// The entity a dashboard row refers to. Resolved against the latest
+/// instance state each frame.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DetailTarget {
+    Session { instance: usize, pid: u32 },
+    /// One of the message queues, by its dashboard label ("Inbox", "Sent", ...).
+    Queue { instance: usize, queue: &'static str },
+    Agent { instance: usize, id: String },
+    /// Memory events carry an optional numeric id; the timestamp disambiguates
+    /// events the server sent without one.
+    MemoryEvent { instance: usize, id: Option<u64>, timestamp: String },
+}
+
+/// A titled list of label/value pairs describing one entity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detail {
+    pub title: String,
+    pub fields: Vec<(String, String)>,
+}
+
+/// Describe `target` using the current instance state.
+///
+/// Returns `None` when the entity is no longer reported (the session exited,
+/// the agent finished, the event rolled out of the recent window).
+pub fn describe(target: &DetailTarget, instances: &[LobsterInstance]) -> Option<Detail> {
+    match target {
+        DetailTarget::Session { instance, pid } => {
+            let inst = instances.get(*instance)?;
+            let session = inst.state.sessions.iter().find(|s| s.pid == *pid)?;
+            let cmdline = if session.cmdline.is_empty() {
+                "(not reported)".to_string()
+            } else {
+                session.cmdline.clone()
+            };
+            Some(Detail {
+                title: format!("Session {}", session.pid),
+                fields: vec![
+                    ("Command line".into(), cmdline),
+                    ("Name".into(), session.name.clone()),
+                    ("Started".into(), session.started.clone().unwrap_or_else(|| "unknown".into())),
+                    ("CPU".into(), format!("{:.1}%", session.cpu_percent)),
+                    ("Memory".into(), format!("{:.0} MB", session.memory_mb)),
+                    ("Host".into(), inst.state.system.hostname.clone()),
+                ],
+            })
+        }
+        DetailTarget::Queue { instance, queue } => {
+            let q = &instances.get(*instance)?.state.message_queues;
+            let (count, recent) = match *queue {
+                "Inbox" => (q.inbox.count, q.inbox.recent.as_slice()),
+                "Processed" => (q.processed.count, &[][..]),
+                "Sent" => (q.sent.count, &[][..]),
+                "Failed" => (q.failed.count, &[][..]),
+                _ => return None,
+            };
+            let payloads = if recent.is_empty() {
+                "No recent payloads reported for this queue".to_string()
+            } else {
+                recent
+                    .iter()
+                    .map(|v| serde_json::to_string_pretty(v).unwrap_or_else(|_| v.to_string()))
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            };
+            Some(Detail {
+                title: format!("{} queue", queue),
+                fields: vec![
+                    ("Messages".into(), count.to_string()),
+                    ("Recent payloads".into(), payloads),
+                ],
+            })
+        }
+        DetailTarget::Agent { instance, id } => {
+            let inst = instances.get(*instance)?;
+            let agent = inst.state.subagent_list.agents.iter().find(|a| &a.id == id)?;
+            Some(describe_agent(agent))
+        }
+        DetailTarget::MemoryEvent { instance, id, timestamp } => {
+            let inst = instances.get(*instance)?;
+            let event = inst
+                .state
+                .memory
+                .recent_events
+                .iter()
+                .find(|e| e.id == *id && &e.timestamp == timestamp)?;
+            let tags = if event.tags.is_empty() {
+                "none".to_string()
+            } else {
+                event.tags.join(", ")
+            };
+            Some(Detail {
+                title: format!("Memory event ({})", event.event_type),
+                fields: vec![
+                    ("Content".into(), event.content.clone()),
+                    ("Tags".into(), tags),
+                    ("Timestamp".into(), event.timestamp.clone()),
+                    ("Source".into(), event.source.clone()),
+                    ("Project".into(), event.project.clone().unwrap_or_else(|| "none".into())),
+                    ("Consolidated".into(), if event.consolidated { "yes" } else { "no" }.into()),
+                ],
+            })
+        }
+    }
+}
+
+fn describe_agent(agent: &SubagentInfo) -> Detail {
+    let mut fields = vec![
+        ("Description".to_string(), agent.description.clone()),
+        ("ID".to_string(), agent.id.clone()),
+        ("Status".to_string(), agent.status.clone()),
+    ];
+    if let Some(s) = agent.elapsed_seconds {
+        fields.push(("Elapsed".into(), format!("{}m {}s", s / 60, s % 60)));
+    }
+    if let Some(started) = &agent.started_at {
+        fields.push(("Started".into(), started.clone()));
+    }
+    if let Some(chat) = agent.chat_id {
+        fields.push(("Chat".into(), chat.to_string()));
+    }
+    if let Some(rt) = &agent.runtime {
+        fields.push(("Turns".into(), rt.turns.to_string()));
+        fields.push(("Tokens".into(), format!("{} in / {} out", rt.input_tokens, rt.output_tokens)));
+        fields.push(("Tool uses".into(), rt.tool_uses.to_string()));
+
+        // Most-used tools first; ties broken by name so the order is stable.
+        let mut tools: Vec<(&String, &u64)> = rt.top_tools.iter().collect();
+        tools.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        let breakdown = if tools.is_empty() {
+            "none".to_string()
+        } else {
+            tools
+                .iter()
+                .map(|(name, n)| format!("{}  {}", name, n))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        fields.push(("Top tools".into(), breakdown));
+
+        if let Some(ago) = rt.last_activity_seconds_ago {
+            let stale = if rt.stale { " (stale)" } else { "" };
+            fields.push(("Last activity".into(), format!("{}s ago{}", ago, stale)));
+        }
+    }
+    Detail {
+        title: "Agent".to_string(),
+        fields,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Overlay
+// ---------------------------------------------------------------------------
+
+/// The open/closed drill-down overlay and its selectable body text.
+pub struct DetailOverlay {
+    target: Option<DetailTarget>,
+    /// The detail the current `regions` were built for. Regions are only
+    /// rebuilt when this changes, so an in-progress selection survives redraws.
+    shown: Option<Detail>,
+    /// Column width the regions were laid out for.
+    shown_width: f64,
+    /// One selectable body per field, in `shown.fields` order.
+    regions: Vec<SelectableText>,
+    scroll: ScrollState,
+}
+
+impl DetailOverlay {
+    pub fn new() -> Self {
+        Self {
+            target: None,
+            shown: None,
+            shown_width: 0.0,
+            regions: Vec::new(),
+            scroll: ScrollState::new(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// Open the overlay for `target`, replacing whatever was shown.
+    pub fn open(&mut self, target: DetailTarget) {
+        self.target = Some(target);
+        self.shown = None;
+        self.regions.clear();
+        self.scroll = ScrollState::new();
+    }
+
+    pub fn close(&mut self) {
+        self.target = None;
+        self.shown = None;
+        self.regions.clear();
+    }
+
+    pub fn scroll_by(&mut self, dy: f64) {
+        self.scroll.scroll_by(dy);
+    }
+
+    pub fn page(&mut self, forward: bool) {
+        self.scroll.page(forward);
+    }
+
+    // ------------------------------------------------------------------
+    // Selection (delegates to SelectableText)
+    // ------------------------------------------------------------------
+
+    pub fn handle_mouse_press(&mut self, x: f64, y: f64, ctx: &mut ParleyCtx) {
+        if y < CONTENT_TOP {
+            return;
+        }
+        for region in &mut self.regions {
+            if region.hit_test(x, y) {
+                region.handle_mouse_press(x, y, ctx);
+            }
+        }
+    }
+
+    pub fn handle_mouse_drag(&mut self, x: f64, y: f64, ctx: &mut ParleyCtx) {
+        for region in &mut self.regions {
+            region.handle_mouse_drag(x, y, ctx);
+        }
+    }
+
+    pub fn handle_mouse_release(&mut self) {
+        for region in &mut self.regions {
+            region.handle_mouse_release();
+        }
+    }
+
+    /// The first non-empty selection in the overlay, if any.
+    pub fn selected_text(&self) -> Option<&str> {
+        self.regions
+            .iter()
+            .filter_map(|r| r.selected_text())
+            .find(|t| !t.is_empty())
+    }
+
+    // ------------------------------------------------------------------
+    // Rendering
+    // ------------------------------------------------------------------
+
+    /// Draw the overlay over the dashboard. No-op when closed. The
+    /// instance list is only locked while the target is described.
+    pub fn render(
+        &mut self,
+        scene: &mut Scene,
+        width: f64,
+        height: f64,
+        instances: &SharedInstances,
+        font_data: Option<&FontData>,
+        ctx: &mut ParleyCtx,
+    ) {
+        let Some(target) = &self.target else {
+            return;
+        };
+
+        scene.fill(Fill::NonZero, Affine::IDENTITY, WASH_COLOR, None, &Rect::new(0.0, 0.0, width, height));
+
+        let col_w = (width - MARGIN * 2.0).min(COLUMN_MAX_WIDTH);
+        let x = ((width - col_w) / 2.0).max(MARGIN);
+        crate::dashboard::draw_text_pub(scene, width - MARGIN - 140.0, TITLE_Y, "Esc to close", TEXT_ANNOTATION, LABEL_SIZE, font_data);
+
+        let detail = describe(target, &instances.lock().unwrap());
+        let Some(detail) = detail else {
+            crate::dashboard::draw_text_pub(scene, x, TITLE_Y, "No longer reported", TEXT_PRIMARY, TITLE_SIZE, font_data);
+            crate::dashboard::draw_text_pub(
+                scene,
+                x,
+                CONTENT_TOP + 24.0,
+                "The server stopped reporting this entry.",
+                TEXT_ANNOTATION,
+                LABEL_SIZE,
+                font_data,
+            );
+            return;
+        };
+
+        crate::dashboard::draw_text_pub(scene, x, TITLE_Y, &detail.title, TEXT_PRIMARY, TITLE_SIZE, font_data);
+
+        if self.shown.as_ref() != Some(&detail) || self.shown_width != col_w {
+            self.regions = detail
+                .fields
+                .iter()
+                .map(|(_, value)| SelectableText::new(value, BODY_SIZE, (x, 0.0), Some(col_w as f32), ctx))
+                .collect();
+            self.shown = Some(detail);
+            self.shown_width = col_w;
+        }
+        let Some(shown) = &self.shown else {
+            return;
+        };
+
+        // Lay fields out top to bottom at unscrolled positions, then shift by
+        // the scroll offset; everything is clipped to the area below the title.
+        let viewport = Rect::new(0.0, CONTENT_TOP, width, height);
+        scroll::push_clip(scene, &viewport);
+        let mut cursor_y = CONTENT_TOP + LABEL_SIZE;
+        for ((label, _), region) in shown.fields.iter().zip(self.regions.iter_mut()) {
+            let y = cursor_y - self.scroll.offset;
+            crate::dashboard::draw_text_pub(scene, x, y, label, TEXT_ANNOTATION, LABEL_SIZE, font_data);
+            cursor_y += LABEL_SIZE * (LINE_HEIGHT_FACTOR - 1.0) + 4.0;
+
+            region.set_origin((x, cursor_y - self.scroll.offset));
+            region.render_into_scene(scene, TEXT_PRIMARY, SELECTION_COLOR, ctx, false);
+            cursor_y += region.layout_height(ctx) + FIELD_SPACING + LABEL_SIZE;
+        }
+        scene.pop_layer();
+
+        self.scroll.set_extents(cursor_y - CONTENT_TOP + MARGIN, viewport.height());
+        scroll::draw_overflow_indicator(scene, &viewport, &self.scroll);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{AgentRuntime, ConnectionStatus, MemoryEvent, Session};
+
+    fn connected() -> LobsterInstance {
+        let mut inst = LobsterInstance::new("ws://host:9100".to_string());
+        inst.status = ConnectionStatus::Connected;
+        inst
+    }
+
+    #[test]
+    fn session_detail_shows_full_cmdline() {
+        let mut inst = connected();
+        let cmdline = "claude --dangerously-skip-permissions --model opus ".repeat(4);
+        inst.state.sessions.push(Session {
+            pid: 4242,
+            name: "claude".into(),
+            cmdline: cmdline.clone(),
+            started: None,
+            cpu_percent: 1.5,
+            memory_mb: 310.0,
+        });
+        let detail = describe(&DetailTarget::Session { instance: 0, pid: 4242 }, &[inst]).unwrap();
+        assert_eq!(detail.title, "Session 4242");
+        assert_eq!(detail.fields[0], ("Command line".to_string(), cmdline));
+    }
+
+    #[test]
+    fn agent_top_tools_sorted_by_count() {
+        let mut inst = connected();
+        let mut runtime = AgentRuntime::default();
+        runtime.top_tools.insert("Read".into(), 3);
+        runtime.top_tools.insert("Bash".into(), 9);
+        runtime.top_tools.insert("Edit".into(), 3);
+        inst.state.subagent_list.agents.push(SubagentInfo {
+            id: "a1".into(),
+            runtime: Some(runtime),
+            ..Default::default()
+        });
+        let detail = describe(&DetailTarget::Agent { instance: 0, id: "a1".into() }, &[inst]).unwrap();
+        let tools = &detail.fields.iter().find(|(l, _)| l == "Top tools").unwrap().1;
+        assert_eq!(tools, "Bash  9\nEdit  3\nRead  3");
+    }
+
+    #[test]
+    fn memory_event_detail_includes_content_and_tags() {
+        let mut inst = connected();
+        inst.state.memory.recent_events.push(MemoryEvent {
+            id: Some(7),
+            timestamp: "2026-01-01T00:00:00".into(),
+            event_type: "decision".into(),
+            source: "telegram".into(),
+            project: None,
+            content: "x".repeat(200),
+            tags: vec!["infra".into(), "docker".into()],
+            consolidated: false,
+        });
+        let target = DetailTarget::MemoryEvent {
+            instance: 0,
+            id: Some(7),
+            timestamp: "2026-01-01T00:00:00".into(),
+        };
+        let detail = describe(&target, &[inst]).unwrap();
+        assert_eq!(detail.fields[0].1.len(), 200);
+        assert_eq!(detail.fields[1].1, "infra, docker");
+    }
+
+    #[test]
+    fn inbox_detail_pretty_prints_recent_payloads() {
+        let mut inst = connected();
+        inst.state.message_queues.inbox.count = 1;
+        inst.state.message_queues.inbox.recent.push(serde_json::json!({"text": "hi"}));
+        let detail = describe(&DetailTarget::Queue { instance: 0, queue: "Inbox" }, &[inst]).unwrap();
+        assert!(detail.fields[1].1.contains("\"text\": \"hi\""));
+    }
+
+    #[test]
+    fn vanished_entity_describes_as_none() {
+        let inst = connected();
+        assert!(describe(&DetailTarget::Session { instance: 0, pid: 1 }, &[inst.clone()]).is_none());
+        assert!(describe(&DetailTarget::Session { instance: 3, pid: 1 }, &[inst]).is_none());
+    }
+}
@@ -0,0 +1,164 @@
+//! Per-glyph font selection for terminal cells.
+//!
+//! The terminal font (Monaco, or the embedded Cascadia Code) covers Latin
+//! text but not CJK, emoji or most symbol blocks. `FontChain` puts system
+//! fallback fonts behind it and picks, per character, the first font whose
+//! charmap has a glyph. Fallback fonts are read from disk once per process
+//! and shared by every pane; lookups are cached per chain.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use skrifa::MetadataProvider;
+use skrifa::instance::{LocationRef, Size};
+use vello::peniko::FontData;
+
+/// Fallback fonts, tried in order after the terminal font.
+#[cfg(target_os = "macos")]
+const FALLBACK_FONT_PATHS: &[&str] = &[
+    "/System/Library/Fonts/Menlo.ttc",
+    "/System/Library/Fonts/Apple Color Emoji.ttc",
+    "/System/Library/Fonts/PingFang.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    "/System/Library/Fonts/AppleSDGothicNeo.ttc",
+    "/System/Library/Fonts/Apple Symbols.ttf",
+    "/System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+];
+
+#[cfg(not(target_os = "macos"))]
+const FALLBACK_FONT_PATHS: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf",
+    "/usr/share/fonts/TTF/DejaVuSansMono.ttf",
+    "/usr/share/fonts/truetype/noto/NotoColorEmoji.ttf",
+    "/usr/share/fonts/noto/NotoColorEmoji.ttf",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/noto/NotoSansSymbols2-Regular.ttf",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+];
+
+/// System fallback fonts that exist on this machine, loaded on first use.
+fn system_fallbacks() -> &'static [FontData] {
+    static FONTS: OnceLock<Vec<FontData>> = OnceLock::new();
+    FONTS.get_or_init(|| {
+        FALLBACK_FONT_PATHS
+            .iter()
+            .filter_map(|path| std::fs::read(path).ok())
+            .map(|data| FontData::new(data.into(), 0))
+            .collect()
+    })
+}
+
+/// A glyph chosen for a character: which font in the chain, and its id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GlyphRef {
+    pub font: usize,
+    pub id: u32,
+}
+
+pub struct FontChain {
+    fonts: Vec<FontData>,
+    glyphs: RefCell<HashMap<char, GlyphRef>>,
+    /// Advance widths at size 1.0, scaled by the caller's font size.
+    advances: RefCell<HashMap<GlyphRef, f32>>,
+}
+
+impl FontChain {
+    /// `primary` followed by the system fallbacks.
+    pub fn new(primary: FontData) -> Self {
+        let mut fonts = vec![primary];
+        fonts.extend(system_fallbacks().iter().cloned());
+        Self::with_fonts(fonts)
+    }
+
+    fn with_fonts(fonts: Vec<FontData>) -> Self {
+        Self {
+            fonts,
+            glyphs: RefCell::new(HashMap::new()),
+            advances: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn primary(&self) -> &FontData {
+        &self.fonts[0]
+    }
+
+    pub fn font(&self, index: usize) -> &FontData {
+        &self.fonts[index]
+    }
+
+    /// The first font with a glyph for `c`; the primary font's missing
+    /// glyph if none has one.
+    pub fn glyph(&self, c: char) -> GlyphRef {
+        if let Some(found) = self.glyphs.borrow().get(&c) {
+            return *found;
+        }
+        let found = self
+            .fonts
+            .iter()
+            .enumerate()
+            .find_map(|(font, data)| {
+                let id = font_ref(data)?.charmap().map(c)?.to_u32();
+                (id != 0).then_some(GlyphRef { font, id })
+            })
+            .unwrap_or(GlyphRef { font: 0, id: 0 });
+        self.glyphs.borrow_mut().insert(c, found);
+        found
+    }
+
+    /// Advance width of `glyph` at `font_size`, in pixels.
+    pub fn advance(&self, glyph: GlyphRef, font_size: f32) -> f32 {
+        if let Some(unit) = self.advances.borrow().get(&glyph) {
+            return unit * font_size;
+        }
+        let unit = font_ref(&self.fonts[glyph.font])
+            .and_then(|f| {
+                f.glyph_metrics(Size::new(1.0), LocationRef::default())
+                    .advance_width(skrifa::GlyphId::new(glyph.id))
+            })
+            .unwrap_or(0.6);
+        self.advances.borrow_mut().insert(glyph, unit);
+        unit * font_size
+    }
+}
+
+fn font_ref(data: &FontData) -> Option<skrifa::FontRef<'_>> {
+    skrifa::FontRef::from_index(data.data.as_ref(), data.index).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CASCADIA: &[u8] = include_bytes!("../assets/CascadiaCode.ttf");
+
+    fn cascadia() -> FontData {
+        FontData::new(CASCADIA.to_vec().into(), 0)
+    }
+
+    #[test]
+    fn primary_font_wins_when_it_has_the_glyph() {
+        let chain = FontChain::with_fonts(vec![cascadia(), cascadia()]);
+        let g = chain.glyph('A');
+        assert_eq!(g.font, 0);
+        assert_ne!(g.id, 0);
+    }
+
+    #[test]
+    fn missing_glyphs_fall_back_to_notdef() {
+        let chain = FontChain::with_fonts(vec![cascadia()]);
+        // A supplementary private-use codepoint no font defines.
+        assert_eq!(chain.glyph('\u{10FFFD}'), GlyphRef { font: 0, id: 0 });
+    }
+
+    #[test]
+    fn advance_scales_with_size() {
+        let chain = FontChain::with_fonts(vec![cascadia()]);
+        let g = chain.glyph('M');
+        let small = chain.advance(g, 10.0);
+        let large = chain.advance(g, 20.0);
+        assert!(small > 0.0);
+        assert!((large - 2.0 * small).abs() < 1e-3);
+    }
+}
@@ -10,6 +10,7 @@ use vello::Scene;
 use vello::kurbo::{Affine, Rect};
 use vello::peniko::{Color, Fill, FontData};
 
+use crate::scroll::{self, ScrollState};
 use crate::ws_client::SharedInstances;
 
 // --- Color palette (typography-only) ---
@@ -36,6 +37,9 @@ const COL_GAP: f64 = 48.0;
 ///
 /// Displays recent memory events (from `DashboardState.memory.recent_events`)
 /// and active subagents (from `DashboardState.subagent_list.agents`).
+///
+/// The title stays pinned; both columns scroll together below it. `scroll`
+/// receives the laid-out content height so input handlers can clamp to it.
 pub fn render_info_screen(
     scene: &mut Scene,
     width: f64,
@@ -43,6 +47,7 @@ pub fn render_info_screen(
     instances: &SharedInstances,
     font_data: Option<&FontData>,
     _tokens: &crate::design::DesignTokens,
+    scroll: &mut ScrollState,
 ) {
     // Background fill.
     scene.fill(
@@ -60,7 +65,6 @@ pub fn render_info_screen(
     );
 
     let content_top = title_y + 32.0;
-    let content_height = height - content_top - LEFT_MARGIN;
     let half_w = (width - LEFT_MARGIN * 2.0 - COL_GAP) / 2.0;
 
     // Get the first connected instance's state (or default).
@@ -74,27 +78,35 @@ pub fn render_info_screen(
         }
     };
 
+    // Columns are laid out at unscrolled coordinates in their own scene,
+    // then composited through the viewport clip.
+    let mut content = Scene::new();
+
     // Left column: Recent Memory Events.
-    draw_memory_column(
-        scene,
+    let memory_bottom = draw_memory_column(
+        &mut content,
         LEFT_MARGIN,
         content_top,
         half_w,
-        content_height,
         instance_state.as_ref(),
         font_data,
     );
 
     // Right column: Active Subagents.
-    draw_agents_column(
-        scene,
+    let agents_bottom = draw_agents_column(
+        &mut content,
         LEFT_MARGIN + half_w + COL_GAP,
         content_top,
         half_w,
-        content_height,
         instance_state.as_ref(),
         font_data,
     );
+
+    let viewport = Rect::new(0.0, content_top - SECTION_SIZE, width, height);
+    let content_height = memory_bottom.max(agents_bottom) - viewport.y0 + LEFT_MARGIN;
+    scroll.set_extents(content_height, viewport.height());
+    scroll::append_scrolled(scene, &content, &viewport, scroll);
+    scroll::draw_overflow_indicator(scene, &viewport, scroll);
 }
 
 /// Draw a section header: title text + thin rule underneath.
@@ -116,16 +128,16 @@ fn draw_section_header(
     y + SECTION_SIZE * LINE_HEIGHT_FACTOR + 4.0
 }
 
-/// Draw the Recent Memory Events column.
+/// Draw the Recent Memory Events column, newest first.
+/// Returns the y position below the last line drawn.
 fn draw_memory_column(
     scene: &mut Scene,
     x: f64,
     y: f64,
     w: f64,
-    h: f64,
     state: Option<&crate::protocol::DashboardState>,
     font_data: Option<&FontData>,
-) {
+) -> f64 {
     let mut cursor_y = y;
 
     cursor_y = draw_section_header(scene, x, cursor_y, w, "Recent Memory", font_data);
@@ -144,14 +156,10 @@ fn draw_memory_column(
             DATA_SECONDARY_SIZE,
             font_data,
         );
-        return;
+        return cursor_y + DATA_SECONDARY_SIZE * LINE_HEIGHT_FACTOR;
     }
 
-    let line_height = DATA_SECONDARY_SIZE * LINE_HEIGHT_FACTOR;
-    let max_visible = ((h - (cursor_y - y)) / (line_height + ANNOTATION_SIZE * LINE_HEIGHT_FACTOR)) as usize;
-    let events_to_show = events.iter().rev().take(max_visible);
-
-    for event in events_to_show {
+    for event in events.iter().rev() {
         let type_label = &event.event_type;
         let content_truncated: String = event.content.chars().take(60).collect();
         let time_short: String = event.timestamp.chars().take(19).collect();
@@ -192,22 +200,21 @@ fn draw_memory_column(
         );
 
         cursor_y += ANNOTATION_SIZE * LINE_HEIGHT_FACTOR + 8.0;
-        if cursor_y + line_height > y + h {
-            break;
-        }
     }
+
+    cursor_y
 }
 
 /// Draw the Active Subagents column.
+/// Returns the y position below the last line drawn.
 fn draw_agents_column(
     scene: &mut Scene,
     x: f64,
     y: f64,
     w: f64,
-    h: f64,
     state: Option<&crate::protocol::DashboardState>,
     font_data: Option<&FontData>,
-) {
+) -> f64 {
     let mut cursor_y = y;
 
     cursor_y = draw_section_header(scene, x, cursor_y, w, "Active Agents", font_data);
@@ -241,14 +248,10 @@ fn draw_agents_column(
             DATA_SECONDARY_SIZE,
             font_data,
         );
-        return;
+        return cursor_y + DATA_SECONDARY_SIZE * LINE_HEIGHT_FACTOR;
     }
 
-    let entry_height = DATA_SECONDARY_SIZE * LINE_HEIGHT_FACTOR * 2.0 + 12.0;
-    let max_visible = ((h - (cursor_y - y)) / entry_height) as usize;
-    let agents_to_show = agents.iter().take(max_visible);
-
-    for agent in agents_to_show {
+    for agent in agents {
         // Agent ID
         let id_short: String = agent.id.chars().take(20).collect();
         crate::dashboard::draw_text_pub(
@@ -303,11 +306,9 @@ fn draw_agents_column(
         );
 
         cursor_y += ANNOTATION_SIZE * LINE_HEIGHT_FACTOR + 12.0;
-
-        if cursor_y + entry_height > y + h {
-            break;
-        }
     }
+
+    cursor_y
 }
 
 /// Format elapsed seconds as "Xm Ys" or "Xs".
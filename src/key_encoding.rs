@@ -0,0 +1,592 @@
+//! Key press → bytes for the PTY.
+//!
+//! Two encodings are supported:
+//!
+//! - **xterm (legacy)**: Ctrl folds letters and `@[\]^_` into C0 controls,
+//!   Alt prefixes ESC, Shift+Tab is `CSI Z`, and modified cursor / editing /
+//!   function keys carry an xterm modifier parameter (`CSI 1;5C` for
+//!   Ctrl+Right, `CSI 5;3~` for Alt+PageUp). Cursor keys honour DECCKM
+//!   (application cursor) and the numeric keypad honours DECKPAM
+//!   (application keypad).
+//! - **kitty keyboard protocol**: used only once the application pushes
+//!   progressive-enhancement flags (`CSI > flags u`), which
+//!   `alacritty_terminal` tracks in `TermMode`. Keys that are ambiguous in
+//!   the legacy encoding become `CSI code;mods u`; the higher flags add
+//!   event types (repeat/release), shifted alternates, every key as an
+//!   escape code, and associated text.
+//!
+//! The modifier parameter is the same in both: `1 + shift + 2·alt + 4·ctrl
+//! + 8·super`.
+
+use winit::event::{ElementState, KeyEvent};
+use winit::keyboard::{Key, KeyLocation, ModifiersState, NamedKey};
+use winit::platform::modifier_supplement::KeyEventExtModifierSupplement;
+
+/// Kitty progressive-enhancement flags (the `flags` in `CSI > flags u`).
+pub mod kitty {
+    pub const DISAMBIGUATE: u8 = 0b1;
+    pub const REPORT_EVENT_TYPES: u8 = 0b10;
+    pub const REPORT_ALTERNATE_KEYS: u8 = 0b100;
+    pub const REPORT_ALL_KEYS: u8 = 0b1000;
+    pub const REPORT_TEXT: u8 = 0b10000;
+}
+
+/// Terminal modes that change how keys are encoded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EncodingMode {
+    /// DECCKM: unmodified cursor keys send `SS3 A` instead of `CSI A`.
+    pub app_cursor: bool,
+    /// DECKPAM: keypad keys send `SS3` sequences instead of their characters.
+    pub app_keypad: bool,
+    /// Active kitty flags; 0 means the legacy encoding.
+    pub kitty_flags: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    Press,
+    Repeat,
+    Release,
+}
+
+/// Everything about a key event that the encoders look at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyPress {
+    /// The key with modifiers applied (Shift+a is `A`).
+    pub key: Key,
+    /// The key without modifiers (Shift+a and Ctrl+a are both `a`).
+    pub base: Key,
+    pub location: KeyLocation,
+    pub mods: ModifiersState,
+    pub action: KeyAction,
+}
+
+impl KeyPress {
+    pub fn from_event(event: &KeyEvent, mods: ModifiersState) -> Self {
+        let action = match (event.state, event.repeat) {
+            (ElementState::Released, _) => KeyAction::Release,
+            (ElementState::Pressed, true) => KeyAction::Repeat,
+            (ElementState::Pressed, false) => KeyAction::Press,
+        };
+        Self {
+            key: composed_key(event),
+            base: event.key_without_modifiers(),
+            location: event.location,
+            mods,
+            action,
+        }
+    }
+}
+
+/// The event's logical key with any dead-key composition applied.
+///
+/// After a dead `´`, pressing `e` reports `Character("e")` as the logical key
+/// but `"é"` as the text, so text that isn't a control character wins.
+pub fn composed_key(event: &KeyEvent) -> Key {
+    match (&event.logical_key, &event.text) {
+        (Key::Character(_), Some(text)) if !text.is_empty() && !text.chars().any(char::is_control) => {
+            Key::Character(text.clone())
+        }
+        _ => event.logical_key.clone(),
+    }
+}
+
+/// Encode a key event. Returns an empty `Vec` when the key has no terminal
+/// meaning (or is a release the active mode does not report).
+pub fn encode(press: &KeyPress, mode: EncodingMode) -> Vec<u8> {
+    if mode.kitty_flags != 0 {
+        encode_kitty(press, mode)
+    } else if press.action == KeyAction::Release {
+        Vec::new()
+    } else {
+        encode_legacy(press, mode)
+    }
+}
+
+/// xterm modifier parameter; 1 means "no modifiers".
+fn modifier_param(mods: ModifiersState) -> u8 {
+    1 + mods.shift_key() as u8
+        + 2 * mods.alt_key() as u8
+        + 4 * mods.control_key() as u8
+        + 8 * mods.super_key() as u8
+}
+
+/// How a non-text key is written in CSI form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Csi {
+    /// `CSI 1;m X` (or `CSI X` / `SS3 X` unmodified).
+    Letter(u8),
+    /// `CSI n;m ~`.
+    Tilde(u8),
+}
+
+/// CSI form of cursor, editing and function keys (xterm numbering).
+fn csi_key(named: NamedKey) -> Option<Csi> {
+    Some(match named {
+        NamedKey::ArrowUp => Csi::Letter(b'A'),
+        NamedKey::ArrowDown => Csi::Letter(b'B'),
+        NamedKey::ArrowRight => Csi::Letter(b'C'),
+        NamedKey::ArrowLeft => Csi::Letter(b'D'),
+        NamedKey::Home => Csi::Letter(b'H'),
+        NamedKey::End => Csi::Letter(b'F'),
+        NamedKey::F1 => Csi::Letter(b'P'),
+        NamedKey::F2 => Csi::Letter(b'Q'),
+        NamedKey::F3 => Csi::Letter(b'R'),
+        NamedKey::F4 => Csi::Letter(b'S'),
+        NamedKey::Insert => Csi::Tilde(2),
+        NamedKey::Delete => Csi::Tilde(3),
+        NamedKey::PageUp => Csi::Tilde(5),
+        NamedKey::PageDown => Csi::Tilde(6),
+        NamedKey::F5 => Csi::Tilde(15),
+        NamedKey::F6 => Csi::Tilde(17),
+        NamedKey::F7 => Csi::Tilde(18),
+        NamedKey::F8 => Csi::Tilde(19),
+        NamedKey::F9 => Csi::Tilde(20),
+        NamedKey::F10 => Csi::Tilde(21),
+        NamedKey::F11 => Csi::Tilde(23),
+        NamedKey::F12 => Csi::Tilde(24),
+        _ => return None,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Legacy (xterm) encoding
+// ---------------------------------------------------------------------------
+
+fn encode_legacy(press: &KeyPress, mode: EncodingMode) -> Vec<u8> {
+    let mods = press.mods;
+    let m = modifier_param(mods);
+
+    if press.location == KeyLocation::Numpad && mode.app_keypad && m == 1 {
+        if let Some(byte) = keypad_ss3(&press.key) {
+            return vec![0x1b, b'O', byte];
+        }
+    }
+
+    match &press.key {
+        Key::Named(named) => {
+            if let Some(csi) = csi_key(*named) {
+                return match csi {
+                    Csi::Letter(final_byte) if m == 1 => {
+                        // SS3 for F1-F4 always, and for cursor keys in DECCKM.
+                        let ss3 = matches!(final_byte, b'P'..=b'S') || mode.app_cursor;
+                        vec![0x1b, if ss3 { b'O' } else { b'[' }, final_byte]
+                    }
+                    Csi::Letter(final_byte) => format!("\x1b[1;{}{}", m, final_byte as char).into_bytes(),
+                    Csi::Tilde(n) if m == 1 => format!("\x1b[{}~", n).into_bytes(),
+                    Csi::Tilde(n) => format!("\x1b[{};{}~", n, m).into_bytes(),
+                };
+            }
+            let bytes: &[u8] = match named {
+                NamedKey::Enter => b"\r",
+                NamedKey::Tab if mods.shift_key() => return b"\x1b[Z".to_vec(),
+                NamedKey::Tab => b"\t",
+                NamedKey::Backspace if mods.control_key() => b"\x08",
+                NamedKey::Backspace => b"\x7f",
+                NamedKey::Escape => b"\x1b",
+                NamedKey::Space if mods.control_key() => b"\x00",
+                NamedKey::Space => b" ",
+                _ => return Vec::new(),
+            };
+            with_alt(mods, bytes.to_vec())
+        }
+        Key::Character(text) => {
+            let bytes = if mods.control_key() {
+                let base = match &press.base {
+                    Key::Character(b) => b.as_str(),
+                    _ => text.as_str(),
+                };
+                ctrl_byte(base).map_or_else(|| text.as_bytes().to_vec(), |b| vec![b])
+            } else if mods.alt_key() {
+                // Alt as Meta: use the unmodified key so macOS Option
+                // compositions (Option+b = ∫) don't leak through.
+                match &press.base {
+                    Key::Character(b) if mods.shift_key() => b.to_uppercase().into_bytes(),
+                    Key::Character(b) => b.as_bytes().to_vec(),
+                    _ => text.as_bytes().to_vec(),
+                }
+            } else {
+                text.as_bytes().to_vec()
+            };
+            with_alt(mods, bytes)
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn with_alt(mods: ModifiersState, mut bytes: Vec<u8>) -> Vec<u8> {
+    if mods.alt_key() && !bytes.is_empty() {
+        bytes.insert(0, 0x1b);
+    }
+    bytes
+}
+
+/// C0 control for Ctrl+`key`, following xterm's table.
+fn ctrl_byte(key: &str) -> Option<u8> {
+    let mut chars = key.chars();
+    let c = chars.next()?.to_ascii_lowercase();
+    if chars.next().is_some() {
+        return None;
+    }
+    Some(match c {
+        'a'..='z' => c as u8 - b'a' + 1,
+        '@' | '2' | ' ' => 0x00,
+        '[' | '3' => 0x1b,
+        '\\' | '4' => 0x1c,
+        ']' | '5' => 0x1d,
+        '^' | '6' => 0x1e,
+        '_' | '7' | '/' | '-' => 0x1f,
+        '8' | '?' => 0x7f,
+        _ => return None,
+    })
+}
+
+/// DECKPAM final byte for a numeric-keypad key.
+fn keypad_ss3(key: &Key) -> Option<u8> {
+    match key {
+        Key::Named(NamedKey::Enter) => Some(b'M'),
+        Key::Character(c) => Some(match c.as_str() {
+            "0" => b'p',
+            "1" => b'q',
+            "2" => b'r',
+            "3" => b's',
+            "4" => b't',
+            "5" => b'u',
+            "6" => b'v',
+            "7" => b'w',
+            "8" => b'x',
+            "9" => b'y',
+            "." => b'n',
+            "+" => b'k',
+            "-" => b'm',
+            "*" => b'j',
+            "/" => b'o',
+            "=" => b'X',
+            _ => return None,
+        }),
+        _ => None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Kitty keyboard protocol
+// ---------------------------------------------------------------------------
+
+fn encode_kitty(press: &KeyPress, mode: EncodingMode) -> Vec<u8> {
+    let flags = mode.kitty_flags;
+    let all_keys = flags & kitty::REPORT_ALL_KEYS != 0;
+    let event_types = flags & kitty::REPORT_EVENT_TYPES != 0;
+    let mods = press.mods;
+    let m = modifier_param(mods);
+
+    if press.action == KeyAction::Release && !event_types {
+        return Vec::new();
+    }
+    let event = match press.action {
+        KeyAction::Press => 1,
+        KeyAction::Repeat if event_types => 2,
+        KeyAction::Repeat => 1,
+        KeyAction::Release => 3,
+    };
+
+    match &press.key {
+        Key::Named(named) => {
+            if let Some(csi) = csi_key(*named) {
+                // F3 moves to `CSI 13~` in kitty (`CSI R` is a cursor report).
+                let csi = if *named == NamedKey::F3 { Csi::Tilde(13) } else { csi };
+                if m == 1 && event == 1 && !all_keys {
+                    return encode_legacy(press, EncodingMode { kitty_flags: 0, ..mode });
+                }
+                let params = kitty_mod_field(m, event);
+                return match csi {
+                    // The leading 1 is omitted when there is nothing after it.
+                    Csi::Letter(final_byte) if params.is_empty() => vec![0x1b, b'[', final_byte],
+                    Csi::Letter(final_byte) => format!("\x1b[1{}{}", params, final_byte as char).into_bytes(),
+                    Csi::Tilde(n) => format!("\x1b[{}{}~", n, params).into_bytes(),
+                };
+            }
+            let code = match named {
+                NamedKey::Escape => 27,
+                NamedKey::Enter => 13,
+                NamedKey::Tab => 9,
+                NamedKey::Backspace => 127,
+                NamedKey::Space => 32,
+                _ => return Vec::new(),
+            };
+            // Enter, Tab and Backspace keep their legacy bytes unless
+            // modified or every key is being reported; they never report
+            // release without REPORT_ALL_KEYS.
+            let legacy_ok = !all_keys && (m == 1 || (code == 32 && m == 2));
+            if *named != NamedKey::Escape && legacy_ok {
+                if press.action == KeyAction::Release {
+                    return Vec::new();
+                }
+                return encode_legacy(press, EncodingMode { kitty_flags: 0, ..mode });
+            }
+            kitty_csi_u(code, None, m, event, None)
+        }
+        Key::Character(text) => {
+            let base = match &press.base {
+                Key::Character(b) => b.as_str(),
+                _ => text.as_str(),
+            };
+            let Some(code) = base.chars().next().map(|c| c.to_lowercase().next().unwrap_or(c) as u32) else {
+                return Vec::new();
+            };
+            // Plain or shifted text stays text unless every key is reported.
+            let text_only = !mods.control_key() && !mods.alt_key() && !mods.super_key();
+            if text_only && !all_keys {
+                if press.action == KeyAction::Release {
+                    return Vec::new();
+                }
+                return text.as_bytes().to_vec();
+            }
+            let shifted = (flags & kitty::REPORT_ALTERNATE_KEYS != 0 && mods.shift_key())
+                .then(|| text.chars().next().map(|c| c as u32))
+                .flatten()
+                .filter(|&s| s != code);
+            let assoc = (all_keys && flags & kitty::REPORT_TEXT != 0 && text_only && press.action != KeyAction::Release)
+                .then_some(text.as_str());
+            kitty_csi_u(code, shifted, m, event, assoc)
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// `;mods[:event]`, or empty when both are defaults.
+fn kitty_mod_field(m: u8, event: u8) -> String {
+    match (m, event) {
+        (1, 1) => String::new(),
+        (m, 1) => format!(";{}", m),
+        (m, e) => format!(";{}:{}", m, e),
+    }
+}
+
+fn kitty_csi_u(code: u32, shifted: Option<u32>, m: u8, event: u8, text: Option<&str>) -> Vec<u8> {
+    let mut out = format!("\x1b[{}", code);
+    if let Some(s) = shifted {
+        out.push_str(&format!(":{}", s));
+    }
+    let mod_field = kitty_mod_field(m, event);
+    match text {
+        Some(t) => {
+            let mod_field = if mod_field.is_empty() { ";1".to_string() } else { mod_field };
+            let cps: Vec<String> = t.chars().map(|c| (c as u32).to_string()).collect();
+            out.push_str(&format!("{};{}", mod_field, cps.join(":")));
+        }
+        None => out.push_str(&mod_field),
+    }
+    out.push('u');
+    out.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONE: ModifiersState = ModifiersState::empty();
+    const SHIFT: ModifiersState = ModifiersState::SHIFT;
+    const CTRL: ModifiersState = ModifiersState::CONTROL;
+    const ALT: ModifiersState = ModifiersState::ALT;
+
+    fn ch(s: &str) -> Key {
+        Key::Character(s.into())
+    }
+
+    fn named(n: NamedKey) -> Key {
+        Key::Named(n)
+    }
+
+    fn press(key: Key, base: Key, mods: ModifiersState) -> KeyPress {
+        KeyPress { key, base, location: KeyLocation::Standard, mods, action: KeyAction::Press }
+    }
+
+    /// (description, key, unmodified key, modifiers, expected bytes)
+    type Case = (&'static str, Key, Key, ModifiersState, &'static [u8]);
+
+    fn run(cases: &[Case], mode: EncodingMode) {
+        for (desc, key, base, mods, expected) in cases {
+            let got = encode(&press(key.clone(), base.clone(), *mods), mode);
+            assert_eq!(got, expected.to_vec(), "{}: got {:?}", desc, String::from_utf8_lossy(&got));
+        }
+    }
+
+    #[test]
+    fn legacy_text_and_controls() {
+        let cases: &[Case] = &[
+            ("a", ch("a"), ch("a"), NONE, b"a"),
+            ("Shift+a", ch("A"), ch("a"), SHIFT, b"A"),
+            ("Ctrl+a", ch("a"), ch("a"), CTRL, b"\x01"),
+            ("Ctrl+Shift+a", ch("A"), ch("a"), CTRL | SHIFT, b"\x01"),
+            ("Ctrl+z", ch("z"), ch("z"), CTRL, b"\x1a"),
+            ("Ctrl+[", ch("["), ch("["), CTRL, b"\x1b"),
+            ("Ctrl+\\", ch("\\"), ch("\\"), CTRL, b"\x1c"),
+            ("Ctrl+]", ch("]"), ch("]"), CTRL, b"\x1d"),
+            ("Ctrl+@", ch("@"), ch("2"), CTRL | SHIFT, b"\x00"),
+            ("Ctrl+/", ch("/"), ch("/"), CTRL, b"\x1f"),
+            ("Alt+b", ch("b"), ch("b"), ALT, b"\x1bb"),
+            ("Option+b on macOS", ch("∫"), ch("b"), ALT, b"\x1bb"),
+            ("Alt+Shift+b", ch("B"), ch("b"), ALT | SHIFT, b"\x1bB"),
+            ("Ctrl+Alt+a", ch("a"), ch("a"), CTRL | ALT, b"\x1b\x01"),
+            ("é passes through", ch("é"), ch("é"), NONE, "é".as_bytes()),
+        ];
+        run(cases, EncodingMode::default());
+    }
+
+    #[test]
+    fn legacy_named_keys() {
+        let cases: &[Case] = &[
+            ("Enter", named(NamedKey::Enter), named(NamedKey::Enter), NONE, b"\r"),
+            ("Alt+Enter", named(NamedKey::Enter), named(NamedKey::Enter), ALT, b"\x1b\r"),
+            ("Tab", named(NamedKey::Tab), named(NamedKey::Tab), NONE, b"\t"),
+            ("Shift+Tab", named(NamedKey::Tab), named(NamedKey::Tab), SHIFT, b"\x1b[Z"),
+            ("Backspace", named(NamedKey::Backspace), named(NamedKey::Backspace), NONE, b"\x7f"),
+            ("Ctrl+Backspace", named(NamedKey::Backspace), named(NamedKey::Backspace), CTRL, b"\x08"),
+            ("Alt+Backspace", named(NamedKey::Backspace), named(NamedKey::Backspace), ALT, b"\x1b\x7f"),
+            ("Escape", named(NamedKey::Escape), named(NamedKey::Escape), NONE, b"\x1b"),
+            ("Space", named(NamedKey::Space), named(NamedKey::Space), NONE, b" "),
+            ("Ctrl+Space", named(NamedKey::Space), named(NamedKey::Space), CTRL, b"\x00"),
+            ("Up", named(NamedKey::ArrowUp), named(NamedKey::ArrowUp), NONE, b"\x1b[A"),
+            ("Shift+Up", named(NamedKey::ArrowUp), named(NamedKey::ArrowUp), SHIFT, b"\x1b[1;2A"),
+            ("Alt+Left", named(NamedKey::ArrowLeft), named(NamedKey::ArrowLeft), ALT, b"\x1b[1;3D"),
+            ("Ctrl+Right", named(NamedKey::ArrowRight), named(NamedKey::ArrowRight), CTRL, b"\x1b[1;5C"),
+            ("Ctrl+Shift+Down", named(NamedKey::ArrowDown), named(NamedKey::ArrowDown), CTRL | SHIFT, b"\x1b[1;6B"),
+            ("Home", named(NamedKey::Home), named(NamedKey::Home), NONE, b"\x1b[H"),
+            ("Ctrl+End", named(NamedKey::End), named(NamedKey::End), CTRL, b"\x1b[1;5F"),
+            ("Insert", named(NamedKey::Insert), named(NamedKey::Insert), NONE, b"\x1b[2~"),
+            ("Delete", named(NamedKey::Delete), named(NamedKey::Delete), NONE, b"\x1b[3~"),
+            ("Shift+Delete", named(NamedKey::Delete), named(NamedKey::Delete), SHIFT, b"\x1b[3;2~"),
+            ("Alt+PageUp", named(NamedKey::PageUp), named(NamedKey::PageUp), ALT, b"\x1b[5;3~"),
+            ("PageDown", named(NamedKey::PageDown), named(NamedKey::PageDown), NONE, b"\x1b[6~"),
+            ("F1", named(NamedKey::F1), named(NamedKey::F1), NONE, b"\x1bOP"),
+            ("Shift+F1", named(NamedKey::F1), named(NamedKey::F1), SHIFT, b"\x1b[1;2P"),
+            ("F4", named(NamedKey::F4), named(NamedKey::F4), NONE, b"\x1bOS"),
+            ("F5", named(NamedKey::F5), named(NamedKey::F5), NONE, b"\x1b[15~"),
+            ("Ctrl+F5", named(NamedKey::F5), named(NamedKey::F5), CTRL, b"\x1b[15;5~"),
+            ("F12", named(NamedKey::F12), named(NamedKey::F12), NONE, b"\x1b[24~"),
+            ("Ctrl+Alt+F12", named(NamedKey::F12), named(NamedKey::F12), CTRL | ALT, b"\x1b[24;7~"),
+        ];
+        run(cases, EncodingMode::default());
+    }
+
+    #[test]
+    fn legacy_application_cursor_mode() {
+        let mode = EncodingMode { app_cursor: true, ..EncodingMode::default() };
+        let cases: &[Case] = &[
+            ("Up", named(NamedKey::ArrowUp), named(NamedKey::ArrowUp), NONE, b"\x1bOA"),
+            ("Home", named(NamedKey::Home), named(NamedKey::Home), NONE, b"\x1bOH"),
+            // Modified keys use CSI regardless of DECCKM.
+            ("Ctrl+Up", named(NamedKey::ArrowUp), named(NamedKey::ArrowUp), CTRL, b"\x1b[1;5A"),
+        ];
+        run(cases, mode);
+    }
+
+    #[test]
+    fn legacy_application_keypad_mode() {
+        let keypad = |key: Key| KeyPress {
+            base: key.clone(),
+            key,
+            location: KeyLocation::Numpad,
+            mods: NONE,
+            action: KeyAction::Press,
+        };
+        let app = EncodingMode { app_keypad: true, ..EncodingMode::default() };
+        assert_eq!(encode(&keypad(ch("5")), app), b"\x1bOu");
+        assert_eq!(encode(&keypad(ch("+")), app), b"\x1bOk");
+        assert_eq!(encode(&keypad(named(NamedKey::Enter)), app), b"\x1bOM");
+        // Numeric mode: the plain character.
+        assert_eq!(encode(&keypad(ch("5")), EncodingMode::default()), b"5");
+        // Main-row digits are unaffected by DECKPAM.
+        assert_eq!(encode(&press(ch("5"), ch("5"), NONE), app), b"5");
+    }
+
+    #[test]
+    fn legacy_ignores_releases() {
+        let mut p = press(ch("a"), ch("a"), NONE);
+        p.action = KeyAction::Release;
+        assert!(encode(&p, EncodingMode::default()).is_empty());
+    }
+
+    #[test]
+    fn kitty_disambiguate() {
+        let mode = EncodingMode { kitty_flags: kitty::DISAMBIGUATE, ..EncodingMode::default() };
+        let cases: &[Case] = &[
+            ("a stays text", ch("a"), ch("a"), NONE, b"a"),
+            ("Shift+a stays text", ch("A"), ch("a"), SHIFT, b"A"),
+            ("Ctrl+a", ch("a"), ch("a"), CTRL, b"\x1b[97;5u"),
+            ("Alt+b", ch("b"), ch("b"), ALT, b"\x1b[98;3u"),
+            ("Ctrl+Shift+a", ch("A"), ch("a"), CTRL | SHIFT, b"\x1b[97;6u"),
+            ("Escape", named(NamedKey::Escape), named(NamedKey::Escape), NONE, b"\x1b[27u"),
+            ("Enter", named(NamedKey::Enter), named(NamedKey::Enter), NONE, b"\r"),
+            ("Shift+Enter", named(NamedKey::Enter), named(NamedKey::Enter), SHIFT, b"\x1b[13;2u"),
+            ("Shift+Tab", named(NamedKey::Tab), named(NamedKey::Tab), SHIFT, b"\x1b[9;2u"),
+            ("Ctrl+Backspace", named(NamedKey::Backspace), named(NamedKey::Backspace), CTRL, b"\x1b[127;5u"),
+            ("Up", named(NamedKey::ArrowUp), named(NamedKey::ArrowUp), NONE, b"\x1b[A"),
+            ("Alt+Left", named(NamedKey::ArrowLeft), named(NamedKey::ArrowLeft), ALT, b"\x1b[1;3D"),
+            ("F3", named(NamedKey::F3), named(NamedKey::F3), NONE, b"\x1bOR"),
+            ("Ctrl+F3", named(NamedKey::F3), named(NamedKey::F3), CTRL, b"\x1b[13;5~"),
+            ("Ctrl+PageUp", named(NamedKey::PageUp), named(NamedKey::PageUp), CTRL, b"\x1b[5;5~"),
+        ];
+        run(cases, mode);
+    }
+
+    #[test]
+    fn kitty_event_types() {
+        let mode = EncodingMode {
+            kitty_flags: kitty::DISAMBIGUATE | kitty::REPORT_EVENT_TYPES,
+            ..EncodingMode::default()
+        };
+        let mut p = press(ch("a"), ch("a"), CTRL);
+        p.action = KeyAction::Repeat;
+        assert_eq!(encode(&p, mode), b"\x1b[97;5:2u");
+        p.action = KeyAction::Release;
+        assert_eq!(encode(&p, mode), b"\x1b[97;5:3u");
+
+        let mut up = press(named(NamedKey::ArrowUp), named(NamedKey::ArrowUp), NONE);
+        up.action = KeyAction::Release;
+        assert_eq!(encode(&up, mode), b"\x1b[1;1:3A");
+
+        // Plain text and Enter never report release without REPORT_ALL_KEYS.
+        let mut text = press(ch("a"), ch("a"), NONE);
+        text.action = KeyAction::Release;
+        assert!(encode(&text, mode).is_empty());
+        let mut enter = press(named(NamedKey::Enter), named(NamedKey::Enter), NONE);
+        enter.action = KeyAction::Release;
+        assert!(encode(&enter, mode).is_empty());
+    }
+
+    #[test]
+    fn kitty_all_keys_alternates_and_text() {
+        let all = EncodingMode {
+            kitty_flags: kitty::DISAMBIGUATE | kitty::REPORT_ALL_KEYS,
+            ..EncodingMode::default()
+        };
+        let cases: &[Case] = &[
+            ("a", ch("a"), ch("a"), NONE, b"\x1b[97u"),
+            ("Shift+a", ch("A"), ch("a"), SHIFT, b"\x1b[97;2u"),
+            ("Enter", named(NamedKey::Enter), named(NamedKey::Enter), NONE, b"\x1b[13u"),
+            ("Up", named(NamedKey::ArrowUp), named(NamedKey::ArrowUp), NONE, b"\x1b[A"),
+            ("F5", named(NamedKey::F5), named(NamedKey::F5), NONE, b"\x1b[15~"),
+        ];
+        run(cases, all);
+
+        let alternates = EncodingMode {
+            kitty_flags: kitty::DISAMBIGUATE | kitty::REPORT_ALL_KEYS | kitty::REPORT_ALTERNATE_KEYS,
+            ..EncodingMode::default()
+        };
+        run(&[("Shift+a", ch("A"), ch("a"), SHIFT, b"\x1b[97:65;2u")], alternates);
+
+        let text = EncodingMode {
+            kitty_flags: kitty::DISAMBIGUATE | kitty::REPORT_ALL_KEYS | kitty::REPORT_TEXT,
+            ..EncodingMode::default()
+        };
+        run(
+            &[
+                ("a", ch("a"), ch("a"), NONE, b"\x1b[97;1;97u"),
+                ("Shift+a", ch("A"), ch("a"), SHIFT, b"\x1b[97;2;65u"),
+                ("Ctrl+a has no text", ch("a"), ch("a"), CTRL, b"\x1b[97;5u"),
+            ],
+            text,
+        );
+    }
+}
@@ -0,0 +1,534 @@
+//! User-configurable keybindings.
+//!
+//! A `Keymap` maps key chords (modifiers + one key) to `CommandId`s. It starts
+//! from per-platform defaults — Cmd-based on macOS, Ctrl-based elsewhere,
+//! since Super usually belongs to the window manager on Linux — and then
+//! applies `~/.config/bisque-computer/keybindings.toml`:
+//!
+//! ```toml
+//! [bindings]
+//! "Mod+K" = "command_palette"        # Mod = Cmd on macOS, Ctrl elsewhere
+//! "Ctrl+Alt+Right" = "next_screen"
+//! "Cmd+D" = "none"                   # remove a default binding
+//! ```
+//!
+//! Action names are the `action` identifiers in `commands::COMMANDS`.
+//! Problems (unparsable chords, unknown actions, two actions on one chord,
+//! user bindings shadowing a default) are reported as warnings at load time;
+//! the rest of the file still applies.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::PathBuf;
+
+use tracing::{info, warn};
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use crate::commands::{self, CommandId};
+
+// ---------------------------------------------------------------------------
+// Chords
+// ---------------------------------------------------------------------------
+
+/// The key part of a chord. Characters are stored lowercase and unshifted.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ChordKey {
+    Named(NamedKey),
+    Char(String),
+}
+
+/// A key plus the exact set of modifiers that must be held.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Chord {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    /// Cmd on macOS, Super/Windows key elsewhere.
+    pub super_key: bool,
+    pub key: ChordKey,
+}
+
+/// Named keys accepted in chord strings: (spelling, key). The first spelling
+/// for each key is the one used for display.
+const NAMED_KEYS: &[(&str, NamedKey)] = &[
+    ("Esc", NamedKey::Escape),
+    ("Escape", NamedKey::Escape),
+    ("Enter", NamedKey::Enter),
+    ("Return", NamedKey::Enter),
+    ("Tab", NamedKey::Tab),
+    ("Space", NamedKey::Space),
+    ("Backspace", NamedKey::Backspace),
+    ("Delete", NamedKey::Delete),
+    ("Insert", NamedKey::Insert),
+    ("Left", NamedKey::ArrowLeft),
+    ("Right", NamedKey::ArrowRight),
+    ("Up", NamedKey::ArrowUp),
+    ("Down", NamedKey::ArrowDown),
+    ("Home", NamedKey::Home),
+    ("End", NamedKey::End),
+    ("PageUp", NamedKey::PageUp),
+    ("PageDown", NamedKey::PageDown),
+    ("F1", NamedKey::F1),
+    ("F2", NamedKey::F2),
+    ("F3", NamedKey::F3),
+    ("F4", NamedKey::F4),
+    ("F5", NamedKey::F5),
+    ("F6", NamedKey::F6),
+    ("F7", NamedKey::F7),
+    ("F8", NamedKey::F8),
+    ("F9", NamedKey::F9),
+    ("F10", NamedKey::F10),
+    ("F11", NamedKey::F11),
+    ("F12", NamedKey::F12),
+];
+
+impl Chord {
+    /// Parse a chord such as `"Cmd+Shift+D"`, `"Ctrl+="`, `"Mod++"` or `"Esc"`.
+    ///
+    /// Modifier names are case-insensitive: `Cmd`/`Command`/`Super`/`Meta`/`Win`,
+    /// `Ctrl`/`Control`, `Alt`/`Option`/`Opt`, `Shift`, and `Mod` (the
+    /// platform's primary modifier).
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        // "Mod++" binds the plus key itself.
+        let (mods_part, key_part) = match s.strip_suffix("++") {
+            Some(rest) => (rest, "+"),
+            None => s.rsplit_once('+').unwrap_or(("", s)),
+        };
+        if key_part.is_empty() {
+            return Err(format!("`{}` has no key", s));
+        }
+
+        let mut chord = Chord {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            super_key: false,
+            key: parse_key(key_part).ok_or_else(|| format!("unknown key `{}` in `{}`", key_part, s))?,
+        };
+        for m in mods_part.split('+').filter(|m| !m.is_empty()) {
+            match m.to_ascii_lowercase().as_str() {
+                "cmd" | "command" | "super" | "meta" | "win" => chord.super_key = true,
+                "ctrl" | "control" => chord.ctrl = true,
+                "alt" | "option" | "opt" => chord.alt = true,
+                "shift" => chord.shift = true,
+                "mod" => {
+                    if cfg!(target_os = "macos") {
+                        chord.super_key = true;
+                    } else {
+                        chord.ctrl = true;
+                    }
+                }
+                other => return Err(format!("unknown modifier `{}` in `{}`", other, s)),
+            }
+        }
+        Ok(chord)
+    }
+
+    /// Build the chord for a key press.
+    ///
+    /// `key` should be the key *without* modifiers applied (so Shift+] is
+    /// `]`, not `}`), which is what chord strings describe.
+    pub fn from_key(key: &Key, mods: ModifiersState) -> Option<Self> {
+        let key = match key {
+            Key::Named(named) => ChordKey::Named(*named),
+            Key::Character(c) => ChordKey::Char(c.to_lowercase()),
+            _ => return None,
+        };
+        Some(Chord {
+            ctrl: mods.control_key(),
+            alt: mods.alt_key(),
+            shift: mods.shift_key(),
+            super_key: mods.super_key(),
+            key,
+        })
+    }
+}
+
+fn parse_key(s: &str) -> Option<ChordKey> {
+    if let Some((_, named)) = NAMED_KEYS.iter().find(|(name, _)| name.eq_ignore_ascii_case(s)) {
+        return Some(ChordKey::Named(*named));
+    }
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(ChordKey::Char(c.to_lowercase().collect())),
+        _ => None,
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let super_name = if cfg!(target_os = "macos") { "Cmd" } else { "Super" };
+        for (held, name) in [
+            (self.ctrl, "Ctrl"),
+            (self.alt, "Alt"),
+            (self.shift, "Shift"),
+            (self.super_key, super_name),
+        ] {
+            if held {
+                write!(f, "{}+", name)?;
+            }
+        }
+        match &self.key {
+            ChordKey::Named(named) => {
+                let name = NAMED_KEYS
+                    .iter()
+                    .find(|(_, k)| k == named)
+                    .map_or("?", |(name, _)| *name);
+                f.write_str(name)
+            }
+            ChordKey::Char(c) => f.write_str(&c.to_uppercase()),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Defaults
+// ---------------------------------------------------------------------------
+
+/// macOS: Cmd-based, matching the platform's conventions.
+#[cfg(target_os = "macos")]
+const DEFAULT_BINDINGS: &[(&str, CommandId)] = &[
+    ("Cmd+K", CommandId::CommandPalette),
+    ("Cmd+Right", CommandId::NextScreen),
+    ("Cmd+Left", CommandId::PrevScreen),
+    ("Cmd+D", CommandId::SplitVertical),
+    ("Cmd+Shift+D", CommandId::SplitHorizontal),
+    ("Cmd+Alt+D", CommandId::SplitVerticalWith),
+    ("Cmd+Alt+Shift+D", CommandId::SplitHorizontalWith),
+    ("Cmd+Alt+P", CommandId::OpenProject),
+    ("Cmd+W", CommandId::ClosePane),
+    ("Cmd+]", CommandId::FocusNextPane),
+    ("Cmd+[", CommandId::FocusPrevPane),
+    ("Cmd+Alt+Left", CommandId::FocusPaneLeft),
+    ("Cmd+Alt+Right", CommandId::FocusPaneRight),
+    ("Cmd+Alt+Up", CommandId::FocusPaneUp),
+    ("Cmd+Alt+Down", CommandId::FocusPaneDown),
+    ("Cmd+Shift+]", CommandId::SwapPaneNext),
+    ("Cmd+Shift+[", CommandId::SwapPanePrev),
+    ("Cmd+Ctrl+R", CommandId::RotatePanes),
+    ("Cmd+Ctrl+Right", CommandId::GrowPaneWidth),
+    ("Cmd+Ctrl+Left", CommandId::ShrinkPaneWidth),
+    ("Cmd+Ctrl+Down", CommandId::GrowPaneHeight),
+    ("Cmd+Ctrl+Up", CommandId::ShrinkPaneHeight),
+    ("Cmd+Ctrl+=", CommandId::EqualizePanes),
+    ("Cmd+Shift+Enter", CommandId::ToggleZoomPane),
+    ("Cmd+Alt+I", CommandId::ToggleSyncInput),
+    ("Cmd+Alt+Shift+I", CommandId::ToggleSyncMark),
+    ("Cmd+T", CommandId::NewWorkspace),
+    ("Cmd+Shift+T", CommandId::NewClaudeWorkspace),
+    ("Cmd+Alt+W", CommandId::CloseWorkspace),
+    ("Ctrl+Tab", CommandId::NextWorkspace),
+    ("Ctrl+Shift+Tab", CommandId::PrevWorkspace),
+    ("Cmd+=", CommandId::IncreaseFontSize),
+    ("Cmd+Shift+=", CommandId::IncreaseFontSize),
+    ("Cmd+-", CommandId::DecreaseFontSize),
+    ("Cmd+0", CommandId::ResetFontSize),
+    ("V", CommandId::ToggleVoice),
+    ("Cmd+Shift+I", CommandId::ToggleDesignRepl),
+    ("Cmd+C", CommandId::CopySelection),
+    ("Cmd+V", CommandId::Paste),
+    ("Cmd+F", CommandId::Find),
+    ("Cmd+G", CommandId::FindNext),
+    ("Cmd+Shift+G", CommandId::FindPrevious),
+    ("Cmd+Shift+R", CommandId::ToggleRecording),
+    ("Cmd+Shift+Up", CommandId::JumpToPreviousCommand),
+    ("Cmd+Shift+Down", CommandId::JumpToNextCommand),
+    ("Cmd+Shift+A", CommandId::SelectLastOutput),
+    ("PageUp", CommandId::ScrollPageUp),
+    ("PageDown", CommandId::ScrollPageDown),
+    ("Esc", CommandId::Quit),
+];
+
+/// Linux and others: Ctrl-based. Keys a shell needs (Ctrl+D, Ctrl+W, Ctrl+C,
+/// Ctrl+V, ...) take Ctrl+Shift so they still reach the terminal unshifted.
+#[cfg(not(target_os = "macos"))]
+const DEFAULT_BINDINGS: &[(&str, CommandId)] = &[
+    ("Ctrl+Shift+K", CommandId::CommandPalette),
+    ("Ctrl+Shift+Right", CommandId::NextScreen),
+    ("Ctrl+Shift+Left", CommandId::PrevScreen),
+    ("Ctrl+Shift+E", CommandId::SplitVertical),
+    ("Ctrl+Shift+O", CommandId::SplitHorizontal),
+    ("Ctrl+Alt+E", CommandId::SplitVerticalWith),
+    ("Ctrl+Alt+O", CommandId::SplitHorizontalWith),
+    ("Ctrl+Alt+Shift+P", CommandId::OpenProject),
+    ("Ctrl+Shift+W", CommandId::ClosePane),
+    ("Ctrl+Shift+]", CommandId::FocusNextPane),
+    ("Ctrl+Shift+[", CommandId::FocusPrevPane),
+    ("Ctrl+Alt+Left", CommandId::FocusPaneLeft),
+    ("Ctrl+Alt+Right", CommandId::FocusPaneRight),
+    ("Ctrl+Alt+Up", CommandId::FocusPaneUp),
+    ("Ctrl+Alt+Down", CommandId::FocusPaneDown),
+    ("Ctrl+Alt+]", CommandId::SwapPaneNext),
+    ("Ctrl+Alt+[", CommandId::SwapPanePrev),
+    ("Ctrl+Alt+R", CommandId::RotatePanes),
+    ("Ctrl+Alt+Shift+Right", CommandId::GrowPaneWidth),
+    ("Ctrl+Alt+Shift+Left", CommandId::ShrinkPaneWidth),
+    ("Ctrl+Alt+Shift+Down", CommandId::GrowPaneHeight),
+    ("Ctrl+Alt+Shift+Up", CommandId::ShrinkPaneHeight),
+    ("Ctrl+Alt+Shift+=", CommandId::EqualizePanes),
+    ("Ctrl+Shift+Enter", CommandId::ToggleZoomPane),
+    ("Ctrl+Alt+I", CommandId::ToggleSyncInput),
+    ("Ctrl+Alt+Shift+I", CommandId::ToggleSyncMark),
+    ("Ctrl+Shift+T", CommandId::NewWorkspace),
+    ("Ctrl+Alt+Shift+T", CommandId::NewClaudeWorkspace),
+    ("Ctrl+Alt+Shift+W", CommandId::CloseWorkspace),
+    ("Ctrl+Tab", CommandId::NextWorkspace),
+    ("Ctrl+Shift+Tab", CommandId::PrevWorkspace),
+    ("Ctrl+=", CommandId::IncreaseFontSize),
+    ("Ctrl+Shift+=", CommandId::IncreaseFontSize),
+    ("Ctrl+-", CommandId::DecreaseFontSize),
+    ("Ctrl+0", CommandId::ResetFontSize),
+    ("V", CommandId::ToggleVoice),
+    ("Ctrl+Shift+I", CommandId::ToggleDesignRepl),
+    ("Ctrl+Shift+C", CommandId::CopySelection),
+    ("Ctrl+Shift+V", CommandId::Paste),
+    ("Ctrl+Shift+F", CommandId::Find),
+    ("Ctrl+Shift+G", CommandId::FindNext),
+    ("Ctrl+Shift+H", CommandId::FindPrevious),
+    ("Ctrl+Shift+R", CommandId::ToggleRecording),
+    ("Ctrl+Shift+Up", CommandId::JumpToPreviousCommand),
+    ("Ctrl+Shift+Down", CommandId::JumpToNextCommand),
+    ("Ctrl+Shift+A", CommandId::SelectLastOutput),
+    ("PageUp", CommandId::ScrollPageUp),
+    ("PageDown", CommandId::ScrollPageDown),
+    ("Esc", CommandId::Quit),
+];
+
+// ---------------------------------------------------------------------------
+// Keymap
+// ---------------------------------------------------------------------------
+
+/// Resolved chord → command table.
+pub struct Keymap {
+    bindings: HashMap<Chord, CommandId>,
+}
+
+#[derive(serde::Deserialize, Default)]
+struct KeybindingsFile {
+    /// Chord string → action name (or `"none"` to unbind).
+    #[serde(default)]
+    bindings: BTreeMap<String, String>,
+}
+
+/// Path to `keybindings.toml`, next to `design.toml`.
+pub fn keybindings_toml_path() -> PathBuf {
+    crate::token_watcher::config_dir().join("keybindings.toml")
+}
+
+impl Keymap {
+    /// The platform defaults.
+    pub fn defaults() -> Self {
+        let bindings = DEFAULT_BINDINGS
+            .iter()
+            .map(|(chord, id)| (Chord::parse(chord).expect("default chords parse"), *id))
+            .collect();
+        Self { bindings }
+    }
+
+    /// Defaults plus the user's `keybindings.toml`, if present.
+    /// Warnings are logged; a broken file falls back to the defaults.
+    pub fn load() -> Self {
+        let path = keybindings_toml_path();
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return Self::defaults();
+        };
+        let (keymap, warnings) = Self::with_overrides(&content);
+        for w in &warnings {
+            warn!(target: "keybindings", "{}: {}", path.display(), w);
+        }
+        info!(target: "keybindings", "Loaded {} ({} bindings)", path.display(), keymap.bindings.len());
+        keymap
+    }
+
+    /// Apply a `keybindings.toml` document on top of the defaults.
+    ///
+    /// Returns the keymap and any warnings (conflicts, unknown names, bad chords).
+    pub fn with_overrides(toml_str: &str) -> (Self, Vec<String>) {
+        let mut keymap = Self::defaults();
+        let mut warnings = Vec::new();
+
+        let file: KeybindingsFile = match toml::from_str(toml_str) {
+            Ok(f) => f,
+            Err(e) => {
+                warnings.push(format!("parse error, using defaults: {}", e));
+                return (keymap, warnings);
+            }
+        };
+
+        // Chords assigned by this file so far, to catch two spellings of the
+        // same chord ("Cmd+D" / "cmd+d") bound to different actions.
+        let mut assigned: HashMap<Chord, (String, Option<CommandId>)> = HashMap::new();
+
+        for (chord_str, action) in &file.bindings {
+            let chord = match Chord::parse(chord_str) {
+                Ok(c) => c,
+                Err(e) => {
+                    warnings.push(e);
+                    continue;
+                }
+            };
+            let id = if action == "none" {
+                None
+            } else {
+                match commands::from_action(action) {
+                    Some(id) => Some(id),
+                    None => {
+                        warnings.push(format!("unknown action `{}` for `{}`", action, chord_str));
+                        continue;
+                    }
+                }
+            };
+
+            if let Some((prev_str, prev_id)) = assigned.get(&chord) {
+                if *prev_id != id {
+                    warnings.push(format!(
+                        "conflict: `{}` and `{}` are the same chord; `{}` wins",
+                        prev_str, chord_str, chord_str
+                    ));
+                }
+            } else if let (Some(default_id), Some(new_id)) = (keymap.bindings.get(&chord), id) {
+                if *default_id != new_id {
+                    warnings.push(format!(
+                        "`{}` was bound to `{}`; now bound to `{}`",
+                        chord,
+                        commands::spec(*default_id).action,
+                        commands::spec(new_id).action
+                    ));
+                }
+            }
+            assigned.insert(chord.clone(), (chord_str.clone(), id));
+
+            match id {
+                Some(id) => keymap.bindings.insert(chord, id),
+                None => keymap.bindings.remove(&chord),
+            };
+        }
+
+        (keymap, warnings)
+    }
+
+    /// The command bound to `chord`, if any.
+    pub fn lookup(&self, chord: &Chord) -> Option<CommandId> {
+        self.bindings.get(chord).copied()
+    }
+
+    /// A chord that triggers `id`, for display (the shortest spelling when
+    /// several are bound).
+    pub fn chord_for(&self, id: CommandId) -> Option<String> {
+        self.bindings
+            .iter()
+            .filter(|(_, bound)| **bound == id)
+            .map(|(chord, _)| chord.to_string())
+            .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_modifiers_and_keys() {
+        let c = Chord::parse("Cmd+Shift+D").unwrap();
+        assert!(c.super_key && c.shift && !c.ctrl && !c.alt);
+        assert_eq!(c.key, ChordKey::Char("d".into()));
+
+        let c = Chord::parse("ctrl+alt+pagedown").unwrap();
+        assert!(c.ctrl && c.alt);
+        assert_eq!(c.key, ChordKey::Named(NamedKey::PageDown));
+
+        let c = Chord::parse("Ctrl++").unwrap();
+        assert!(c.ctrl);
+        assert_eq!(c.key, ChordKey::Char("+".into()));
+
+        assert_eq!(Chord::parse("Esc").unwrap().key, ChordKey::Named(NamedKey::Escape));
+    }
+
+    #[test]
+    fn parse_rejects_garbage() {
+        assert!(Chord::parse("Hyper+K").is_err());
+        assert!(Chord::parse("Ctrl+Banana").is_err());
+        assert!(Chord::parse("Ctrl+").is_err());
+    }
+
+    #[test]
+    fn display_roundtrips_through_parse() {
+        for (s, _) in DEFAULT_BINDINGS {
+            let chord = Chord::parse(s).unwrap();
+            assert_eq!(Chord::parse(&chord.to_string()).unwrap(), chord, "{}", s);
+        }
+    }
+
+    #[test]
+    fn defaults_have_no_conflicts() {
+        let mut seen: HashMap<Chord, &str> = HashMap::new();
+        for (s, _) in DEFAULT_BINDINGS {
+            let chord = Chord::parse(s).unwrap();
+            assert!(seen.insert(chord, s).is_none(), "{} bound twice", s);
+        }
+    }
+
+    #[test]
+    fn defaults_leave_alt_arrows_to_the_shell() {
+        // Word and line movement in shells and editors.
+        let keymap = Keymap::defaults();
+        for key in ["Left", "Right", "Up", "Down"] {
+            let chord = Chord::parse(&format!("Alt+{}", key)).unwrap();
+            assert_eq!(keymap.lookup(&chord), None, "Alt+{} is bound", key);
+        }
+    }
+
+    #[test]
+    fn from_key_matches_parsed_chord() {
+        let key = Key::Character("D".into());
+        let chord = Chord::from_key(&key, ModifiersState::CONTROL | ModifiersState::SHIFT).unwrap();
+        assert_eq!(chord, Chord::parse("Ctrl+Shift+D").unwrap());
+    }
+
+    #[test]
+    fn overrides_rebind_and_unbind() {
+        let toml = r#"
+            [bindings]
+            "Ctrl+Alt+P" = "command_palette"
+            "PageUp" = "none"
+        "#;
+        let (keymap, warnings) = Keymap::with_overrides(toml);
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        let chord = Chord::parse("Ctrl+Alt+P").unwrap();
+        assert_eq!(keymap.lookup(&chord), Some(CommandId::CommandPalette));
+        assert_eq!(keymap.lookup(&Chord::parse("PageUp").unwrap()), None);
+    }
+
+    #[test]
+    fn overrides_warn_on_conflicts_and_unknowns() {
+        let toml = r#"
+            [bindings]
+            "Ctrl+Alt+X" = "split_vertical"
+            "ctrl+alt+x" = "close_pane"
+            "Esc" = "toggle_voice"
+            "Ctrl+Alt+Y" = "make_coffee"
+            "Hyper+Z" = "quit"
+        "#;
+        let (keymap, warnings) = Keymap::with_overrides(toml);
+        assert_eq!(warnings.len(), 4, "{:?}", warnings);
+        assert!(warnings.iter().any(|w| w.starts_with("conflict")));
+        assert!(warnings.iter().any(|w| w.contains("was bound to `quit`")));
+        assert!(warnings.iter().any(|w| w.contains("make_coffee")));
+        assert_eq!(keymap.lookup(&Chord::parse("Esc").unwrap()), Some(CommandId::ToggleVoice));
+    }
+
+    #[test]
+    fn broken_file_falls_back_to_defaults() {
+        let (keymap, warnings) = Keymap::with_overrides("[bindings\n");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(keymap.lookup(&Chord::parse("Esc").unwrap()), Some(CommandId::Quit));
+    }
+
+    #[test]
+    fn chord_for_prefers_shortest_spelling() {
+        let keymap = Keymap::defaults();
+        let shown = keymap.chord_for(CommandId::IncreaseFontSize).unwrap();
+        assert!(!shown.contains("Shift"), "{}", shown);
+    }
+}
@@ -17,7 +17,9 @@
 //! - Screen 1 (Info): memory events + active agents
 //! - Screen 2 (Terminal): PTY-backed terminal emulator
 //!
-//! Cmd+Right / Cmd+Left slides between screens with a spring animation.
+//! Cmd+Right / Cmd+Left (Ctrl+Shift+Right / Left on Linux) slides between
+//! screens with a spring animation. Shortcuts are configurable; see
+//! `keybindings.rs`.
 //!
 //! ## State Management
 //!
@@ -31,27 +33,45 @@
 //! Mouse events for text selection are routed through `text_selection::SelectableText`
 //! instances, backed by `parley::PlainEditor`.
 
+mod asciicast;
+mod box_drawing;
+mod color_scheme;
+mod command_palette;
+mod commands;
 mod dashboard;
 #[allow(dead_code)]
 mod design;
 mod design_repl;
+mod detail_overlay;
+mod font_fallback;
 mod info_screen;
+mod key_encoding;
+mod keybindings;
 mod logging;
+mod pane_picker;
+mod pane_profiles;
 mod pane_tree;
 mod paths;
+mod project;
 #[allow(dead_code)]
 mod protocol;
+mod scroll;
+mod session;
+mod shell_integration;
 mod state_machine;
 mod terminal;
+mod terminal_links;
+mod terminal_search;
 mod text_selection;
 mod token_watcher;
 mod voice;
+mod workspaces;
 #[allow(dead_code)]
 mod vm;
 mod ws_client;
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use statig::prelude::*;
 use statig::blocking::StateMachine;
 use tracing::{error, info, warn};
@@ -65,18 +85,22 @@ use vello::util::{RenderContext, RenderSurface};
 use vello::{AaConfig, Renderer, RendererOptions, Scene};
 use winit::application::ApplicationHandler;
 use winit::dpi::PhysicalPosition;
-use winit::event::{ElementState, KeyEvent, Modifiers, MouseButton, WindowEvent};
+use winit::event::{ElementState, Ime, KeyEvent, Modifiers, MouseButton, WindowEvent};
 use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
 use winit::keyboard::{Key, NamedKey};
-use winit::window::{Fullscreen, Window};
+use winit::platform::modifier_supplement::KeyEventExtModifierSupplement;
+use winit::window::{CursorIcon, Fullscreen, Window};
 
 use vello::wgpu;
 
 use state_machine::{AppModeEvent, AppModeMachine, VoiceEvent, VoiceMachine};
 use state_machine::voice_sm::State as VoiceState;
 use state_machine::app_mode_sm::State as AppModeState;
+use commands::CommandId;
 use design::DesignTokens;
+use keybindings::{Chord, Keymap};
 use pane_tree::PaneTree;
+use workspaces::{Workspace, Workspaces};
 use text_selection::{ParleyCtx, SelectableText};
 
 // ---------------------------------------------------------------------------
@@ -158,6 +182,34 @@ struct Args {
     /// Use Docker-isolated Claude Code containers for terminal panes
     #[arg(long)]
     docker: bool,
+
+    /// Start a fresh session instead of restoring the last one
+    #[arg(long)]
+    no_restore: bool,
+
+    /// Record every Claude (Docker) terminal pane as an asciicast file
+    #[arg(long)]
+    record_claude: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Replay an asciicast recording into a read-only terminal pane
+    Play {
+        /// Recording to replay (.cast)
+        file: PathBuf,
+
+        /// Playback speed multiplier
+        #[arg(long, default_value_t = 1.0)]
+        speed: f64,
+
+        /// Shorten pauses longer than this many seconds
+        #[arg(long)]
+        idle_limit: Option<f64>,
+    },
 }
 
 // ---------------------------------------------------------------------------
@@ -165,8 +217,10 @@ struct Args {
 // ---------------------------------------------------------------------------
 
 /// The three horizontally-arranged screens.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 enum ScreenIndex {
+    #[default]
     Dashboard = 0,
     Info = 1,
     Terminal = 2,
@@ -228,6 +282,12 @@ struct App {
     scene: Scene,
     start_time: Instant,
     windowed: bool,
+    /// Window geometry from the previous session, applied when the window
+    /// is first created.
+    saved_window: Option<session::WindowGeometry>,
+    /// Off when replaying a recording, so playback doesn't replace the
+    /// user's saved session.
+    save_session: bool,
     font_data: Option<FontData>,
     #[allow(dead_code)]
     mono_font_data: Option<FontData>,
@@ -244,6 +304,14 @@ struct App {
     parley_ctx: ParleyCtx,
     selectable_regions: Vec<SelectableText>,
 
+    // --- Dashboard drill-down ---
+    /// Clickable rows from the last dashboard render (screen coordinates).
+    dashboard_rows: Vec<dashboard::RowTarget>,
+    /// Where the left button went down on the dashboard, to tell a click
+    /// (opens the detail overlay) from a selection drag.
+    press_pos: Option<PhysicalPosition<f64>>,
+    detail_overlay: detail_overlay::DetailOverlay,
+
     // --- Setup mode ---
     setup_input: String,
 
@@ -259,13 +327,38 @@ struct App {
     /// Timestamp of the last redraw (for animation delta-time, if needed).
     last_frame: Instant,
 
+    // --- Vertical scrolling (per screen) ---
+    dashboard_scroll: scroll::ScrollState,
+    info_scroll: scroll::ScrollState,
+
     // --- Terminal ---
-    pane_tree: Option<PaneTree>,
+    /// Named pane trees on the terminal screen; one shows at a time.
+    workspaces: Workspaces,
+    /// Backend of new workspaces (`--docker` makes it Docker).
+    default_backend: pane_tree::TerminalBackend,
     /// Last cursor blink instant.
     last_blink: Instant,
+    /// `DesignTokens.terminal` as last applied to the panes.
+    terminal_tokens: Option<design::TerminalTokens>,
+    /// Whether the window has keyboard focus (unfocused: hollow cursors).
+    window_focused: bool,
+    /// The last left press only focused a pane; drop its release.
+    swallow_release: bool,
 
     // --- Design REPL ---
     design_repl: design_repl::DesignRepl,
+
+    // --- Command palette ---
+    command_palette: command_palette::CommandPalette,
+    /// Chooses the backend and profile of a new split.
+    pane_picker: pane_picker::PanePicker,
+
+    // --- Keybindings ---
+    keymap: Keymap,
+
+    // --- Text input ---
+    /// Whether IME is currently enabled on the window.
+    ime_allowed: bool,
 }
 
 impl App {
@@ -279,13 +372,21 @@ impl App {
         }
     }
 
+    /// Size of the area the active workspace's panes share: the window
+    /// below the workspace tab strip.
+    fn pane_area(&self) -> Option<(f64, f64)> {
+        let (w, h) = self.surface_size()?;
+        Some((w, (h - self.workspaces.strip_height()).max(0.0)))
+    }
+
     /// Get the offset `(x, y)` of the focused terminal pane within the window.
     fn terminal_pane_offset(&self) -> (f64, f64) {
-        if let (Some(tree), Some((w, h))) = (&self.pane_tree, self.surface_size()) {
+        let top = self.workspaces.strip_height();
+        if let (Some(tree), Some((w, h))) = (self.workspaces.active(), self.pane_area()) {
             tree.focused_rect(w, h)
-                .map_or((0.0, 0.0), |(x, y, _, _)| (x, y))
+                .map_or((0.0, top), |(x, y, _, _)| (x, y + top))
         } else {
-            (0.0, 0.0)
+            (0.0, top)
         }
     }
 
@@ -309,6 +410,15 @@ impl App {
     }
 
     fn copy_selection_to_clipboard(&self) {
+        if self.detail_overlay.is_open() {
+            if let Some(text) = self.detail_overlay.selected_text() {
+                match arboard::Clipboard::new().and_then(|mut cb| cb.set_text(text.to_string())) {
+                    Ok(()) => info!(target: "selection", "Copied {} chars to clipboard", text.len()),
+                    Err(e) => error!(target: "selection", "Clipboard write failed: {}", e),
+                }
+            }
+            return;
+        }
         for region in &self.selectable_regions {
             if let Some(text) = region.selected_text() {
                 if !text.is_empty() {
@@ -334,7 +444,15 @@ impl App {
             v
         };
 
-        let specs = dashboard::compute_selectable_regions(&instances, width, height);
+        let scale = self.tokens.read().unwrap().type_scale.base / 18.0;
+        dashboard::update_scroll(&instances, width, height, scale, &mut self.dashboard_scroll);
+        let specs = dashboard::compute_selectable_regions(
+            &instances,
+            width,
+            height,
+            scale,
+            self.dashboard_scroll.offset,
+        );
         for spec in specs {
             let region = SelectableText::new(
                 &spec.text,
@@ -347,6 +465,557 @@ impl App {
         }
     }
 
+    /// The clickable dashboard row under the mouse cursor, if any.
+    fn row_at_cursor(&self) -> Option<&dashboard::RowTarget> {
+        let p = vello::kurbo::Point::new(self.cursor_pos.x, self.cursor_pos.y);
+        self.dashboard_rows.iter().find(|row| row.rect.contains(p))
+    }
+
+    /// Lay the panes out again after their arrangement changed. Every
+    /// workspace is laid out, as the tab strip comes and goes with them.
+    fn relayout_panes(&mut self) {
+        if let Some((w, h)) = self.pane_area() {
+            for tree in self.workspaces.trees_mut() {
+                tree.resize_all(w, h);
+            }
+        }
+    }
+
+    fn set_cursor_icon(&self, icon: CursorIcon) {
+        if let RenderState::Active { window, .. } = &self.render_state {
+            window.set_cursor(icon);
+        }
+    }
+
+    /// Scroll state of the current screen, if it scrolls vertically.
+    ///
+    /// The terminal has its own scrollback and is not covered here.
+    fn current_scroll_mut(&mut self) -> Option<&mut scroll::ScrollState> {
+        match self.current_screen {
+            ScreenIndex::Dashboard => Some(&mut self.dashboard_scroll),
+            ScreenIndex::Info => Some(&mut self.info_scroll),
+            ScreenIndex::Terminal => None,
+        }
+    }
+
+    /// Snapshot of the state command availability depends on.
+    fn command_context(&self) -> commands::CommandContext {
+        commands::CommandContext {
+            screen: self.current_screen,
+            setup_mode: matches!(self.app_mode_machine.state(), AppModeState::Setup {}),
+            pane_count: self.workspaces.active().map_or(0, |t| t.pane_count()),
+            workspace_count: self.workspaces.len(),
+        }
+    }
+
+    /// Execute a registered command.
+    ///
+    /// Keybindings and the command palette both funnel through here.
+    /// Commands that are unavailable in the current context are ignored.
+    fn run_command(&mut self, event_loop: &ActiveEventLoop, id: CommandId) {
+        let ctx = self.command_context();
+        if !commands::is_available(id, &ctx) {
+            return;
+        }
+        match id {
+            CommandId::CommandPalette => self.command_palette.open(&ctx),
+            CommandId::NextScreen => self.navigate_right(),
+            CommandId::PrevScreen => self.navigate_left(),
+            CommandId::ShowDashboard => self.navigate_to(ScreenIndex::Dashboard),
+            CommandId::ShowInfo => self.navigate_to(ScreenIndex::Info),
+            CommandId::ShowTerminal => self.navigate_to(ScreenIndex::Terminal),
+            CommandId::SplitVertical | CommandId::SplitHorizontal => {
+                let direction = if id == CommandId::SplitVertical {
+                    pane_tree::SplitDirection::Vertical
+                } else {
+                    pane_tree::SplitDirection::Horizontal
+                };
+                if let (Some((w, h)), Some(tree)) = (self.pane_area(), self.workspaces.active_mut()) {
+                    tree.split_focused(direction, None, w, h);
+                }
+            }
+            CommandId::SplitVerticalWith | CommandId::SplitHorizontalWith => {
+                let direction = if id == CommandId::SplitVerticalWith {
+                    pane_tree::SplitDirection::Vertical
+                } else {
+                    pane_tree::SplitDirection::Horizontal
+                };
+                self.pane_picker.open(direction, pane_profiles::load());
+            }
+            CommandId::OpenProject => {
+                self.pane_picker.open_titled(
+                    Some("New pane for project"),
+                    pane_tree::SplitDirection::Vertical,
+                    project::load(),
+                );
+            }
+            CommandId::ClosePane => {
+                if let Some(tree) = self.workspaces.active_mut() {
+                    if !tree.close_focused() {
+                        self.workspaces.close_active();
+                    }
+                }
+                self.relayout_panes();
+            }
+            CommandId::FocusNextPane | CommandId::FocusPrevPane => {
+                if let Some(tree) = self.workspaces.active_mut() {
+                    tree.cycle_focus(id == CommandId::FocusNextPane);
+                }
+                // A zoomed tree shows the newly focused pane full size.
+                self.relayout_panes();
+            }
+            CommandId::FocusPaneLeft | CommandId::FocusPaneRight
+            | CommandId::FocusPaneUp | CommandId::FocusPaneDown => {
+                let direction = match id {
+                    CommandId::FocusPaneLeft => pane_tree::FocusDirection::Left,
+                    CommandId::FocusPaneRight => pane_tree::FocusDirection::Right,
+                    CommandId::FocusPaneUp => pane_tree::FocusDirection::Up,
+                    _ => pane_tree::FocusDirection::Down,
+                };
+                if let (Some((w, h)), Some(tree)) = (self.pane_area(), self.workspaces.active_mut()) {
+                    tree.focus_direction(direction, w, h);
+                }
+                self.relayout_panes();
+            }
+            CommandId::SwapPaneNext | CommandId::SwapPanePrev | CommandId::RotatePanes => {
+                if let Some(tree) = self.workspaces.active_mut() {
+                    match id {
+                        CommandId::RotatePanes => tree.rotate_panes(),
+                        _ => tree.swap_focused(id == CommandId::SwapPaneNext),
+                    }
+                }
+                self.relayout_panes();
+            }
+            CommandId::GrowPaneWidth | CommandId::ShrinkPaneWidth
+            | CommandId::GrowPaneHeight | CommandId::ShrinkPaneHeight => {
+                let direction = match id {
+                    CommandId::GrowPaneWidth | CommandId::ShrinkPaneWidth => pane_tree::SplitDirection::Vertical,
+                    _ => pane_tree::SplitDirection::Horizontal,
+                };
+                let grow = matches!(id, CommandId::GrowPaneWidth | CommandId::GrowPaneHeight);
+                if let (Some((w, h)), Some(tree)) = (self.pane_area(), self.workspaces.active_mut()) {
+                    tree.resize_focused(direction, grow, w, h);
+                }
+            }
+            CommandId::EqualizePanes => {
+                if let (Some((w, h)), Some(tree)) = (self.pane_area(), self.workspaces.active_mut()) {
+                    tree.equalize(w, h);
+                }
+            }
+            CommandId::ToggleZoomPane => {
+                if let Some(tree) = self.workspaces.active_mut() {
+                    tree.toggle_zoom();
+                }
+                self.relayout_panes();
+            }
+            CommandId::ToggleSyncInput => {
+                if let Some(tree) = self.workspaces.active_mut() {
+                    tree.toggle_sync_all();
+                }
+            }
+            CommandId::ToggleSyncMark => {
+                if let Some(tree) = self.workspaces.active_mut() {
+                    tree.toggle_sync_mark();
+                }
+            }
+            CommandId::NewWorkspace | CommandId::NewClaudeWorkspace => {
+                let backend = if id == CommandId::NewClaudeWorkspace {
+                    pane_tree::TerminalBackend::Docker
+                } else {
+                    self.default_backend.clone()
+                };
+                let (w, h) = self.pane_area().unwrap_or((1280.0, 800.0));
+                let workspace = Workspace {
+                    name: self.workspaces.unused_name(),
+                    backend: backend.clone(),
+                    tree: PaneTree::with_backend(w, h, backend),
+                };
+                self.workspaces.add(workspace);
+                // The new tree picks up the terminal settings on the next sync.
+                self.terminal_tokens = None;
+                self.relayout_panes();
+            }
+            CommandId::CloseWorkspace => {
+                self.workspaces.close_active();
+                self.relayout_panes();
+            }
+            CommandId::RenameWorkspace => {
+                self.workspaces.start_rename();
+                self.relayout_panes();
+            }
+            CommandId::NextWorkspace | CommandId::PrevWorkspace => {
+                self.workspaces.cycle(id == CommandId::NextWorkspace);
+            }
+            CommandId::MoveWorkspaceLeft | CommandId::MoveWorkspaceRight => {
+                self.workspaces.move_active(id == CommandId::MoveWorkspaceRight);
+            }
+            CommandId::MovePaneToNextWorkspace => {
+                if let Some((w, h)) = self.pane_area() {
+                    self.workspaces.move_focused_pane(w, h);
+                }
+                self.terminal_tokens = None;
+                self.relayout_panes();
+            }
+            CommandId::IncreaseFontSize | CommandId::DecreaseFontSize | CommandId::ResetFontSize => {
+                if let Ok(mut tokens) = self.tokens.write() {
+                    tokens.type_scale.base = match id {
+                        CommandId::IncreaseFontSize => (tokens.type_scale.base + 2.0).min(72.0),
+                        CommandId::DecreaseFontSize => (tokens.type_scale.base - 2.0).max(8.0),
+                        _ => 18.0,
+                    };
+                }
+                if self.current_screen == ScreenIndex::Terminal {
+                    if let Some(term) = self.workspaces.active_mut().and_then(|t| t.focused_mut()) {
+                        match id {
+                            CommandId::IncreaseFontSize => term.increase_font_size(),
+                            CommandId::DecreaseFontSize => term.decrease_font_size(),
+                            _ => term.reset_font_size(),
+                        }
+                    }
+                }
+            }
+            CommandId::ToggleVoice => {
+                if self.voice_enabled() {
+                    self.handle_voice_event(VoiceEvent::Disable);
+                    info!(target: "voice", "Voice input disabled");
+                } else {
+                    self.handle_voice_event(VoiceEvent::Enable);
+                    info!(target: "voice", "Voice input enabled");
+                }
+            }
+            CommandId::ToggleDesignRepl => self.design_repl.toggle(),
+            CommandId::ReconnectEndpoints => {
+                info!(target: "commands", "Reconnecting to all endpoints");
+                self.app_mode_machine.inner().outbound.reconnect_all();
+            }
+            CommandId::TakeScreenshot => self.take_screenshot(),
+            CommandId::CopySelection => {
+                if self.current_screen == ScreenIndex::Terminal {
+                    // Copy from terminal selection.
+                    if let Some(term) = self.workspaces.active().and_then(|t| t.focused()) {
+                        term.copy_selection();
+                    }
+                } else {
+                    self.copy_selection_to_clipboard();
+                }
+            }
+            CommandId::Find => {
+                if let Some(term) = self.workspaces.active_mut().and_then(|t| t.focused_mut()) {
+                    term.open_search();
+                }
+            }
+            CommandId::FindNext | CommandId::FindPrevious => {
+                if let Some(term) = self.workspaces.active_mut().and_then(|t| t.focused_mut()) {
+                    term.search_step(id == CommandId::FindNext);
+                }
+            }
+            CommandId::ToggleRecording => {
+                if let Some(tree) = self.workspaces.active_mut() {
+                    tree.toggle_focused_recording();
+                }
+            }
+            CommandId::JumpToPreviousCommand | CommandId::JumpToNextCommand => {
+                if let Some(term) = self.workspaces.active_mut().and_then(|t| t.focused_mut()) {
+                    term.jump_to_prompt(id == CommandId::JumpToPreviousCommand);
+                }
+            }
+            CommandId::SelectLastOutput => {
+                if let Some(term) = self.workspaces.active_mut().and_then(|t| t.focused_mut()) {
+                    if !term.select_last_output() {
+                        info!(target: "terminal", "No command output to select (needs OSC 133 shell integration)");
+                    }
+                }
+            }
+            CommandId::Paste => {
+                if let Some(tree) = self.workspaces.active_mut().filter(|t| t.focused().is_some()) {
+                    match arboard::Clipboard::new().and_then(|mut cb| cb.get_text()) {
+                        Ok(text) => {
+                            tree.paste(&text);
+                            info!(target: "terminal", "Pasted {} chars", text.len());
+                        }
+                        Err(e) => error!(target: "terminal", "Clipboard read failed: {}", e),
+                    }
+                }
+            }
+            CommandId::ScrollPageUp | CommandId::ScrollPageDown => {
+                let forward = id == CommandId::ScrollPageDown;
+                if self.current_screen == ScreenIndex::Dashboard && self.detail_overlay.is_open() {
+                    self.detail_overlay.page(forward);
+                } else if let Some(scroll) = self.current_scroll_mut() {
+                    scroll.page(forward);
+                }
+            }
+            CommandId::Quit => event_loop.exit(),
+        }
+        if let RenderState::Active { window, .. } = &self.render_state {
+            window.request_redraw();
+        }
+    }
+
+    /// Route a key press through the modal overlays and the keymap.
+    ///
+    /// Returns `true` when the key was consumed; otherwise it falls through
+    /// to the text-entry paths in `window_event` (setup URL, terminal PTY,
+    /// push-to-talk). Order:
+    ///
+    /// 1. The command palette, while open, takes every key; so does the
+    ///    split picker.
+    /// 2. The design REPL, while open, takes every key except the palette
+    ///    and REPL-toggle chords.
+    /// 3. Escape closes the detail overlay.
+    /// 4. Renaming a workspace takes every key except the palette chord.
+    /// 5. The focused terminal pane's find bar, while open, takes every key
+    ///    except the palette and find chords.
+    /// 6. Chords bound in the keymap run their command, if available. On the
+    ///    terminal, chords without Ctrl/Alt/Cmd are left for the PTY so that
+    ///    Esc, PageUp and plain letters reach the shell.
+    fn route_key(&mut self, event_loop: &ActiveEventLoop, event: &KeyEvent) -> bool {
+        if self.command_palette.is_active() {
+            let ctx = self.command_context();
+            match self.command_palette.handle_key(&key_encoding::composed_key(event), &ctx) {
+                command_palette::PaletteOutcome::Run(id) => self.run_command(event_loop, id),
+                _ => self.request_redraw(),
+            }
+            return true;
+        }
+        if self.pane_picker.is_active() {
+            self.picker_key(&key_encoding::composed_key(event));
+            return true;
+        }
+
+        let mods = self.modifiers.state();
+        let bound = Chord::from_key(&event.key_without_modifiers(), mods)
+            .and_then(|chord| self.keymap.lookup(&chord));
+
+        if self.design_repl.is_active() {
+            match bound {
+                Some(id @ (CommandId::CommandPalette | CommandId::ToggleDesignRepl)) => {
+                    self.run_command(event_loop, id);
+                }
+                _ => {
+                    let key = key_encoding::composed_key(event);
+                    self.design_repl.handle_key(&key, mods.control_key(), &self.tokens);
+                    self.request_redraw();
+                }
+            }
+            return true;
+        }
+
+        if event.logical_key == Key::Named(NamedKey::Escape)
+            && self.current_screen == ScreenIndex::Dashboard
+            && self.detail_overlay.is_open()
+        {
+            self.detail_overlay.close();
+            self.request_redraw();
+            return true;
+        }
+
+        if self.current_screen == ScreenIndex::Terminal && self.workspaces.is_renaming() {
+            if bound == Some(CommandId::CommandPalette) {
+                self.run_command(event_loop, CommandId::CommandPalette);
+            } else {
+                self.workspaces.rename_key(&key_encoding::composed_key(event), mods);
+                if !self.workspaces.is_renaming() {
+                    // A lone workspace's strip hides again.
+                    self.relayout_panes();
+                }
+                self.request_redraw();
+            }
+            return true;
+        }
+
+        let searching = self.current_screen == ScreenIndex::Terminal
+            && self.workspaces.active().and_then(|t| t.focused()).is_some_and(|t| t.is_searching());
+        if searching {
+            match bound {
+                Some(
+                    id @ (CommandId::CommandPalette
+                    | CommandId::Find
+                    | CommandId::FindNext
+                    | CommandId::FindPrevious),
+                ) => self.run_command(event_loop, id),
+                _ => {
+                    if let Some(term) = self.workspaces.active_mut().and_then(|t| t.focused_mut()) {
+                        term.search_key(&key_encoding::composed_key(event), mods);
+                    }
+                    self.request_redraw();
+                }
+            }
+            return true;
+        }
+
+        let Some(id) = bound else {
+            return false;
+        };
+        if self.current_screen == ScreenIndex::Terminal
+            && !(mods.control_key() || mods.alt_key() || mods.super_key())
+        {
+            return false;
+        }
+        if !commands::is_available(id, &self.command_context()) {
+            return false;
+        }
+        self.run_command(event_loop, id);
+        true
+    }
+
+    /// Handle a key in the split picker, splitting the focused pane if it
+    /// chose a profile.
+    fn picker_key(&mut self, key: &Key) {
+        if let pane_picker::PickerOutcome::Split(direction, spec) = self.pane_picker.handle_key(key) {
+            if let (Some((w, h)), Some(tree)) = (self.pane_area(), self.workspaces.active_mut()) {
+                tree.split_focused(direction, Some(spec), w, h);
+            }
+        }
+        self.request_redraw();
+    }
+
+    /// Whether something that takes typed text has focus: the palette, the
+    /// REPL, the setup URL field, or a terminal pane.
+    fn wants_text_input(&self) -> bool {
+        self.command_palette.is_active()
+            || self.design_repl.is_active()
+            || matches!(self.app_mode_machine.state(), AppModeState::Setup {})
+            || (self.current_screen == ScreenIndex::Terminal
+                && self.workspaces.active().is_some_and(|t| t.focused().is_some()))
+    }
+
+    /// Rebuild the terminal color scheme and cursor style when the
+    /// terminal tokens change (design REPL `set`, `reset`) and hand them
+    /// to every pane.
+    fn sync_terminal_tokens(&mut self) {
+        let tokens = self.tokens.read().unwrap();
+        if self.terminal_tokens.as_ref() == Some(&tokens.terminal) {
+            return;
+        }
+        let terminal = tokens.terminal.clone();
+        drop(tokens);
+        let scheme = Arc::new(color_scheme::ColorScheme::from_tokens(&terminal));
+        let cursor_style = terminal::default_cursor_style(&terminal.cursor_shape, terminal.cursor_blink);
+        let on_exit = pane_tree::OnExit::from_token(&terminal.on_exit);
+        for tree in self.workspaces.trees_mut() {
+            tree.set_color_scheme(scheme.clone());
+            tree.set_cursor_style(cursor_style);
+            tree.set_clipboard_write(terminal.clipboard_write);
+            tree.set_on_exit(on_exit);
+        }
+        self.terminal_tokens = Some(terminal);
+    }
+
+    /// Enable IME while text input has focus and keep the candidate window
+    /// next to the terminal cursor.
+    fn sync_ime(&mut self) {
+        let wants = self.wants_text_input();
+        let RenderState::Active { window, .. } = &self.render_state else { return };
+        if wants != self.ime_allowed {
+            window.set_ime_allowed(wants);
+            self.ime_allowed = wants;
+            if !wants {
+                if let Some(term) = self.workspaces.active_mut().and_then(|t| t.focused_mut()) {
+                    term.set_preedit(String::new(), None);
+                }
+            }
+        }
+        if wants && self.current_screen == ScreenIndex::Terminal {
+            let (px, py) = self.terminal_pane_offset();
+            if let Some(term) = self.workspaces.active().and_then(|t| t.focused()) {
+                let (x, y, w, h) = term.cursor_cell_rect();
+                window.set_ime_cursor_area(
+                    PhysicalPosition::new(px + x, py + y),
+                    winit::dpi::PhysicalSize::new(w, h),
+                );
+            }
+        }
+    }
+
+    /// Route IME commit text to whichever input has focus.
+    fn commit_text(&mut self, text: &str) {
+        let key = Key::Character(text.into());
+        if self.command_palette.is_active() {
+            let ctx = self.command_context();
+            self.command_palette.handle_key(&key, &ctx);
+        } else if self.pane_picker.is_active() {
+            self.picker_key(&key);
+        } else if self.design_repl.is_active() {
+            self.design_repl.handle_key(&key, false, &self.tokens);
+        } else if matches!(self.app_mode_machine.state(), AppModeState::Setup {}) {
+            self.setup_input.push_str(text);
+        } else if self.current_screen == ScreenIndex::Terminal {
+            let Some(tree) = self.workspaces.active_mut() else { return };
+            match tree.focused_mut() {
+                Some(term) if term.is_searching() => {
+                    term.set_preedit(String::new(), None);
+                    term.search_key(&key, winit::keyboard::ModifiersState::empty());
+                }
+                Some(_) => tree.commit_text(text),
+                None => {}
+            }
+        }
+    }
+
+    fn request_redraw(&self) {
+        if let RenderState::Active { window, .. } = &self.render_state {
+            window.request_redraw();
+        }
+    }
+
+    /// Current session state, for saving on exit.
+    fn session_snapshot(&self) -> session::SessionState {
+        let window = match &self.render_state {
+            RenderState::Active { window, .. } | RenderState::Suspended(Some(window)) => {
+                let size = window.inner_size();
+                let pos = window.outer_position().unwrap_or_default();
+                Some(session::WindowGeometry {
+                    x: pos.x,
+                    y: pos.y,
+                    width: size.width,
+                    height: size.height,
+                    fullscreen: window.fullscreen().is_some(),
+                })
+            }
+            RenderState::Suspended(None) => self.saved_window,
+        };
+        let (workspaces, active_workspace) = self.workspaces.snapshot();
+        session::SessionState {
+            screen: self.current_screen,
+            workspaces,
+            active_workspace,
+            window,
+            repl_history: self.design_repl.history().to_vec(),
+        }
+    }
+
+    /// Save a screenshot of the display under `<data>/screenshots/`.
+    ///
+    /// Uses the macOS `screencapture` tool; other platforms only log.
+    fn take_screenshot(&self) {
+        let Some(paths) = paths::BisquePaths::resolve() else {
+            warn!(target: "commands", "Could not resolve HOME — screenshot not saved");
+            return;
+        };
+        let dir = paths.data.join("screenshots");
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            warn!(target: "commands", "Failed to create {}: {}", dir.display(), e);
+            return;
+        }
+        let file = dir.join(format!("bisque-{}.png", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+        if cfg!(target_os = "macos") {
+            match std::process::Command::new("screencapture").arg("-x").arg(&file).spawn() {
+                Ok(_) => info!(target: "commands", "Saving screenshot to {}", file.display()),
+                Err(e) => warn!(target: "commands", "screencapture failed: {}", e),
+            }
+        } else {
+            warn!(target: "commands", "Screenshots are only supported on macOS");
+        }
+    }
+
+    /// Jump straight to `screen` (animated, like Cmd+Arrow).
+    fn navigate_to(&mut self, screen: ScreenIndex) {
+        self.current_screen = screen;
+        self.target_offset = screen.to_usize() as f64;
+        self.animating = true;
+    }
+
     /// Navigate to the next screen (Cmd+Right).
     fn navigate_right(&mut self) {
         let next = (self.current_screen.to_usize() + 1).min(ScreenIndex::COUNT - 1);
@@ -391,7 +1060,7 @@ impl ApplicationHandler for App {
 
         let window = cached_window
             .take()
-            .unwrap_or_else(|| create_window(event_loop, self.windowed));
+            .unwrap_or_else(|| create_window(event_loop, self.windowed, self.saved_window));
 
         let size = window.inner_size();
         let surface_future = self.context.create_surface(
@@ -430,335 +1099,127 @@ impl ApplicationHandler for App {
             RenderState::Active { window, .. } => window.id() == window_id,
             _ => false,
         };
-        if !is_our_window {
-            return;
-        }
-
-        match event {
-            // ----------------------------------------------------------------
-            // Window lifecycle
-            // ----------------------------------------------------------------
-            WindowEvent::CloseRequested => event_loop.exit(),
-
-            WindowEvent::Resized(size) => {
-                if let RenderState::Active {
-                    surface,
-                    valid_surface,
-                    ..
-                } = &mut self.render_state
-                {
-                    if size.width != 0 && size.height != 0 {
-                        self.context.resize_surface(surface, size.width, size.height);
-                        *valid_surface = true;
-                        // Notify terminal pane tree of new size.
-                        if let Some(tree) = &mut self.pane_tree {
-                            tree.resize_all(size.width as f64, size.height as f64);
-                        }
-                    } else {
-                        *valid_surface = false;
-                    }
-                }
-            }
-
-            // ----------------------------------------------------------------
-            // Modifier tracking
-            // ----------------------------------------------------------------
-            WindowEvent::ModifiersChanged(new_mods) => {
-                let shift_was_held = self.modifiers.state().shift_key();
-                self.modifiers = new_mods;
-                let shift_now_held = self.modifiers.state().shift_key();
-
-                if self.is_dashboard()
-                    && shift_was_held
-                    && !shift_now_held
-                    && matches!(self.voice_machine.state(), VoiceState::Recording {})
-                {
-                    self.handle_voice_event(VoiceEvent::StopRecording);
-                }
-            }
-
-            // ----------------------------------------------------------------
-            // Global hotkeys
-            // ----------------------------------------------------------------
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key: Key::Named(NamedKey::Escape),
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if self.current_screen != ScreenIndex::Terminal => event_loop.exit(),
-
-            // Escape in terminal: send ESC to PTY (handled below in the terminal
-            // keyboard routing block).
-
-            // ----------------------------------------------------------------
-            // Multi-screen navigation: Cmd+Right / Cmd+Left
-            // ----------------------------------------------------------------
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key: Key::Named(NamedKey::ArrowRight),
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if self.modifiers.state().super_key() => {
-                self.navigate_right();
-                if let RenderState::Active { window, .. } = &self.render_state {
-                    window.request_redraw();
-                }
-            }
-
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key: Key::Named(NamedKey::ArrowLeft),
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if self.modifiers.state().super_key() => {
-                self.navigate_left();
-                if let RenderState::Active { window, .. } = &self.render_state {
-                    window.request_redraw();
-                }
-            }
-
-            // ----------------------------------------------------------------
-            // Design REPL toggle: Cmd+Shift+I
-            // ----------------------------------------------------------------
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key: Key::Character(ref c),
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if (c.as_str() == "i" || c.as_str() == "I")
-                && self.modifiers.state().super_key()
-                && self.modifiers.state().shift_key() =>
-            {
-                self.design_repl.toggle();
-                if let RenderState::Active { window, .. } = &self.render_state {
-                    window.request_redraw();
-                }
-            }
-
-            // ----------------------------------------------------------------
-            // Design REPL keyboard routing (when active, captures all keys)
-            // ----------------------------------------------------------------
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        ref logical_key,
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if self.design_repl.is_active() => {
-                let ctrl = self.modifiers.state().control_key();
-                self.design_repl.handle_key(logical_key, ctrl, &self.tokens);
-                if let RenderState::Active { window, .. } = &self.render_state {
-                    window.request_redraw();
-                }
-            }
-
-            // ----------------------------------------------------------------
-            // Terminal screen: pane management (Cmd+D, Cmd+Shift+D, Cmd+W, Cmd+], Cmd+[)
-            // ----------------------------------------------------------------
-
-            // Cmd+D (no shift) — vertical split
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key: Key::Character(ref c),
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if self.current_screen == ScreenIndex::Terminal
-                && self.modifiers.state().super_key()
-                && !self.modifiers.state().shift_key()
-                && (c.as_str() == "d" || c.as_str() == "D") =>
-            {
-                if let (Some(tree), RenderState::Active { surface, .. }) = (&mut self.pane_tree, &self.render_state) {
-                    let w = surface.config.width as f64;
-                    let h = surface.config.height as f64;
-                    tree.split_focused(pane_tree::SplitDirection::Vertical, w, h);
-                }
-            }
+        if !is_our_window {
+            return;
+        }
 
-            // Cmd+Shift+D — horizontal split
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key: Key::Character(ref c),
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if self.current_screen == ScreenIndex::Terminal
-                && self.modifiers.state().super_key()
-                && self.modifiers.state().shift_key()
-                && (c.as_str() == "d" || c.as_str() == "D") =>
-            {
-                if let (Some(tree), RenderState::Active { surface, .. }) = (&mut self.pane_tree, &self.render_state) {
-                    let w = surface.config.width as f64;
-                    let h = surface.config.height as f64;
-                    tree.split_focused(pane_tree::SplitDirection::Horizontal, w, h);
-                }
+        // Keyboard shortcuts and modal overlays go through one router.
+        if let WindowEvent::KeyboardInput { event: ref key_event, .. } = event {
+            if key_event.state == ElementState::Pressed && self.route_key(event_loop, key_event) {
+                return;
             }
+        }
 
-            // Cmd+W — close focused pane
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key: Key::Character(ref c),
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if self.current_screen == ScreenIndex::Terminal
-                && self.modifiers.state().super_key()
-                && (c.as_str() == "w" || c.as_str() == "W") =>
-            {
-                if let Some(tree) = &mut self.pane_tree {
-                    if !tree.close_focused() {
-                        self.pane_tree = None;
+        match event {
+            // ----------------------------------------------------------------
+            // Window lifecycle
+            // ----------------------------------------------------------------
+            WindowEvent::CloseRequested => event_loop.exit(),
+
+            WindowEvent::Resized(size) => {
+                if let RenderState::Active {
+                    surface,
+                    valid_surface,
+                    ..
+                } = &mut self.render_state
+                {
+                    if size.width != 0 && size.height != 0 {
+                        self.context.resize_surface(surface, size.width, size.height);
+                        *valid_surface = true;
+                        // Notify the terminal pane trees of the new size.
+                        let height = (size.height as f64 - self.workspaces.strip_height()).max(0.0);
+                        for tree in self.workspaces.trees_mut() {
+                            tree.resize_all(size.width as f64, height);
+                        }
+                    } else {
+                        *valid_surface = false;
                     }
                 }
             }
 
-            // Cmd+] — cycle focus forward
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key: Key::Character(ref c),
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if self.current_screen == ScreenIndex::Terminal
-                && self.modifiers.state().super_key()
-                && c.as_str() == "]" =>
-            {
-                if let Some(tree) = &mut self.pane_tree {
-                    tree.cycle_focus(true);
-                }
+            WindowEvent::Focused(focused) => {
+                self.window_focused = focused;
+                self.request_redraw();
             }
 
-            // Cmd+[ — cycle focus backward
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key: Key::Character(ref c),
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if self.current_screen == ScreenIndex::Terminal
-                && self.modifiers.state().super_key()
-                && c.as_str() == "[" =>
-            {
-                if let Some(tree) = &mut self.pane_tree {
-                    tree.cycle_focus(false);
-                }
-            }
+            // ----------------------------------------------------------------
+            // Modifier tracking
+            // ----------------------------------------------------------------
+            WindowEvent::ModifiersChanged(new_mods) => {
+                let shift_was_held = self.modifiers.state().shift_key();
+                self.modifiers = new_mods;
+                let shift_now_held = self.modifiers.state().shift_key();
 
-            // Cmd+= — increase text size (all screens)
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key: Key::Character(ref c),
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if self.modifiers.state().super_key()
-                && (c.as_str() == "=" || c.as_str() == "+") =>
-            {
-                if let Ok(mut tokens) = self.tokens.write() {
-                    tokens.type_scale.base = (tokens.type_scale.base + 2.0).min(72.0);
-                }
-                if self.current_screen == ScreenIndex::Terminal {
-                    if let Some(tree) = &mut self.pane_tree {
-                        if let Some(term) = tree.focused_mut() {
-                            term.increase_font_size();
-                        }
-                    }
+                if self.is_dashboard()
+                    && shift_was_held
+                    && !shift_now_held
+                    && matches!(self.voice_machine.state(), VoiceState::Recording {})
+                {
+                    self.handle_voice_event(VoiceEvent::StopRecording);
                 }
             }
 
-            // Cmd+- — decrease text size (all screens)
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key: Key::Character(ref c),
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if self.modifiers.state().super_key()
-                && (c.as_str() == "-" || c.as_str() == "_") =>
-            {
-                if let Ok(mut tokens) = self.tokens.write() {
-                    tokens.type_scale.base = (tokens.type_scale.base - 2.0).max(8.0);
-                }
-                if self.current_screen == ScreenIndex::Terminal {
-                    if let Some(tree) = &mut self.pane_tree {
-                        if let Some(term) = tree.focused_mut() {
-                            term.decrease_font_size();
+            // ----------------------------------------------------------------
+            // IME: composition (dead keys, CJK input methods)
+            // ----------------------------------------------------------------
+            WindowEvent::Ime(ime) => {
+                match ime {
+                    // Composition is drawn in the terminal only; overlays
+                    // show the text once it is committed.
+                    Ime::Preedit(text, cursor)
+                        if self.current_screen == ScreenIndex::Terminal
+                            && !self.command_palette.is_active()
+                            && !self.pane_picker.is_active()
+                            && !self.design_repl.is_active() =>
+                    {
+                        if let Some(term) = self.workspaces.active_mut().and_then(|t| t.focused_mut()) {
+                            term.set_preedit(text, cursor.map(|(start, _)| start));
                         }
                     }
-                }
-            }
-
-            // Cmd+0 — reset text size to default (all screens)
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key: Key::Character(ref c),
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if self.modifiers.state().super_key()
-                && c.as_str() == "0" =>
-            {
-                if let Ok(mut tokens) = self.tokens.write() {
-                    tokens.type_scale.base = 18.0;
-                }
-                if self.current_screen == ScreenIndex::Terminal {
-                    if let Some(tree) = &mut self.pane_tree {
-                        if let Some(term) = tree.focused_mut() {
-                            term.reset_font_size();
+                    Ime::Preedit(..) => {}
+                    Ime::Commit(text) => self.commit_text(&text),
+                    Ime::Enabled => {}
+                    Ime::Disabled => {
+                        if let Some(term) = self.workspaces.active_mut().and_then(|t| t.focused_mut()) {
+                            term.set_preedit(String::new(), None);
                         }
                     }
                 }
+                self.request_redraw();
             }
 
             // ----------------------------------------------------------------
             // Terminal screen: keyboard → PTY
             // ----------------------------------------------------------------
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        ref logical_key,
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if self.current_screen == ScreenIndex::Terminal => {
-                // Cmd+Left/Right and pane management keys are consumed above,
-                // so anything reaching here that is NOT a Cmd key goes to the PTY.
-                if !self.modifiers.state().super_key() {
-                    let ctrl = self.modifiers.state().control_key();
-                    if let Some(tree) = &mut self.pane_tree {
-                        if let Some(term) = tree.focused_mut() {
-                            term.write_key(logical_key, ctrl);
+            WindowEvent::KeyboardInput { ref event, .. } if self.current_screen == ScreenIndex::Terminal => {
+                // Bound chords were consumed by `route_key`, so anything
+                // reaching here that is NOT a Cmd key goes to the PTY.
+                // Releases are only encoded when the application asked for
+                // kitty event types. Keys belong to the IME while it is
+                // composing.
+                let mods = self.modifiers.state();
+                let exited = self
+                    .workspaces
+                    .active()
+                    .and_then(|t| t.focused())
+                    .is_some_and(|term| *term.state() != terminal::PaneState::Running);
+                if exited {
+                    // Nothing reads input any more; Enter starts the command again.
+                    if event.state == ElementState::Pressed && event.logical_key == Key::Named(NamedKey::Enter) {
+                        if let Some(tree) = self.workspaces.active_mut() {
+                            tree.restart_focused();
+                        }
+                    }
+                } else if !mods.super_key() {
+                    let press = key_encoding::KeyPress::from_event(event, mods);
+                    if let Some(tree) = self.workspaces.active_mut() {
+                        // Typing shows the cursor and restarts its blink.
+                        let composing = tree.focused().is_none_or(|term| term.has_preedit());
+                        if !composing && tree.write_key(&press) {
+                            if let Some(term) = tree.focused_mut() {
+                                term.cursor_visible = true;
+                            }
+                            self.last_blink = Instant::now();
                         }
                     }
                 }
@@ -830,20 +1291,18 @@ impl ApplicationHandler for App {
                 }
             }
 
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key: Key::Character(ref c),
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if matches!(self.app_mode_machine.state(), AppModeState::Setup {}) => {
-                self.setup_input.push_str(c.as_str());
+            WindowEvent::KeyboardInput { ref event, .. }
+                if event.state == ElementState::Pressed
+                    && matches!(event.logical_key, Key::Character(_))
+                    && matches!(self.app_mode_machine.state(), AppModeState::Setup {}) =>
+            {
+                if let Key::Character(c) = key_encoding::composed_key(event) {
+                    self.setup_input.push_str(c.as_str());
+                }
             }
 
             // ----------------------------------------------------------------
-            // Dashboard mode: voice and clipboard
+            // Dashboard mode: push-to-talk
             // ----------------------------------------------------------------
 
             // Shift+Enter: push-to-talk.
@@ -871,80 +1330,6 @@ impl ApplicationHandler for App {
                 }
             }
 
-            // 'V' key: toggle voice (dashboard screen only).
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key: Key::Character(ref c),
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if (c.as_str() == "v" || c.as_str() == "V")
-                && self.is_dashboard()
-                && self.current_screen == ScreenIndex::Dashboard
-                && !self.modifiers.state().super_key()
-                && !self.modifiers.state().control_key() =>
-            {
-                if self.voice_enabled() {
-                    self.handle_voice_event(VoiceEvent::Disable);
-                    info!(target: "voice", "Voice input disabled");
-                } else {
-                    self.handle_voice_event(VoiceEvent::Enable);
-                    info!(target: "voice", "Voice input enabled");
-                }
-            }
-
-            // Cmd+V: paste into terminal.
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key: Key::Character(ref c),
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if (c.as_str() == "v" || c.as_str() == "V")
-                && self.modifiers.state().super_key()
-                && self.current_screen == ScreenIndex::Terminal =>
-            {
-                if let Some(tree) = &mut self.pane_tree {
-                    if let Some(term) = tree.focused_mut() {
-                        match arboard::Clipboard::new().and_then(|mut cb| cb.get_text()) {
-                            Ok(text) => {
-                                term.paste(&text);
-                                info!(target: "terminal", "Pasted {} chars", text.len());
-                            }
-                            Err(e) => error!(target: "terminal", "Clipboard read failed: {}", e),
-                        }
-                    }
-                }
-            }
-
-            // Cmd+C: copy selected text (all screens).
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key: Key::Character(ref c),
-                        state: ElementState::Pressed,
-                        ..
-                    },
-                ..
-            } if (c.as_str() == "c" || c.as_str() == "C")
-                && self.modifiers.state().super_key() =>
-            {
-                if self.current_screen == ScreenIndex::Terminal {
-                    // Copy from terminal selection.
-                    if let Some(tree) = &self.pane_tree {
-                        if let Some(term) = tree.focused() {
-                            term.copy_selection();
-                        }
-                    }
-                } else {
-                    self.copy_selection_to_clipboard();
-                }
-            }
-
             // 'R' key: reserved.
             WindowEvent::KeyboardInput {
                 event:
@@ -966,19 +1351,51 @@ impl ApplicationHandler for App {
                 if self.is_dashboard() && self.current_screen == ScreenIndex::Dashboard {
                     let x = position.x;
                     let y = position.y;
-                    let parley_ctx = &mut self.parley_ctx;
-                    for region in &mut self.selectable_regions {
-                        region.handle_mouse_drag(x, y, parley_ctx);
+                    if self.detail_overlay.is_open() {
+                        self.detail_overlay.handle_mouse_drag(x, y, &mut self.parley_ctx);
+                    } else {
+                        let parley_ctx = &mut self.parley_ctx;
+                        for region in &mut self.selectable_regions {
+                            region.handle_mouse_drag(x, y, parley_ctx);
+                        }
+                        let icon = if self.row_at_cursor().is_some() {
+                            CursorIcon::Pointer
+                        } else {
+                            CursorIcon::Default
+                        };
+                        self.set_cursor_icon(icon);
+                    }
+                    if let RenderState::Active { window, .. } = &self.render_state {
+                        window.request_redraw();
                     }
                 } else if self.current_screen == ScreenIndex::Terminal {
                     let (ox, oy) = self.terminal_pane_offset();
-                    if let Some(tree) = &mut self.pane_tree {
+                    let size = self.pane_area().unwrap_or_default();
+                    // Pane tree coordinates start below the tab strip.
+                    let tree_y = position.y - self.workspaces.strip_height();
+                    let mut over_link = false;
+                    let mut separator = None;
+                    if let Some(tree) = self.workspaces.active_mut() {
+                        if tree.drag_separator(position.x, tree_y, size.0, size.1) {
+                            if let RenderState::Active { window, .. } = &self.render_state {
+                                window.request_redraw();
+                            }
+                            return;
+                        }
+                        separator = tree.separator_at(position.x, tree_y, size.0, size.1).map(|(_, d)| d);
                         if let Some(term) = tree.focused_mut() {
                             // Always update hover cell for hyperlink detection.
                             term.update_hover(position.x, position.y, ox, oy);
                             term.mouse_drag(position.x, position.y, ox, oy);
+                            over_link = term.link_at_hover().is_some();
                         }
                     }
+                    self.set_cursor_icon(match separator {
+                        Some(pane_tree::SplitDirection::Vertical) => CursorIcon::ColResize,
+                        Some(pane_tree::SplitDirection::Horizontal) => CursorIcon::RowResize,
+                        None if over_link => CursorIcon::Pointer,
+                        None => CursorIcon::Default,
+                    });
                 }
             }
 
@@ -991,8 +1408,18 @@ impl ApplicationHandler for App {
             {
                 let x = self.cursor_pos.x;
                 let y = self.cursor_pos.y;
+                if self.detail_overlay.is_open() {
+                    match btn_state {
+                        ElementState::Pressed => {
+                            self.detail_overlay.handle_mouse_press(x, y, &mut self.parley_ctx);
+                        }
+                        ElementState::Released => self.detail_overlay.handle_mouse_release(),
+                    }
+                    return;
+                }
                 match btn_state {
                     ElementState::Pressed => {
+                        self.press_pos = Some(self.cursor_pos);
                         let parley_ctx = &mut self.parley_ctx;
                         for region in &mut self.selectable_regions {
                             if region.hit_test(x, y) {
@@ -1004,6 +1431,20 @@ impl ApplicationHandler for App {
                         for region in &mut self.selectable_regions {
                             region.handle_mouse_release();
                         }
+                        // A press and release in (nearly) the same spot is a
+                        // click, not a selection drag: drill into the row.
+                        let clicked = self.press_pos.take().is_some_and(|p| {
+                            (p.x - x).abs() < 4.0 && (p.y - y).abs() < 4.0
+                        });
+                        if clicked {
+                            if let Some(target) = self.row_at_cursor().map(|r| r.target.clone()) {
+                                self.detail_overlay.open(target);
+                                self.set_cursor_icon(CursorIcon::Default);
+                                if let RenderState::Active { window, .. } = &self.render_state {
+                                    window.request_redraw();
+                                }
+                            }
+                        }
                     }
                 }
             }
@@ -1016,28 +1457,56 @@ impl ApplicationHandler for App {
                 let x = self.cursor_pos.x;
                 let y = self.cursor_pos.y;
                 let (ox, oy) = self.terminal_pane_offset();
+                let (w, h) = self.pane_area().unwrap_or_default();
+                // A click on a tab shows that workspace; the rest of the
+                // strip ignores presses.
+                if btn_state == ElementState::Pressed && y < self.workspaces.strip_height() {
+                    if let Some(index) = self.workspaces.tab_at(x, y) {
+                        if self.workspaces.activate(index) {
+                            self.request_redraw();
+                        }
+                    }
+                    return;
+                }
+                // Separators are dragged, not passed to the pane.
+                let tree_y = y - self.workspaces.strip_height();
+                if let Some(tree) = self.workspaces.active_mut() {
+                    let grabbed = match btn_state {
+                        ElementState::Pressed => tree.begin_separator_drag(x, tree_y, w, h),
+                        ElementState::Released => tree.end_separator_drag(),
+                    };
+                    if grabbed {
+                        return;
+                    }
+                    // A click in another pane only focuses it.
+                    if btn_state == ElementState::Pressed && tree.focus_at(x, tree_y, w, h) {
+                        self.swallow_release = true;
+                        return;
+                    }
+                }
+                // Only the focusing press's own release clears the flag.
+                if btn_state == ElementState::Released && std::mem::take(&mut self.swallow_release) {
+                    return;
+                }
                 match btn_state {
                     ElementState::Pressed => {
-                        // Cmd+click: open hyperlink if present.
-                        if self.modifiers.state().super_key() {
-                            if let Some(tree) = &self.pane_tree {
-                                if let Some(term) = tree.focused() {
-                                    if let Some(uri) = term.hyperlink_at_hover() {
-                                        info!(target: "terminal", "Opening hyperlink: {}", uri);
-                                        let _ = std::process::Command::new("open")
-                                            .arg(&uri)
-                                            .spawn();
-                                    }
-                                }
+                        // Cmd+click (Ctrl+click off macOS): open the link under the mouse.
+                        let mods = self.modifiers.state();
+                        let link_click = if cfg!(target_os = "macos") { mods.super_key() } else { mods.control_key() };
+                        if link_click {
+                            let link = self.workspaces.active().and_then(|t| t.focused()).and_then(|term| term.link_at_hover());
+                            if let Some(link) = link {
+                                let editor = self.tokens.read().unwrap().terminal.editor.clone();
+                                terminal_links::open(&link, &editor);
                             }
-                        } else if let Some(tree) = &mut self.pane_tree {
+                        } else if let Some(tree) = self.workspaces.active_mut() {
                             if let Some(term) = tree.focused_mut() {
                                 term.mouse_press(x, y, ox, oy);
                             }
                         }
                     }
                     ElementState::Released => {
-                        if let Some(tree) = &mut self.pane_tree {
+                        if let Some(tree) = self.workspaces.active_mut() {
                             if let Some(term) = tree.focused_mut() {
                                 term.mouse_release(x, y, ox, oy);
                             }
@@ -1059,14 +1528,14 @@ impl ApplicationHandler for App {
                     }
                     winit::event::MouseScrollDelta::PixelDelta(pos) => {
                         // Approximate: divide by cell height (or a reasonable default).
-                        let cell_h = self.pane_tree.as_ref()
+                        let cell_h = self.workspaces.active()
                             .and_then(|t| t.focused().map(|term| term.cell_height as f64))
                             .unwrap_or(28.0);
                         (pos.y / cell_h).round() as i32
                     }
                 };
                 if lines != 0 {
-                    if let Some(tree) = &mut self.pane_tree {
+                    if let Some(tree) = self.workspaces.active_mut() {
                         if let Some(term) = tree.focused_mut() {
                             term.scroll(lines);
                         }
@@ -1074,15 +1543,36 @@ impl ApplicationHandler for App {
                 }
             }
 
+            // ----------------------------------------------------------------
+            // Mouse: scroll wheel / trackpad on dashboard and info screens
+            // ----------------------------------------------------------------
+            WindowEvent::MouseWheel { delta, .. } => {
+                let dy = scroll::wheel_delta_px(&delta);
+                if self.current_screen == ScreenIndex::Dashboard && self.detail_overlay.is_open() {
+                    self.detail_overlay.scroll_by(dy);
+                } else if let Some(scroll) = self.current_scroll_mut() {
+                    scroll.scroll_by(dy);
+                }
+                if let RenderState::Active { window, .. } = &self.render_state {
+                    window.request_redraw();
+                }
+            }
+
             // ----------------------------------------------------------------
             // Render
             // ----------------------------------------------------------------
             WindowEvent::RedrawRequested => {
                 self.poll_transcription();
+                self.sync_ime();
+                self.sync_terminal_tokens();
 
-                // Drain PTY output before rendering.
-                if let Some(tree) = &mut self.pane_tree {
-                    tree.drain_all_output();
+                // Drain PTY output before rendering, then apply the
+                // on-exit behaviour to panes whose process has ended.
+                if self.workspaces.drain_all_output() {
+                    self.relayout_panes();
+                }
+                if let Some(tree) = self.workspaces.active_mut() {
+                    tree.refresh_searches();
                 }
 
                 // Read cursor blink interval from tokens, then drop the lock
@@ -1092,7 +1582,7 @@ impl ApplicationHandler for App {
                 // Cursor blink (focused pane only).
                 let blink_elapsed = self.last_blink.elapsed();
                 if blink_elapsed >= std::time::Duration::from_millis(cursor_blink_ms) {
-                    if let Some(tree) = &mut self.pane_tree {
+                    if let Some(tree) = self.workspaces.active_mut() {
                         if let Some(term) = tree.focused_mut() {
                             term.cursor_visible = !term.cursor_visible;
                         }
@@ -1183,8 +1673,14 @@ impl ApplicationHandler for App {
                             &voice_ui_state,
                             voice_enabled,
                             &tokens,
+                            &mut self.dashboard_scroll,
+                            &mut self.dashboard_rows,
                         );
-                        // Render text selection overlays.
+                        // Render text selection overlays and the hover cue
+                        // through the same clip as the scrolled panels, so
+                        // nothing spills over the title row.
+                        let viewport = dashboard::content_viewport(width, height);
+                        scroll::push_clip(&mut dashboard_scene, &viewport);
                         let selection_color = vello::peniko::Color::new([0.2_f32, 0.5, 1.0, 0.35]);
                         let text_color = vello::peniko::Color::new([0.0_f32, 0.0, 0.0, 1.0]);
                         let parley_ctx = &mut self.parley_ctx;
@@ -1197,6 +1693,25 @@ impl ApplicationHandler for App {
                                 true, // highlights only — dashboard draws the text
                             );
                         }
+                        let p = vello::kurbo::Point::new(self.cursor_pos.x, self.cursor_pos.y);
+                        let hovered = self.dashboard_rows.iter().find(|r| r.rect.contains(p));
+                        if let Some(row) = hovered.filter(|_| !self.detail_overlay.is_open()) {
+                            dashboard::draw_row_hover(&mut dashboard_scene, &row.rect);
+                        }
+                        dashboard_scene.pop_layer();
+
+                        // Drill-down: the detail overlay when a row has been
+                        // clicked, over everything.
+                        if self.detail_overlay.is_open() {
+                            self.detail_overlay.render(
+                                &mut dashboard_scene,
+                                width,
+                                height,
+                                instances,
+                                self.font_data.as_ref(),
+                                parley_ctx,
+                            );
+                        }
                     }
                     self.scene.append(
                         &dashboard_scene,
@@ -1214,6 +1729,7 @@ impl ApplicationHandler for App {
                             instances,
                             self.font_data.as_ref(),
                             &tokens,
+                            &mut self.info_scroll,
                         );
                     }
                     self.scene.append(
@@ -1223,12 +1739,14 @@ impl ApplicationHandler for App {
 
                     // --- Screen 2: Terminal ---
                     let mut terminal_scene = Scene::new();
-                    if let Some(tree) = &self.pane_tree {
-                        tree.render_into_scene(&mut terminal_scene, 0.0, 0.0, width, height);
+                    let top = self.workspaces.strip_height();
+                    if let Some(tree) = self.workspaces.active() {
+                        tree.render_into_scene(&mut terminal_scene, 0.0, top, width, height - top, self.window_focused);
                     } else {
                         // No terminal panes; show a placeholder.
                         render_terminal_placeholder(&mut terminal_scene, width, height, self.font_data.as_ref());
                     }
+                    self.workspaces.render_strip(&mut terminal_scene, width, self.font_data.as_ref());
                     self.scene.append(
                         &terminal_scene,
                         Some(Affine::translate((2.0 * width - offset_px, 0.0))),
@@ -1240,6 +1758,11 @@ impl ApplicationHandler for App {
                     self.design_repl.render(&mut self.scene, width, height, &tokens);
                 }
 
+                self.pane_picker.render(&mut self.scene, width, height, self.font_data.as_ref(), &tokens);
+
+                // Command palette sits above the REPL so it is always reachable.
+                self.command_palette.render(&mut self.scene, width, height, self.font_data.as_ref(), &tokens, &self.keymap);
+
                 // Release the design tokens read lock before GPU submission.
                 drop(tokens);
 
@@ -1315,6 +1838,20 @@ impl ApplicationHandler for App {
             window.request_redraw();
         }
     }
+
+    fn exiting(&mut self, _event_loop: &ActiveEventLoop) {
+        if self.save_session {
+            self.session_snapshot().save();
+        }
+        // Give project panes their last backup before the process ends.
+        // Each pane cleans up on a thread of its own, so the wait is that
+        // of the slowest; the window goes first so quitting looks done.
+        if let RenderState::Active { window, .. } | RenderState::Suspended(Some(window)) = &self.render_state {
+            window.set_visible(false);
+        }
+        self.workspaces.close_all_panes();
+        pane_tree::wait_for_cleanup(std::time::Duration::from_secs(8));
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -1427,17 +1964,53 @@ fn main() -> Result<()> {
         initialized.into()
     };
 
-    // Spawn terminal pane tree. Use Docker backend when --docker is set,
-    // otherwise fall back to a local PTY shell.
-    let pane_tree = if args.docker {
+    // `play` replays a recording into a read-only pane and leaves the saved
+    // session alone.
+    let playback = match &args.command {
+        Some(Command::Play { file, speed, idle_limit }) => {
+            let cast = asciicast::Cast::load(file).map_err(|e| anyhow::anyhow!("Cannot play {}", e))?;
+            info!("Replaying {} ({}x{})", file.display(), cast.header.width, cast.header.height);
+            let (cols, rows) = (cast.header.width, cast.header.height);
+            let output = asciicast::spawn_player(cast, *speed, *idle_limit);
+            Some(terminal::TerminalPane::playback(output, cols, rows))
+        }
+        None => None,
+    };
+    let save_session = playback.is_none();
+
+    let session = if args.no_restore || !save_session { None } else { session::SessionState::load() };
+
+    // Spawn the terminal workspaces: the previous session's if there are
+    // any (a session from before workspaces has one pane layout), otherwise
+    // one workspace with a single pane. New panes use the Docker backend
+    // when --docker is set, otherwise a local PTY shell.
+    let backend = if args.docker {
         info!("Terminal: using Docker backend");
-        PaneTree::with_backend(1280.0, 800.0, pane_tree::TerminalBackend::Docker)
+        pane_tree::TerminalBackend::Docker
     } else {
         info!("Terminal: using local PTY backend");
-        PaneTree::new(1280.0, 800.0)
+        pane_tree::TerminalBackend::Local
     };
-    if pane_tree.is_some() {
-        info!("Terminal: pane tree spawned successfully");
+    let restored = match (playback, &session) {
+        (Some(term), _) => Some(Workspaces::new(Workspace {
+            name: "Playback".into(),
+            backend: pane_tree::TerminalBackend::Local,
+            tree: Some(PaneTree::playback(term)),
+        })),
+        (None, Some(s)) => Workspaces::restore(&s.workspaces, s.active_workspace, 1280.0, 800.0),
+        (None, None) => None,
+    };
+    let mut workspaces = restored.unwrap_or_else(|| Workspaces::new(Workspace {
+        name: workspaces::default_name(1),
+        backend: backend.clone(),
+        tree: PaneTree::with_backend(1280.0, 800.0, backend.clone()),
+    }));
+    if args.record_claude {
+        info!("Terminal: recording Claude panes");
+        workspaces.set_record_claude(true);
+    }
+    if workspaces.active().is_some() {
+        info!("Terminal: {} workspace(s) spawned", workspaces.len());
     } else {
         warn!("Terminal: failed to spawn terminal — screen will show placeholder");
     }
@@ -1460,6 +2033,17 @@ fn main() -> Result<()> {
 
     let now = Instant::now();
 
+    let session = session.unwrap_or_default();
+    let start_screen = if !save_session {
+        ScreenIndex::Terminal
+    } else if start_in_setup {
+        ScreenIndex::Dashboard
+    } else {
+        session.screen
+    };
+    let mut design_repl = design_repl::DesignRepl::new();
+    design_repl.set_history(session.repl_history);
+
     let mut app = App {
         tokens: Arc::new(RwLock::new(DesignTokens::default())),
         context: RenderContext::new(),
@@ -1468,6 +2052,8 @@ fn main() -> Result<()> {
         scene: Scene::new(),
         start_time: now,
         windowed: args.windowed,
+        saved_window: session.window,
+        save_session,
         font_data,
         mono_font_data,
         voice_machine,
@@ -1476,15 +2062,28 @@ fn main() -> Result<()> {
         cursor_pos: PhysicalPosition::default(),
         parley_ctx: ParleyCtx::new(),
         selectable_regions: Vec::new(),
+        dashboard_rows: Vec::new(),
+        press_pos: None,
+        detail_overlay: detail_overlay::DetailOverlay::new(),
         setup_input: String::new(),
-        current_screen: ScreenIndex::Dashboard,
-        screen_offset: 0.0,
-        target_offset: 0.0,
+        current_screen: start_screen,
+        screen_offset: start_screen.to_usize() as f64,
+        target_offset: start_screen.to_usize() as f64,
         animating: false,
         last_frame: now,
-        pane_tree,
+        dashboard_scroll: scroll::ScrollState::new(),
+        info_scroll: scroll::ScrollState::new(),
+        workspaces,
+        default_backend: backend,
         last_blink: now,
-        design_repl: design_repl::DesignRepl::new(),
+        terminal_tokens: None,
+        window_focused: true,
+        swallow_release: false,
+        design_repl,
+        command_palette: command_palette::CommandPalette::new(),
+        pane_picker: pane_picker::PanePicker::new(),
+        keymap: Keymap::load(),
+        ime_allowed: false,
     };
 
     let event_loop = EventLoop::new()?;
@@ -1497,11 +2096,25 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn create_window(event_loop: &ActiveEventLoop, windowed: bool) -> Arc<Window> {
+/// Create the main window.
+///
+/// Fullscreen unless `--windowed` was passed or the last session ended
+/// windowed; a windowed session gets its saved size and position back.
+fn create_window(
+    event_loop: &ActiveEventLoop,
+    windowed: bool,
+    saved: Option<session::WindowGeometry>,
+) -> Arc<Window> {
     let mut attr = Window::default_attributes().with_title("bisque-computer | Lobster Dashboard");
+    let saved = saved.filter(|g| g.is_usable());
+    let fullscreen = !windowed && saved.is_none_or(|g| g.fullscreen);
 
-    if !windowed {
+    if fullscreen {
         attr = attr.with_fullscreen(Some(Fullscreen::Borderless(None)));
+    } else if let Some(g) = saved {
+        attr = attr
+            .with_inner_size(winit::dpi::PhysicalSize::new(g.width, g.height))
+            .with_position(winit::dpi::PhysicalPosition::new(g.x, g.y));
     } else {
         attr = attr.with_inner_size(winit::dpi::LogicalSize::new(1280, 800));
     }
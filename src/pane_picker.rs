@@ -0,0 +1,192 @@
+//! Split picker — choose what a new pane runs.
+//!
+//! Opened by the `split_vertical_with` / `split_horizontal_with` bindings
+//! on the terminal screen, listing `pane_profiles::load()`, and by
+//! `open_project`, listing `project::load()`. Up/Down move the
+//! selection, Enter or a digit splits the focused pane with that profile,
+//! Escape closes. Drawn like the command palette, and like it captures all
+//! keyboard input while open.
+
+use vello::Scene;
+use vello::kurbo::{Affine, Rect};
+use vello::peniko::{Color, Fill, FontData};
+use winit::keyboard::{Key, NamedKey};
+
+use crate::design::DesignTokens;
+use crate::pane_profiles::PaneProfile;
+use crate::pane_tree::{PaneSpec, SplitDirection};
+
+const TITLE_SIZE: f64 = 28.0;
+const ROW_SIZE: f64 = 20.0;
+const HINT_SIZE: f64 = 16.0;
+const COLUMN_MAX_WIDTH: f64 = 720.0;
+
+/// What the app should do after the picker handled a key.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PickerOutcome {
+    /// Key consumed; picker stays open.
+    Consumed,
+    /// Picker closed without choosing.
+    Closed,
+    /// Picker closed; split the focused pane with this spec.
+    Split(SplitDirection, PaneSpec),
+}
+
+pub struct PanePicker {
+    active: bool,
+    direction: SplitDirection,
+    /// Heading in place of the split direction's.
+    title: Option<&'static str>,
+    profiles: Vec<PaneProfile>,
+    selected: usize,
+}
+
+impl PanePicker {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            direction: SplitDirection::Vertical,
+            title: None,
+            profiles: Vec::new(),
+            selected: 0,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Open listing `profiles`, for a split in `direction`.
+    pub fn open(&mut self, direction: SplitDirection, profiles: Vec<PaneProfile>) {
+        self.open_titled(None, direction, profiles);
+    }
+
+    /// Like `open`, headed by `title` rather than the split direction.
+    pub fn open_titled(&mut self, title: Option<&'static str>, direction: SplitDirection, profiles: Vec<PaneProfile>) {
+        self.active = !profiles.is_empty();
+        self.title = title;
+        self.direction = direction;
+        self.profiles = profiles;
+        self.selected = 0;
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    fn choose(&mut self, index: usize) -> PickerOutcome {
+        match self.profiles.get(index) {
+            Some(profile) => {
+                self.close();
+                PickerOutcome::Split(self.direction, profile.spec.clone())
+            }
+            None => PickerOutcome::Consumed,
+        }
+    }
+
+    /// Handle a key press while the picker is open.
+    pub fn handle_key(&mut self, key: &Key) -> PickerOutcome {
+        let n = self.profiles.len().max(1);
+        match key {
+            Key::Named(NamedKey::Escape) => {
+                self.close();
+                PickerOutcome::Closed
+            }
+            Key::Named(NamedKey::Enter) => self.choose(self.selected),
+            Key::Named(NamedKey::ArrowDown) | Key::Named(NamedKey::Tab) => {
+                self.selected = (self.selected + 1) % n;
+                PickerOutcome::Consumed
+            }
+            Key::Named(NamedKey::ArrowUp) => {
+                self.selected = (self.selected + n - 1) % n;
+                PickerOutcome::Consumed
+            }
+            Key::Character(c) => match c.parse::<usize>() {
+                Ok(digit @ 1..=9) => self.choose(digit - 1),
+                _ => PickerOutcome::Consumed,
+            },
+            _ => PickerOutcome::Consumed,
+        }
+    }
+
+    /// Draw the picker over the terminal screen: a bisque wash, a title
+    /// naming the split, and the numbered profiles with their backend and
+    /// directory right-aligned. The selected row is in primary ink with a
+    /// hairline beneath; the rest are secondary.
+    pub fn render(&self, scene: &mut Scene, width: f64, height: f64, font_data: Option<&FontData>, tokens: &DesignTokens) {
+        if !self.active {
+            return;
+        }
+
+        let wash = Color::new([
+            tokens.background.r as f32,
+            tokens.background.g as f32,
+            tokens.background.b as f32,
+            0.95,
+        ]);
+        scene.fill(Fill::NonZero, Affine::IDENTITY, wash, None, &Rect::new(0.0, 0.0, width, height));
+
+        let primary = tokens.ink_color(tokens.ink.primary);
+        let secondary = tokens.ink_color(tokens.ink.secondary);
+        let annotation = tokens.ink_color(tokens.ink.annotation);
+        let rule = tokens.ink_color(tokens.ink.rule);
+
+        let col_w = (width - 96.0).min(COLUMN_MAX_WIDTH);
+        let x = (width - col_w) / 2.0;
+        let mut y = height * 0.22;
+
+        let title = match (self.title, self.direction) {
+            (Some(title), _) => title,
+            (None, SplitDirection::Vertical) => "Split vertically with",
+            (None, SplitDirection::Horizontal) => "Split horizontally with",
+        };
+        crate::dashboard::draw_text_pub(scene, x, y, title, primary, TITLE_SIZE, font_data);
+        y += 14.0;
+        scene.fill(Fill::NonZero, Affine::IDENTITY, rule, None, &Rect::new(x, y, x + col_w, y + 0.5));
+        y += ROW_SIZE * 1.8;
+
+        for (i, profile) in self.profiles.iter().enumerate() {
+            let is_selected = i == self.selected;
+            let color = if is_selected { primary } else { secondary };
+            if i < 9 {
+                crate::dashboard::draw_text_pub(scene, x, y, &(i + 1).to_string(), annotation, ROW_SIZE, font_data);
+            }
+            crate::dashboard::draw_text_pub(scene, x + ROW_SIZE * 1.5, y, &profile.name, color, ROW_SIZE, font_data);
+            let detail = profile.detail();
+            let dw = detail.chars().count() as f64 * HINT_SIZE * 0.6;
+            crate::dashboard::draw_text_pub(scene, x + col_w - dw, y, &detail, annotation, HINT_SIZE, font_data);
+            if is_selected {
+                let ry = y + 6.0;
+                scene.fill(Fill::NonZero, Affine::IDENTITY, primary, None, &Rect::new(x, ry, x + col_w, ry + 1.0));
+            }
+            y += ROW_SIZE * 1.6;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pane_profiles;
+    use crate::pane_tree::TerminalBackend;
+
+    #[test]
+    fn digits_and_enter_choose_a_profile() {
+        let mut picker = PanePicker::new();
+        picker.open(SplitDirection::Horizontal, pane_profiles::builtin());
+        assert_eq!(picker.handle_key(&Key::Character("7".into())), PickerOutcome::Consumed);
+        assert!(picker.is_active());
+        assert_eq!(
+            picker.handle_key(&Key::Character("2".into())),
+            PickerOutcome::Split(SplitDirection::Horizontal, PaneSpec::from(TerminalBackend::Docker)),
+        );
+        assert!(!picker.is_active());
+
+        picker.open(SplitDirection::Vertical, pane_profiles::builtin());
+        picker.handle_key(&Key::Named(NamedKey::ArrowUp));
+        assert_eq!(
+            picker.handle_key(&Key::Named(NamedKey::Enter)),
+            PickerOutcome::Split(SplitDirection::Vertical, PaneSpec::from(TerminalBackend::Vm)),
+        );
+    }
+}
@@ -0,0 +1,312 @@
+//! Pane profiles: the choices the split picker offers.
+//!
+//! The built-in ones (a local shell, Claude Code in Docker, a shell in the
+//! Lobster VM) come first, then any in `panes.toml` next to `design.toml`.
+//! A profile is a backend with its own starting directory and environment;
+//! a saved host is a machine to open an ssh shell on:
+//!
+//! ```toml
+//! [[profile]]
+//! name = "api"
+//! backend = "local"              # local, docker or vm
+//! cwd = "~/src/api"              # inside the container or VM for those
+//! env = { RUST_LOG = "debug" }
+//!
+//! [[profile]]
+//! name = "offline"
+//! backend = "docker"
+//! docker = { network = "none" }  # replaces [docker] for this profile
+//!
+//! [docker]                       # limits for every other Docker pane
+//! cpus = 2.0
+//! memory = "4g"
+//! pids = 512
+//! network = "bridge"
+//! read_only = true               # tmpfs at /tmp and the home directory
+//!
+//! [[host]]
+//! name = "Lobster host"
+//! host = "lobster.local"         # or an alias from ~/.ssh/config
+//! user = "admin"                 # optional, like port and identity
+//! identity = "~/.ssh/id_ed25519"
+//! ```
+//!
+//! The file is read each time the picker opens, so edits apply to the next
+//! split without a restart.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use tracing::warn;
+
+use crate::pane_tree::{DockerOptions, PaneSpec, TerminalBackend};
+
+/// One entry in the split picker.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaneProfile {
+    pub name: String,
+    pub spec: PaneSpec,
+}
+
+impl PaneProfile {
+    /// Secondary text for the picker: backend, then host and directory.
+    pub fn detail(&self) -> String {
+        let mut detail = self.spec.backend.label().to_string();
+        if let TerminalBackend::Ssh { host, user, .. } = &self.spec.backend {
+            detail.push_str("  ");
+            if let Some(user) = user {
+                detail.push_str(user);
+                detail.push('@');
+            }
+            detail.push_str(host);
+        }
+        if let Some(dir) = &self.spec.cwd {
+            detail.push_str("  ");
+            detail.push_str(&dir.to_string_lossy());
+        }
+        detail
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ProfilesFile {
+    #[serde(default)]
+    profile: Vec<ProfileEntry>,
+    #[serde(default)]
+    host: Vec<HostEntry>,
+    #[serde(default)]
+    docker: Option<DockerOptions>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ProfileEntry {
+    name: String,
+    backend: TerminalBackend,
+    #[serde(default)]
+    cwd: Option<String>,
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+    #[serde(default)]
+    docker: Option<DockerOptions>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct HostEntry {
+    name: String,
+    host: String,
+    #[serde(default)]
+    port: Option<u16>,
+    #[serde(default)]
+    user: Option<String>,
+    #[serde(default)]
+    identity: Option<String>,
+    #[serde(default)]
+    cwd: Option<String>,
+}
+
+/// Path to `panes.toml`, next to `design.toml`.
+pub fn panes_toml_path() -> PathBuf {
+    crate::token_watcher::config_dir().join("panes.toml")
+}
+
+/// Container settings for Docker panes whose profile has none of its own:
+/// the `[docker]` table of `panes.toml`, read when the pane starts.
+pub fn docker_defaults() -> DockerOptions {
+    let path = panes_toml_path();
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return DockerOptions::default();
+    };
+    match toml::from_str::<ProfilesFile>(&content) {
+        Ok(file) => file.docker.unwrap_or_default(),
+        Err(e) => {
+            warn!(target: "terminal", "{}: parse error, ignoring [docker]: {}", path.display(), e);
+            DockerOptions::default()
+        }
+    }
+}
+
+/// The choices that need no configuration.
+pub fn builtin() -> Vec<PaneProfile> {
+    [
+        ("Local shell", TerminalBackend::Local),
+        ("Claude Code in Docker", TerminalBackend::Docker),
+        ("Shell in the Lobster VM", TerminalBackend::Vm),
+    ]
+    .into_iter()
+    .map(|(name, backend)| PaneProfile { name: name.to_string(), spec: PaneSpec::from(backend) })
+    .collect()
+}
+
+/// The built-in profiles followed by those in `panes.toml`. A missing or
+/// unreadable file leaves the built-ins alone.
+pub fn load() -> Vec<PaneProfile> {
+    let mut profiles = builtin();
+    let path = panes_toml_path();
+    if let Ok(content) = std::fs::read_to_string(&path) {
+        let (parsed, warnings) = parse(&content);
+        for w in &warnings {
+            warn!(target: "terminal", "{}: {}", path.display(), w);
+        }
+        profiles.extend(parsed);
+    }
+    profiles
+}
+
+/// Read the profiles in a `panes.toml` document, with warnings for the
+/// entries that were skipped.
+pub fn parse(toml_str: &str) -> (Vec<PaneProfile>, Vec<String>) {
+    let file: ProfilesFile = match toml::from_str(toml_str) {
+        Ok(f) => f,
+        Err(e) => return (Vec::new(), vec![format!("parse error, ignoring profiles: {}", e)]),
+    };
+    let mut warnings = Vec::new();
+    let mut profiles: Vec<PaneProfile> = file
+        .profile
+        .into_iter()
+        .filter_map(|entry| {
+            if entry.backend == TerminalBackend::Playback {
+                warnings.push(format!("profile {:?}: playback is not a pane backend", entry.name));
+                return None;
+            }
+            // `~` only means something for a directory on this machine.
+            let cwd = entry.cwd.map(|dir| match entry.backend {
+                TerminalBackend::Local => expand_home(&dir),
+                _ => PathBuf::from(dir),
+            });
+            Some(PaneProfile {
+                spec: PaneSpec {
+                    backend: expand_identity(entry.backend),
+                    profile: Some(entry.name.clone()),
+                    cwd,
+                    env: entry.env,
+                    docker: entry.docker,
+                    project: None,
+                },
+                name: entry.name,
+            })
+        })
+        .collect();
+    profiles.extend(file.host.into_iter().map(|entry| {
+        let backend = TerminalBackend::Ssh {
+            host: entry.host,
+            port: entry.port,
+            user: entry.user,
+            identity: entry.identity.map(|path| expand_home(&path)),
+        };
+        PaneProfile {
+            spec: PaneSpec {
+                backend,
+                profile: Some(entry.name.clone()),
+                cwd: entry.cwd.map(PathBuf::from),
+                env: BTreeMap::new(),
+                docker: None,
+                project: None,
+            },
+            name: entry.name,
+        }
+    }));
+    (profiles, warnings)
+}
+
+/// An ssh key path is on this machine, so `~` is expanded there.
+fn expand_identity(backend: TerminalBackend) -> TerminalBackend {
+    match backend {
+        TerminalBackend::Ssh { host, port, user, identity } => TerminalBackend::Ssh {
+            host,
+            port,
+            user,
+            identity: identity.map(|path| expand_home(&path.to_string_lossy())),
+        },
+        other => other,
+    }
+}
+
+fn expand_home(dir: &str) -> PathBuf {
+    let home = std::env::var("HOME").ok();
+    match (dir.strip_prefix('~'), home) {
+        (Some(""), Some(home)) => PathBuf::from(home),
+        (Some(rest), Some(home)) if rest.starts_with('/') => PathBuf::from(format!("{}{}", home, rest)),
+        _ => PathBuf::from(dir),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_profiles_and_skips_bad_ones() {
+        let (profiles, warnings) = parse(
+            r#"
+            [[profile]]
+            name = "api"
+            backend = "local"
+            cwd = "/src/api"
+            env = { RUST_LOG = "debug" }
+
+            [[profile]]
+            name = "replay"
+            backend = "playback"
+
+            [[profile]]
+            name = "sandbox"
+            backend = "vm"
+            cwd = "~/work"
+            "#,
+        );
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].spec.profile.as_deref(), Some("api"));
+        assert_eq!(profiles[0].spec.env.get("RUST_LOG").map(String::as_str), Some("debug"));
+        assert_eq!(profiles[0].detail(), "local  /src/api");
+        // Remote directories are left for the VM's shell.
+        assert_eq!(profiles[1].spec.cwd, Some(PathBuf::from("~/work")));
+    }
+
+    #[test]
+    fn saved_hosts_open_ssh_panes() {
+        let (profiles, warnings) = parse(
+            r#"
+            [[host]]
+            name = "Lobster host"
+            host = "lobster.local"
+            user = "admin"
+            port = 2200
+            "#,
+        );
+        assert!(warnings.is_empty());
+        assert_eq!(profiles[0].name, "Lobster host");
+        assert_eq!(
+            profiles[0].spec.backend,
+            TerminalBackend::Ssh { host: "lobster.local".into(), port: Some(2200), user: Some("admin".into()), identity: None },
+        );
+        assert_eq!(profiles[0].detail(), "ssh  admin@lobster.local");
+    }
+
+    #[test]
+    fn docker_options_come_from_the_profile() {
+        let (profiles, warnings) = parse(
+            r#"
+            [docker]
+            memory = "4g"
+
+            [[profile]]
+            name = "offline"
+            backend = "docker"
+            docker = { network = "none", pids = 128 }
+            "#,
+        );
+        assert!(warnings.is_empty());
+        assert_eq!(
+            profiles[0].spec.docker,
+            Some(DockerOptions { network: Some("none".into()), pids: Some(128), ..Default::default() }),
+        );
+    }
+
+    #[test]
+    fn broken_file_gives_no_profiles() {
+        let (profiles, warnings) = parse("[[profile]\nname = ");
+        assert!(profiles.is_empty());
+        assert_eq!(warnings.len(), 1);
+    }
+}
@@ -1,79 +1,280 @@
 //! Binary split tree for tiling terminal panes.
 //!
-//! Provides iTerm2-style pane splitting, closing, and focus cycling.
-//! Each leaf node owns a `TerminalPane` backed by either a local PTY
-//! or a Docker container running Claude Code.
+//! Provides iTerm2-style pane splitting, closing, and focus cycling,
+//! plus spatial focus moves, click-to-focus, and swapping or rotating
+//! panes within the current layout. Typed input can be synchronized to
+//! every pane, or to marked ones, as well as the focused pane.
+//! Split ratios live in the `Split` nodes: separators can be dragged with
+//! the mouse or nudged from the keyboard, and one pane can be zoomed to
+//! fill the whole area without changing them.
+//! Each leaf node owns a `TerminalPane` and the `PaneSpec` it was
+//! started from: a local PTY, a Docker container running Claude Code or a
+//! shell in the Lobster VM, chosen per pane, with its own working
+//! directory and environment. A Docker or VM pane can instead be opened on
+//! a registered project, cloning it before Claude starts and snapshotting
+//! the work tree to a backup branch while it runs.
 
+use std::collections::BTreeMap;
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use alacritty_terminal::vte::ansi::CursorStyle;
 use tracing::{info, warn};
-use vello::kurbo::{Affine, Rect};
+use vello::kurbo::{Affine, Point, Rect};
 use vello::peniko::{Color, Fill};
 use vello::Scene;
 
-use crate::terminal::TerminalPane;
+use crate::color_scheme::ColorScheme;
+use crate::key_encoding::KeyPress;
+use crate::paths::BisquePaths;
+use crate::project::{BackupTarget, Project};
+use crate::terminal::{self, BackupStatus, PaneState, TerminalPane};
 
 /// The Dockerfile embedded in the binary at compile time.
 const DOCKERFILE: &str = include_str!("../docker/Dockerfile");
 
-/// Local image name built from the embedded Dockerfile.
+/// Local image name built from the embedded Dockerfile. It is tagged with
+/// a hash of the Dockerfile, so changing the file builds a fresh image.
 const DOCKER_IMAGE: &str = "bisque-claude-code";
 
+/// Label on every container a pane starts, for finding strays with
+/// `docker ps --filter label=...`.
+const DOCKER_LABEL: &str = "com.fullyparsed.bisque-computer";
+
+/// Docker panes started by this process, numbering their containers.
+static DOCKER_PANES: AtomicUsize = AtomicUsize::new(0);
+
+/// Set once a stand-in has found or built the image (see
+/// `image_build_pane`); later Docker panes start their container at once.
+static DOCKER_IMAGE_READY: AtomicBool = AtomicBool::new(false);
+
+/// Env-files copied to ssh hosts by this process, naming them.
+static SSH_ENV_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// Longest a pane's env-file is kept waiting for `docker run` to read it.
+const ENV_FILE_WAIT: Duration = Duration::from_secs(60);
+
+/// Seconds between a project pane's auto-commit snapshots.
+const BACKUP_INTERVAL_SECS: u32 = 30;
+/// Snapshots between pushes of the backup branch: about five minutes.
+const BACKUP_PUSH_EVERY: u32 = 10;
+/// Where a host-side backup repo is mounted in the container.
+const BACKUP_MOUNT: &str = "/bisque-backup";
+
+/// Project panes started by this process, numbering their sessions.
+static BACKUP_SESSIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Cleanup still running for closed panes (see `run_cleanup`).
+static CLEANUPS: Mutex<Vec<JoinHandle<()>>> = Mutex::new(Vec::new());
+
+/// Host port the Lobster VM's sshd is forwarded to.
+const VM_SSH_PORT: u16 = 2222;
+/// User a VM pane logs in as.
+const VM_SSH_USER: &str = "root";
+
 /// Selects how new terminal panes are created.
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TerminalBackend {
     /// Local PTY shell (existing behavior).
     Local,
     /// Docker container running Claude Code (fully isolated, no mounts).
     Docker,
+    /// Login shell in the Lobster VM, over ssh.
+    Vm,
+    /// Login shell on another machine over ssh. Fields left out fall back
+    /// to ssh's defaults and `~/.ssh/config`, so `host` may be an alias.
+    Ssh {
+        host: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        port: Option<u16>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        user: Option<String>,
+        /// Private key file (`ssh -i`).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        identity: Option<PathBuf>,
+    },
+    /// Read-only replay of a recording (`bisque-computer play`). Never
+    /// respawned: a restored layout drops these panes.
+    Playback,
 }
 
-/// Ensure the Docker image exists locally, building from the embedded
-/// Dockerfile if necessary. Returns `true` if the image is available.
-fn ensure_docker_image() -> bool {
-    // Check if image already exists.
-    let check = std::process::Command::new("docker")
-        .args(["image", "inspect", DOCKER_IMAGE])
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null())
-        .status();
+impl TerminalBackend {
+    /// Short name used in recording file names.
+    pub fn label(&self) -> &'static str {
+        match self {
+            TerminalBackend::Local => "local",
+            TerminalBackend::Docker => "docker",
+            TerminalBackend::Vm => "vm",
+            TerminalBackend::Ssh { .. } => "ssh",
+            TerminalBackend::Playback => "playback",
+        }
+    }
+}
 
-    match check {
-        Ok(status) if status.success() => {
-            info!(image = DOCKER_IMAGE, "Docker image already exists");
-            return true;
+/// What a pane runs: its backend, plus the profile it was opened from and
+/// the directory and environment it starts with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaneSpec {
+    pub backend: TerminalBackend,
+    /// Name of the `panes.toml` profile, if it came from one.
+    pub profile: Option<String>,
+    /// Starting directory: on the host for a local shell, inside the
+    /// container or VM otherwise.
+    pub cwd: Option<PathBuf>,
+    /// Variables added to the pane's environment.
+    pub env: BTreeMap<String, String>,
+    /// Container settings for a Docker pane; `None` takes the `[docker]`
+    /// table of `panes.toml`.
+    pub docker: Option<DockerOptions>,
+    /// Project cloned into the container or VM, with `claude` started in
+    /// it in place of the backend's usual program.
+    pub project: Option<Project>,
+}
+
+impl From<TerminalBackend> for PaneSpec {
+    fn from(backend: TerminalBackend) -> Self {
+        Self { backend, profile: None, cwd: None, env: BTreeMap::new(), docker: None, project: None }
+    }
+}
+
+/// Limits and isolation for a Docker pane's container. Everything unset
+/// is left to docker's defaults.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct DockerOptions {
+    /// CPUs the container may use (`--cpus`), e.g. `2.5`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpus: Option<f64>,
+    /// Memory limit (`--memory`), e.g. `"4g"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<String>,
+    /// Most processes the container may run (`--pids-limit`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pids: Option<u32>,
+    /// Network to attach to (`--network`); `"none"` cuts the container off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    /// Mount the root filesystem read-only, with tmpfs at `/tmp` and the
+    /// home directory. Claude's config volume stays writable.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+}
+
+impl PaneSpec {
+    /// Text of the pane's badge: the backend, then the project and its
+    /// branch, the profile name, or for ssh the host.
+    pub fn label(&self) -> String {
+        if let Some(project) = &self.project {
+            return format!("{} · {} · {}", self.backend.label(), project.name, project.branch);
         }
-        _ => {
-            info!(image = DOCKER_IMAGE, "Docker image not found — building");
+        match (&self.profile, &self.backend) {
+            (Some(name), _) => format!("{} · {}", self.backend.label(), name),
+            (None, TerminalBackend::Ssh { host, .. }) => format!("ssh · {}", host),
+            (None, _) => self.backend.label().to_string(),
         }
     }
+}
 
-    // Write Dockerfile to a temp directory and build.
+/// The image for this binary's Dockerfile: `bisque-claude-code:<hash>`.
+fn docker_image() -> String {
+    format!("{}:{:016x}", DOCKER_IMAGE, fnv1a(DOCKERFILE.as_bytes()))
+}
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher` it is stable across Rust
+/// releases, so an unchanged Dockerfile keeps its image tag.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, &b| (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// A stand-in for a Docker pane while its image is checked for and, if
+/// missing, built from the embedded Dockerfile: the build runs in the
+/// pane, with its output on screen, rather than on the UI thread. The
+/// pane tree swaps the stand-in for the container once it succeeds; if
+/// it fails (no Docker, a broken build) it stays, showing why.
+fn image_build_pane(width: f64, height: f64) -> Option<TerminalPane> {
+    let image = docker_image();
     let build_dir = std::env::temp_dir().join("bisque-docker-build");
-    if let Err(e) = std::fs::create_dir_all(&build_dir) {
-        warn!("Failed to create Docker build dir: {}", e);
-        return false;
-    }
-    if let Err(e) = std::fs::write(build_dir.join("Dockerfile"), DOCKERFILE) {
+    let written = std::fs::create_dir_all(&build_dir).and_then(|()| std::fs::write(build_dir.join("Dockerfile"), DOCKERFILE));
+    if let Err(e) = written {
         warn!("Failed to write Dockerfile: {}", e);
-        return false;
+        return None;
     }
+    info!(image = image.as_str(), "Preparing the Docker image");
+    let script = image_build_script(&image, &build_dir);
+    let mut pane = TerminalPane::spawn_command(width, height, "sh", &["-c", &script], &[])?;
+    pane.mark_stand_in();
+    pane.set_badge("docker · preparing image");
+    Some(pane)
+}
 
-    let build = std::process::Command::new("docker")
-        .args(["build", "-t", DOCKER_IMAGE, "."])
-        .current_dir(&build_dir)
-        .status();
+/// Shell command that succeeds at once if `image` exists, and otherwise
+/// builds it from the Dockerfile in `dir`.
+fn image_build_script(image: &str, dir: &Path) -> String {
+    format!(
+        "docker image inspect {image} >/dev/null 2>&1 && exit 0\n\
+         printf 'Building the Docker image %s\\n' {image}\n\
+         exec docker build -t {image} {dir}",
+        image = shell_quote(image),
+        dir = shell_quote(&dir.to_string_lossy()),
+    )
+}
 
-    match build {
-        Ok(status) if status.success() => {
-            info!(image = DOCKER_IMAGE, "Docker image built successfully");
-            true
-        }
-        Ok(status) => {
-            warn!(image = DOCKER_IMAGE, code = ?status.code(), "Docker build failed");
-            false
-        }
-        Err(e) => {
-            warn!("Failed to run docker build: {}", e);
-            false
+/// `OnExit::Restart` leaves a process that ran for less than this alone,
+/// so a command that fails at startup is not respawned in a loop.
+const MIN_RESTART_UPTIME: Duration = Duration::from_secs(2);
+
+/// Whether typed keys and pastes go beyond the focused pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyncInput {
+    #[default]
+    Off,
+    /// To every pane in the tree.
+    All,
+    /// To the panes marked with `toggle_sync_mark`.
+    Marked,
+}
+
+impl SyncInput {
+    /// Whether input typed in another pane reaches `term`. A pane whose
+    /// process has exited reads nothing and is skipped.
+    fn reaches(self, term: &TerminalPane) -> bool {
+        *term.state() == PaneState::Running
+            && match self {
+                SyncInput::Off => false,
+                SyncInput::All => true,
+                SyncInput::Marked => term.is_sync_marked(),
+            }
+    }
+}
+
+/// What happens to a pane when its process exits (`terminal.on_exit`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnExit {
+    /// Leave the pane open with an exit banner.
+    Keep,
+    /// Close the pane.
+    Close,
+    /// Start the pane's command again.
+    Restart,
+}
+
+impl OnExit {
+    pub fn from_token(value: &str) -> Self {
+        match value {
+            "keep" => OnExit::Keep,
+            "close" => OnExit::Close,
+            "restart" => OnExit::Restart,
+            other => {
+                warn!(target: "terminal", "Unknown on_exit {:?}, keeping exited panes open", other);
+                OnExit::Keep
+            }
         }
     }
 }
@@ -81,24 +282,46 @@ fn ensure_docker_image() -> bool {
 const MIN_PANE_WIDTH: f64 = 80.0;
 const MIN_PANE_HEIGHT: f64 = 40.0;
 const SEPARATOR_PX: f64 = 2.0;
+/// How far either side of a separator a press still grabs it.
+const SEPARATOR_GRAB_PX: f64 = 4.0;
+/// Ratio change per keyboard resize step.
+const RESIZE_STEP: f64 = 0.05;
 const SEPARATOR_COLOR: Color = Color::new([0.25, 0.25, 0.30, 1.0]);
 const FOCUS_BORDER_COLOR: Color = Color::new([0.85, 0.65, 0.20, 0.85]);
 const FOCUS_BORDER_PX: f64 = 2.0;
+/// Inset border on every pane that synchronized input reaches.
+const SYNC_BORDER_COLOR: Color = Color::new([0.80, 0.24, 0.18, 0.9]);
+const SYNC_BORDER_PX: f64 = 3.0;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SplitDirection {
     Vertical,
     Horizontal,
 }
 
+/// Where to look for a pane, relative to the focused one.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FocusChild {
     First,
     Second,
 }
 
 pub enum PaneNode {
-    Leaf(TerminalPane),
+    Leaf {
+        term: TerminalPane,
+        /// What the pane runs; recorded so the layout can be restored.
+        spec: PaneSpec,
+    },
     Split {
         direction: SplitDirection,
         ratio: f64,
@@ -107,35 +330,162 @@ pub enum PaneNode {
     },
 }
 
+/// Serializable shape of a pane tree, saved with the session.
+///
+/// Mirrors `PaneNode` without the live terminals: each leaf records its
+/// `PaneSpec` and font size.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PaneLayout {
+    Leaf {
+        backend: TerminalBackend,
+        font_size: f32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        profile: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cwd: Option<PathBuf>,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        env: BTreeMap<String, String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        docker: Option<DockerOptions>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        project: Option<Project>,
+    },
+    Split {
+        direction: SplitDirection,
+        ratio: f64,
+        first: Box<PaneLayout>,
+        second: Box<PaneLayout>,
+    },
+}
+
 /// The tree uses `Option<PaneNode>` at the root so we can take() ownership
 /// for transformations like split and close.
 pub struct PaneTree {
     root: Option<PaneNode>,
     focus_path: Vec<FocusChild>,
-    /// Backend configuration for spawning new panes.
-    backend: TerminalBackend,
+    /// Record every Claude (Docker) pane from the moment it spawns.
+    record_claude: bool,
+    /// Terminal colors, shared by every pane.
+    scheme: Arc<ColorScheme>,
+    /// Cursor style panes use until their application sets one.
+    cursor_style: CursorStyle,
+    /// Whether applications may set the clipboard (OSC 52).
+    clipboard_write: bool,
+    /// What to do with panes whose process has exited.
+    on_exit: OnExit,
+    /// The focused pane fills the whole area; the others keep their
+    /// place in the tree but are not drawn.
+    zoomed: bool,
+    /// Path of the split whose separator is being dragged.
+    dragging: Option<Vec<FocusChild>>,
+    /// Where typed input goes besides the focused pane.
+    sync_input: SyncInput,
 }
 
 impl PaneTree {
-    /// Create a new pane tree with a single terminal pane.
-    ///
-    /// Uses the `Local` backend by default (existing behavior).
-    pub fn new(width: f64, height: f64) -> Option<Self> {
-        Self::with_backend(width, height, TerminalBackend::Local)
+    /// Create a new pane tree with a single terminal pane on `backend`.
+    pub fn with_backend(width: f64, height: f64, backend: TerminalBackend) -> Option<Self> {
+        let spec = PaneSpec::from(backend);
+        let term = spawn_pane(&spec, width, height, None)?;
+        Some(Self::from_pane(term, spec))
     }
 
-    /// Create a new pane tree with a specific backend.
-    pub fn with_backend(width: f64, height: f64, backend: TerminalBackend) -> Option<Self> {
-        let pane = spawn_pane_for_backend(&backend, width, height)?;
-        Some(Self {
-            root: Some(PaneNode::Leaf(pane)),
+    /// A tree holding a single replay pane. Splits open local shells.
+    pub fn playback(mut term: TerminalPane) -> Self {
+        let spec = PaneSpec::from(TerminalBackend::Playback);
+        term.set_badge(spec.label());
+        Self::from_pane(term, spec)
+    }
+
+    /// A tree around an existing pane started from `spec`.
+    pub fn from_pane(term: TerminalPane, spec: PaneSpec) -> Self {
+        Self {
+            root: Some(PaneNode::Leaf { term, spec }),
             focus_path: Vec::new(),
-            backend,
-        })
+            record_claude: false,
+            scheme: Arc::default(),
+            cursor_style: terminal::DEFAULT_CURSOR_STYLE,
+            clipboard_write: true,
+            on_exit: OnExit::Keep,
+            zoomed: false,
+            dragging: None,
+            sync_input: SyncInput::Off,
+        }
+    }
+
+    /// Record Claude panes automatically, starting with the ones open now.
+    pub fn set_record_claude(&mut self, on: bool) {
+        self.record_claude = on;
+        if on {
+            if let Some(ref mut root) = self.root { start_claude_recordings(root); }
+        }
     }
 
-    pub fn split_focused(&mut self, direction: SplitDirection, width: f64, height: f64) {
+    /// Recolor every pane, and use `scheme` for panes opened later.
+    pub fn set_color_scheme(&mut self, scheme: Arc<ColorScheme>) {
+        if let Some(ref mut root) = self.root {
+            for_each_pane(root, &mut |term| term.set_color_scheme(scheme.clone()));
+        }
+        self.scheme = scheme;
+    }
+
+    /// Set the default cursor style of every pane, now and later.
+    pub fn set_cursor_style(&mut self, style: CursorStyle) {
+        if let Some(ref mut root) = self.root {
+            for_each_pane(root, &mut |term| term.set_cursor_style(style));
+        }
+        self.cursor_style = style;
+    }
+
+    /// Allow or refuse OSC 52 clipboard writes in every pane, now and later.
+    pub fn set_clipboard_write(&mut self, allowed: bool) {
+        if let Some(ref mut root) = self.root {
+            for_each_pane(root, &mut |term| term.set_clipboard_write(allowed));
+        }
+        self.clipboard_write = allowed;
+    }
+
+    pub fn set_on_exit(&mut self, on_exit: OnExit) {
+        self.on_exit = on_exit;
+    }
+
+    /// Apply the tree's shared settings to a pane it is about to adopt.
+    fn configure_pane(&self, pane: &mut TerminalPane) {
+        pane.set_color_scheme(self.scheme.clone());
+        pane.set_cursor_style(self.cursor_style);
+        pane.set_clipboard_write(self.clipboard_write);
+    }
+
+    /// A leaf for a pane joining the tree, configured and, for Claude
+    /// panes when asked, recording.
+    fn new_leaf(&self, mut term: TerminalPane, spec: PaneSpec) -> PaneNode {
+        self.configure_pane(&mut term);
+        let mut leaf = PaneNode::Leaf { term, spec };
+        if self.record_claude {
+            start_claude_recordings(&mut leaf);
+        }
+        leaf
+    }
+
+    /// Start or stop recording the focused pane.
+    pub fn toggle_focused_recording(&mut self) {
+        let Some(root) = self.root.as_mut() else { return };
+        if let PaneNode::Leaf { term, spec } = node_at_path_mut(root, &self.focus_path) {
+            if term.is_recording() {
+                term.stop_recording();
+            } else {
+                term.start_recording(spec.backend.label());
+            }
+        }
+    }
+
+    /// Split the focused pane, opening a pane from `spec` beside it; with
+    /// `None`, the new pane runs the same as the focused one (a replay's
+    /// splits open local shells).
+    pub fn split_focused(&mut self, direction: SplitDirection, spec: Option<PaneSpec>, width: f64, height: f64) {
         let Some(root) = self.root.take() else { return };
+        self.zoomed = false;
 
         let focused_rect = rect_at_path(&root, &self.focus_path, 0.0, 0.0, width, height);
         let (first_w, first_h, second_w, second_h) = match direction {
@@ -157,49 +507,195 @@ impl PaneTree {
             return;
         }
 
-        let new_pane = match spawn_pane_for_backend(&self.backend, second_w, second_h) {
+        // Local shells open in the directory the focused shell reported
+        // (OSC 7), unless the chosen spec names one.
+        let (reported, focused_spec) = match node_at_path(&root, &self.focus_path) {
+            PaneNode::Leaf { term, spec } => (term.cwd().map(Path::to_path_buf), Some(spec.clone())),
+            PaneNode::Split { .. } => (None, None),
+        };
+        let (spec, cwd) = match spec {
+            Some(spec) => {
+                let cwd = spec.cwd.clone().or(reported);
+                (spec, cwd)
+            }
+            None => {
+                let mut spec = focused_spec.unwrap_or_else(|| PaneSpec::from(TerminalBackend::Local));
+                if spec.backend == TerminalBackend::Playback {
+                    spec = PaneSpec::from(TerminalBackend::Local);
+                }
+                let cwd = reported.or_else(|| spec.cwd.clone());
+                (spec, cwd)
+            }
+        };
+        let new_pane = match spawn_pane(&spec, second_w, second_h, cwd.as_deref()) {
             Some(pane) => pane,
             None => {
-                warn!("Failed to spawn new terminal pane for split");
+                warn!("Failed to spawn new {} pane for split", spec.backend.label());
                 self.root = Some(root);
                 return;
             }
         };
 
-        let new_root = transform_split(root, &self.focus_path, 0, direction, new_pane, first_w, first_h);
+        let new_leaf = self.new_leaf(new_pane, spec);
+        let new_root = transform_split(root, &self.focus_path, 0, direction, new_leaf, first_w, first_h);
         self.root = Some(new_root);
         self.focus_path.push(FocusChild::Second);
     }
 
     /// Close the focused pane. Returns `false` if this was the last pane.
     pub fn close_focused(&mut self) -> bool {
-        if self.focus_path.is_empty() {
-            // Root is a single leaf — last pane.
-            return false;
+        let path = self.focus_path.clone();
+        self.close_at(&path)
+    }
+
+    /// Close the pane at `path`. Returns `false`, leaving the tree as it
+    /// is, if it is the last pane.
+    fn close_at(&mut self, path: &[FocusChild]) -> bool {
+        self.take_at(path).is_some()
+    }
+
+    /// Take the focused pane out of the tree, to move it elsewhere. The
+    /// last pane leaves the tree empty.
+    pub fn detach_focused(&mut self) -> Option<(TerminalPane, PaneSpec)> {
+        let removed = if self.focus_path.is_empty() {
+            self.root.take()
+        } else {
+            let path = self.focus_path.clone();
+            self.take_at(&path)
+        };
+        match removed? {
+            PaneNode::Leaf { mut term, spec } => {
+                term.set_sync_marked(false);
+                Some((term, spec))
+            }
+            PaneNode::Split { .. } => None,
         }
+    }
 
-        let Some(root) = self.root.take() else { return false };
-        let parent_path_len = self.focus_path.len() - 1;
-        let which_child = self.focus_path[parent_path_len];
+    /// Add an existing pane beside the focused one (or as the only pane of
+    /// an empty tree) and focus it. Follow with `resize_all`.
+    pub fn insert_pane(&mut self, term: TerminalPane, spec: PaneSpec, width: f64, height: f64) {
+        let leaf = self.new_leaf(term, spec);
+        self.zoomed = false;
+        let Some(root) = self.root.take() else {
+            self.root = Some(leaf);
+            self.focus_path.clear();
+            return;
+        };
+        let rect = rect_at_path(&root, &self.focus_path, 0.0, 0.0, width, height);
+        let ((first_w, first_h), _) = split_dims(rect.width(), rect.height(), SplitDirection::Vertical, 0.5);
+        self.root = Some(transform_split(root, &self.focus_path, 0, SplitDirection::Vertical, leaf, first_w, first_h));
+        self.focus_path.push(FocusChild::Second);
+    }
 
-        let new_root = transform_close(root, &self.focus_path, 0, which_child);
-        self.root = Some(new_root);
+    /// Remove the pane at `path` and return it; its sibling takes its
+    /// place. `None`, leaving the tree as it is, if it is the last pane.
+    fn take_at(&mut self, path: &[FocusChild]) -> Option<PaneNode> {
+        // An empty path is the root: a single leaf, the last pane.
+        let (&which_child, parent) = path.split_last()?;
+        let root = self.root.take()?;
+        let (root, removed) = transform_close(root, path, 0, which_child);
+        self.root = Some(root);
+        self.zoomed = false;
+        self.dragging = None;
 
-        // Update focus to first leaf under the replacement node.
-        self.focus_path.truncate(parent_path_len);
-        if let Some(ref root) = self.root {
-            let node = node_at_path(root, &self.focus_path);
-            descend_first_leaf(node, &mut self.focus_path);
+        if self.focus_path.starts_with(path) {
+            // Update focus to first leaf under the replacement node.
+            self.focus_path.truncate(parent.len());
+            if let Some(ref root) = self.root {
+                let node = node_at_path(root, &self.focus_path);
+                descend_first_leaf(node, &mut self.focus_path);
+            }
+        } else if self.focus_path.starts_with(parent) {
+            // Focus was in the sibling, which moved up a level.
+            self.focus_path.remove(parent.len());
         }
+        self.refresh_sync_marks();
+        removed
+    }
 
+    /// Check every pane's process and apply `on_exit` to those that have
+    /// ended. Returns `false` if the last pane was closed.
+    pub fn handle_exits(&mut self) -> bool {
+        let Some(ref mut root) = self.root else { return true };
+        let mut ended = Vec::new();
+        poll_exits(root, &mut Vec::new(), &mut ended);
+        // A stand-in that succeeded gives way to the pane it stood in for;
+        // one that failed stays to show why. `on_exit` is for real panes.
+        let (stand_ins, ended): (Vec<_>, Vec<_>) = ended.into_iter().partition(|path| {
+            self.root.as_ref().is_some_and(|root| matches!(node_at_path(root, path), PaneNode::Leaf { term, .. } if term.is_stand_in()))
+        });
+        for path in &stand_ins {
+            let succeeded = self.root.as_ref().is_some_and(|root| {
+                matches!(node_at_path(root, path), PaneNode::Leaf { term, .. } if *term.state() == PaneState::Exited(0))
+            });
+            if succeeded {
+                DOCKER_IMAGE_READY.store(true, Ordering::Relaxed);
+                self.restart_at(path, true);
+            }
+        }
+        match self.on_exit {
+            OnExit::Keep => {}
+            OnExit::Restart => {
+                for path in &ended {
+                    self.restart_at(path, false);
+                }
+            }
+            OnExit::Close => {
+                // Closing a pane moves its sibling up; look paths up afresh.
+                while let Some(path) = self.root.as_ref().and_then(|root| first_exited(root, &mut Vec::new())) {
+                    if !self.close_at(&path) {
+                        return false;
+                    }
+                }
+            }
+        }
         true
     }
 
+    /// Start the focused pane's command again if it has ended.
+    pub fn restart_focused(&mut self) {
+        let path = self.focus_path.clone();
+        self.restart_at(&path, true);
+    }
+
+    /// Replace the exited pane at `path` with a new one from the same spec,
+    /// in the same directory and at the same font size. Unless `forced`,
+    /// a process that exited right after starting is left alone.
+    fn restart_at(&mut self, path: &[FocusChild], forced: bool) {
+        let Some(root) = self.root.as_ref() else { return };
+        let PaneNode::Leaf { term, spec } = node_at_path(root, path) else { return };
+        if *term.state() == PaneState::Running {
+            return;
+        }
+        let label = spec.backend.label();
+        if !forced && term.uptime() < MIN_RESTART_UPTIME {
+            warn!(target: "terminal", "Not restarting {} pane: it exited within {:?} of starting", label, MIN_RESTART_UPTIME);
+            return;
+        }
+        let spec = spec.clone();
+        let (width, height) = term.pixel_size();
+        let cwd = term.cwd().map(Path::to_path_buf).or_else(|| spec.cwd.clone());
+        let font_size = term.font_size;
+
+        let Some(mut pane) = spawn_pane(&spec, width, height, cwd.as_deref()) else {
+            warn!(target: "terminal", "Failed to restart {} pane", label);
+            return;
+        };
+        pane.set_font_size(font_size);
+        pane.set_sync_marked(term.is_sync_marked());
+        let leaf = self.new_leaf(pane, spec);
+        if let Some(root) = self.root.as_mut() {
+            *node_at_path_mut(root, path) = leaf;
+        }
+        info!(target: "terminal", "Restarted {} pane", label);
+    }
+
     pub fn focused_mut(&mut self) -> Option<&mut TerminalPane> {
         let root = self.root.as_mut()?;
         let node = node_at_path_mut(root, &self.focus_path);
         match node {
-            PaneNode::Leaf(term) => Some(term),
+            PaneNode::Leaf { term, .. } => Some(term),
             _ => None,
         }
     }
@@ -208,7 +704,7 @@ impl PaneTree {
         let root = self.root.as_ref()?;
         let node = node_at_path(root, &self.focus_path);
         match node {
-            PaneNode::Leaf(term) => Some(term),
+            PaneNode::Leaf { term, .. } => Some(term),
             _ => None,
         }
     }
@@ -227,18 +723,304 @@ impl PaneTree {
         self.focus_path = leaves[next].clone();
     }
 
+    /// Focus the nearest pane in `direction`, judged by the panes'
+    /// rectangles in the split layout. Returns `false` if there is none.
+    pub fn focus_direction(&mut self, direction: FocusDirection, width: f64, height: f64) -> bool {
+        match self.neighbour(direction, width, height) {
+            Some(path) => {
+                self.focus_path = path;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The pane beside the focused one in `direction`: the closest whose
+    /// facing edge lies that way and that overlaps it across the other
+    /// axis, preferring the larger overlap.
+    fn neighbour(&self, direction: FocusDirection, width: f64, height: f64) -> Option<Vec<FocusChild>> {
+        let root = self.root.as_ref()?;
+        let current = rect_at_path(root, &self.focus_path, 0.0, 0.0, width, height);
+        let mut leaves = Vec::new();
+        collect_leaf_paths(root, &mut Vec::new(), &mut leaves);
+        leaves
+            .into_iter()
+            .filter(|path| *path != self.focus_path)
+            .filter_map(|path| {
+                let r = rect_at_path(root, &path, 0.0, 0.0, width, height);
+                let (gap, overlap) = match direction {
+                    FocusDirection::Left => (current.x0 - r.x1, span_overlap(r.y0, r.y1, current.y0, current.y1)),
+                    FocusDirection::Right => (r.x0 - current.x1, span_overlap(r.y0, r.y1, current.y0, current.y1)),
+                    FocusDirection::Up => (current.y0 - r.y1, span_overlap(r.x0, r.x1, current.x0, current.x1)),
+                    FocusDirection::Down => (r.y0 - current.y1, span_overlap(r.x0, r.x1, current.x0, current.x1)),
+                };
+                (gap >= 0.0 && overlap > 0.0).then_some((gap, overlap, path))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0).then(b.1.total_cmp(&a.1)))
+            .map(|(_, _, path)| path)
+    }
+
+    /// Focus the pane under `(px, py)`. Returns `true` if focus moved.
+    pub fn focus_at(&mut self, px: f64, py: f64, width: f64, height: f64) -> bool {
+        if self.zoomed { return false; }
+        let Some(root) = self.root.as_ref() else { return false };
+        let mut leaves = Vec::new();
+        collect_leaf_paths(root, &mut Vec::new(), &mut leaves);
+        let hit = leaves
+            .into_iter()
+            .find(|path| rect_at_path(root, path, 0.0, 0.0, width, height).contains(Point::new(px, py)));
+        match hit {
+            Some(path) if path != self.focus_path => {
+                self.focus_path = path;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Exchange the focused pane with the next (or previous) one in tree
+    /// order. Focus stays with the pane that moved.
+    pub fn swap_focused(&mut self, forward: bool) {
+        self.reorder_leaves(|order, focused| {
+            let n = order.len();
+            let other = if forward { (focused + 1) % n } else { (focused + n - 1) % n };
+            order.swap(focused, other);
+        });
+    }
+
+    /// Move every pane one place along in tree order, the last wrapping
+    /// round to the first slot. The split layout stays as it is.
+    pub fn rotate_panes(&mut self) {
+        self.reorder_leaves(|order, _| order.rotate_right(1));
+    }
+
+    /// Rearrange the panes over the existing layout. `reorder` receives
+    /// the leaf indices in tree order (and the focused one's index) and
+    /// permutes them; slot `i` then holds the pane that was at `order[i]`.
+    /// An `order` that is not a permutation leaves the tree as it is.
+    /// Follow with `resize_all`, as panes change size.
+    fn reorder_leaves(&mut self, reorder: impl FnOnce(&mut Vec<usize>, usize)) {
+        let Some(root) = self.root.as_ref() else { return };
+        let mut paths = Vec::new();
+        collect_leaf_paths(root, &mut Vec::new(), &mut paths);
+        let focused = paths.iter().position(|p| *p == self.focus_path).unwrap_or(0);
+
+        let mut order: Vec<usize> = (0..paths.len()).collect();
+        reorder(&mut order, focused);
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        if !sorted.into_iter().eq(0..paths.len()) {
+            return;
+        }
+
+        let Some(root) = self.root.take() else { return };
+        let shape = layout_of(&root);
+        let mut leaves = Vec::new();
+        into_leaves(root, &mut leaves);
+        let mut slots: Vec<Option<PaneNode>> = leaves.into_iter().map(Some).collect();
+        let mut moved = order.iter().filter_map(|&i| slots[i].take());
+        self.root = assemble(&shape, &mut moved);
+        if let Some(slot) = order.iter().position(|&i| i == focused) {
+            self.focus_path = paths[slot].clone();
+        }
+    }
+
     pub fn drain_all_output(&mut self) {
         if let Some(ref mut root) = self.root { drain_recursive(root); }
     }
 
+    /// Bring the find bars of the panes on screen up to date with their
+    /// output. Call once a frame, before drawing.
+    pub fn refresh_searches(&mut self) {
+        if self.zoomed {
+            if let Some(term) = self.focused_mut() { term.refresh_search_matches(); }
+        } else if let Some(ref mut root) = self.root {
+            for_each_pane(root, &mut |term| term.refresh_search_matches());
+        }
+    }
+
+    /// Lay every pane out again in `width` x `height`. Call after
+    /// anything that changes the layout: a window resize, a ratio change,
+    /// closing a pane or toggling zoom.
     pub fn resize_all(&mut self, width: f64, height: f64) {
-        if let Some(ref mut root) = self.root { resize_recursive(root, width, height); }
+        if self.zoomed {
+            if let Some(term) = self.focused_mut() { term.resize(width, height); }
+        } else if let Some(ref mut root) = self.root {
+            resize_recursive(root, width, height);
+        }
     }
 
-    pub fn render_into_scene(&self, scene: &mut Scene, x: f64, y: f64, w: f64, h: f64) {
+    /// Draw every pane. `window_focused` is false while the app window is
+    /// in the background, when even the focused pane's cursor is hollow.
+    pub fn render_into_scene(&self, scene: &mut Scene, x: f64, y: f64, w: f64, h: f64, window_focused: bool) {
         let Some(ref root) = self.root else { return };
+        if self.zoomed {
+            let node = node_at_path(root, &self.focus_path);
+            render_recursive(node, scene, x, y, w, h, &[], &[], false, window_focused, self.sync_input);
+            return;
+        }
         let multi = count_leaves(root) > 1;
-        render_recursive(root, scene, x, y, w, h, &self.focus_path, &[], multi);
+        render_recursive(root, scene, x, y, w, h, &self.focus_path, &[], multi, window_focused, self.sync_input);
+    }
+
+    /// Zoom the focused pane to the full area, or restore the split
+    /// layout. Only splits can zoom; follow with `resize_all`.
+    pub fn toggle_zoom(&mut self) {
+        self.zoomed = !self.zoomed && self.pane_count() > 1;
+        self.dragging = None;
+    }
+
+    pub fn is_zoomed(&self) -> bool {
+        self.zoomed
+    }
+
+    pub fn sync_input(&self) -> SyncInput {
+        self.sync_input
+    }
+
+    /// Synchronize input to every pane, or stop synchronizing.
+    pub fn toggle_sync_all(&mut self) {
+        self.sync_input = match self.sync_input {
+            SyncInput::Off => SyncInput::All,
+            SyncInput::All | SyncInput::Marked => SyncInput::Off,
+        };
+    }
+
+    /// Mark or unmark the focused pane. While any pane is marked, input
+    /// is synchronized to the marked ones; unmarking the last stops it.
+    pub fn toggle_sync_mark(&mut self) {
+        if let Some(term) = self.focused_mut() {
+            term.set_sync_marked(!term.is_sync_marked());
+        }
+        self.sync_input = SyncInput::Marked;
+        self.refresh_sync_marks();
+    }
+
+    /// Stop synchronizing to marked panes once none is left.
+    fn refresh_sync_marks(&mut self) {
+        if self.sync_input != SyncInput::Marked {
+            return;
+        }
+        let mut any = false;
+        if let Some(root) = self.root.as_mut() {
+            for_each_pane(root, &mut |term| any |= term.is_sync_marked());
+        }
+        if !any {
+            self.sync_input = SyncInput::Off;
+        }
+    }
+
+    /// Write a key to the focused pane and any it is synchronized to.
+    /// Returns whether the focused pane took it.
+    pub fn write_key(&mut self, press: &KeyPress) -> bool {
+        let wrote = self.focused_mut().is_some_and(|term| term.write_key(press));
+        self.for_each_sync_target(|term| {
+            term.write_key(press);
+        });
+        wrote
+    }
+
+    /// Paste into the focused pane and any it is synchronized to.
+    pub fn paste(&mut self, text: &str) {
+        if let Some(term) = self.focused_mut() {
+            term.paste(text);
+        }
+        self.for_each_sync_target(|term| term.paste(text));
+    }
+
+    /// Write IME text to the focused pane and any it is synchronized to.
+    pub fn commit_text(&mut self, text: &str) {
+        if let Some(term) = self.focused_mut() {
+            term.commit_text(text);
+        }
+        self.for_each_sync_target(|term| term.commit_text(text));
+    }
+
+    /// Run `f` on every pane other than the focused one that synchronized
+    /// input reaches.
+    fn for_each_sync_target(&mut self, mut f: impl FnMut(&mut TerminalPane)) {
+        let mode = self.sync_input;
+        if mode == SyncInput::Off {
+            return;
+        }
+        let Some(root) = self.root.as_mut() else { return };
+        let mut leaves = Vec::new();
+        collect_leaf_paths(root, &mut Vec::new(), &mut leaves);
+        for path in leaves.iter().filter(|path| **path != self.focus_path) {
+            if let PaneNode::Leaf { term, .. } = node_at_path_mut(root, path) {
+                if mode.reaches(term) {
+                    f(term);
+                }
+            }
+        }
+    }
+
+    /// Move the nearest separator beside the focused pane in `direction`
+    /// (`Vertical` for width) so the pane grows or shrinks by one step.
+    /// Returns `false` if no split in that direction contains the pane.
+    pub fn resize_focused(&mut self, direction: SplitDirection, grow: bool, width: f64, height: f64) -> bool {
+        if self.zoomed { return false; }
+        let Some(root) = self.root.as_mut() else { return false };
+        for depth in (0..self.focus_path.len()).rev() {
+            let parent = &self.focus_path[..depth];
+            let rect = rect_at_path(root, parent, 0.0, 0.0, width, height);
+            let PaneNode::Split { direction: d, ratio, .. } = node_at_path_mut(root, parent) else { continue };
+            if *d != direction { continue; }
+            let step = match (self.focus_path[depth], grow) {
+                (FocusChild::First, true) | (FocusChild::Second, false) => RESIZE_STEP,
+                _ => -RESIZE_STEP,
+            };
+            *ratio = clamp_ratio(*ratio + step, direction, rect);
+            self.resize_all(width, height);
+            return true;
+        }
+        false
+    }
+
+    /// Give every pane of a run of same-direction splits an equal share.
+    pub fn equalize(&mut self, width: f64, height: f64) {
+        if let Some(ref mut root) = self.root { equalize_recursive(root); }
+        self.resize_all(width, height);
+    }
+
+    /// The separator under `(px, py)`, if any: the split's path and direction.
+    pub fn separator_at(&self, px: f64, py: f64, width: f64, height: f64) -> Option<(Vec<FocusChild>, SplitDirection)> {
+        if self.zoomed { return None; }
+        let root = self.root.as_ref()?;
+        separator_hit(root, Rect::new(0.0, 0.0, width, height), Point::new(px, py), &mut Vec::new())
+    }
+
+    /// Start dragging the separator under `(px, py)`. Returns `false` if
+    /// there is none there.
+    pub fn begin_separator_drag(&mut self, px: f64, py: f64, width: f64, height: f64) -> bool {
+        self.dragging = self.separator_at(px, py, width, height).map(|(path, _)| path);
+        self.dragging.is_some()
+    }
+
+    /// Move the dragged separator to follow the pointer. Returns `false`
+    /// when no drag is in progress.
+    pub fn drag_separator(&mut self, px: f64, py: f64, width: f64, height: f64) -> bool {
+        let (Some(path), Some(root)) = (self.dragging.as_ref(), self.root.as_mut()) else { return false };
+        let rect = rect_at_path(root, path, 0.0, 0.0, width, height);
+        if let PaneNode::Split { direction, ratio, .. } = node_at_path_mut(root, path) {
+            let (offset, extent) = match direction {
+                SplitDirection::Vertical => (px - rect.x0, rect.width()),
+                SplitDirection::Horizontal => (py - rect.y0, rect.height()),
+            };
+            let dragged = (offset - SEPARATOR_PX / 2.0) / (extent - SEPARATOR_PX);
+            *ratio = clamp_ratio(dragged, *direction, rect);
+        }
+        self.resize_all(width, height);
+        true
+    }
+
+    /// Finish a separator drag. Returns `false` if none was in progress.
+    pub fn end_separator_drag(&mut self) -> bool {
+        self.dragging.take().is_some()
+    }
+
+    pub fn is_dragging_separator(&self) -> bool {
+        self.dragging.is_some()
     }
 
     pub fn pane_count(&self) -> usize {
@@ -249,9 +1031,155 @@ impl PaneTree {
     /// the given total area.
     pub fn focused_rect(&self, width: f64, height: f64) -> Option<(f64, f64, f64, f64)> {
         let root = self.root.as_ref()?;
+        if self.zoomed {
+            return Some((0.0, 0.0, width, height));
+        }
         let r = rect_at_path(root, &self.focus_path, 0.0, 0.0, width, height);
         Some((r.x0, r.y0, r.width(), r.height()))
     }
+
+    /// Snapshot of the tree's shape for the session file.
+    pub fn layout(&self) -> Option<PaneLayout> {
+        self.root.as_ref().map(layout_of)
+    }
+
+    pub fn focus_path(&self) -> &[FocusChild] {
+        &self.focus_path
+    }
+
+    /// Rebuild a tree from a saved layout, spawning a fresh process per leaf.
+    ///
+    /// Leaves that fail to spawn are dropped and their sibling takes the
+    /// space. A `focus_path` that no longer names a leaf falls back to the
+    /// first pane.
+    pub fn from_layout(layout: &PaneLayout, focus_path: &[FocusChild], width: f64, height: f64) -> Option<Self> {
+        let root = build_from_layout(layout, width, height)?;
+        let mut focus_path = focus_path.to_vec();
+        if !is_leaf_path(&root, &focus_path) {
+            focus_path.clear();
+            descend_first_leaf(&root, &mut focus_path);
+        }
+        Some(Self {
+            root: Some(root),
+            focus_path,
+            record_claude: false,
+            scheme: Arc::default(),
+            cursor_style: terminal::DEFAULT_CURSOR_STYLE,
+            clipboard_write: true,
+            on_exit: OnExit::Keep,
+            zoomed: false,
+            dragging: None,
+            sync_input: SyncInput::Off,
+        })
+    }
+}
+
+fn start_claude_recordings(node: &mut PaneNode) {
+    match node {
+        PaneNode::Leaf { term, spec } if spec.backend == TerminalBackend::Docker && !term.is_recording() => {
+            term.start_recording(TerminalBackend::Docker.label());
+        }
+        PaneNode::Leaf { .. } => {}
+        PaneNode::Split { first, second, .. } => {
+            start_claude_recordings(first);
+            start_claude_recordings(second);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Layout snapshot / restore
+// ---------------------------------------------------------------------------
+
+fn layout_of(node: &PaneNode) -> PaneLayout {
+    match node {
+        PaneNode::Leaf { term, spec } => PaneLayout::Leaf {
+            backend: spec.backend.clone(),
+            font_size: term.font_size,
+            profile: spec.profile.clone(),
+            cwd: spec.cwd.clone(),
+            env: spec.env.clone(),
+            docker: spec.docker.clone(),
+            project: spec.project.clone(),
+        },
+        PaneNode::Split { direction, ratio, first, second } => PaneLayout::Split {
+            direction: *direction,
+            ratio: *ratio,
+            first: Box::new(layout_of(first)),
+            second: Box::new(layout_of(second)),
+        },
+    }
+}
+
+fn build_from_layout(layout: &PaneLayout, w: f64, h: f64) -> Option<PaneNode> {
+    match layout {
+        PaneLayout::Leaf { backend, font_size, profile, cwd, env, docker, project } => {
+            let spec = PaneSpec {
+                backend: backend.clone(),
+                profile: profile.clone(),
+                cwd: cwd.clone(),
+                env: env.clone(),
+                docker: docker.clone(),
+                project: project.clone(),
+            };
+            let mut term = spawn_pane(&spec, w, h, spec.cwd.as_deref())?;
+            term.set_font_size(*font_size);
+            Some(PaneNode::Leaf { term, spec })
+        }
+        PaneLayout::Split { direction, ratio, first, second } => {
+            let ratio = ratio.clamp(0.05, 0.95);
+            let (r1, r2) = split_dims(w, h, *direction, ratio);
+            let first = build_from_layout(first, r1.0, r1.1);
+            let second = build_from_layout(second, r2.0, r2.1);
+            match (first, second) {
+                (Some(first), Some(second)) => Some(PaneNode::Split {
+                    direction: *direction,
+                    ratio,
+                    first: Box::new(first),
+                    second: Box::new(second),
+                }),
+                (Some(only), None) | (None, Some(only)) => Some(only),
+                (None, None) => None,
+            }
+        }
+    }
+}
+
+/// Move the leaves out of `node`, in tree order.
+fn into_leaves(node: PaneNode, out: &mut Vec<PaneNode>) {
+    match node {
+        leaf @ PaneNode::Leaf { .. } => out.push(leaf),
+        PaneNode::Split { first, second, .. } => {
+            into_leaves(*first, out);
+            into_leaves(*second, out);
+        }
+    }
+}
+
+/// Rebuild the split structure of `shape` around existing leaves, taken
+/// in tree order. Leaf entries of `shape` only mark where a pane goes.
+/// `None` if `leaves` runs out; leaves beyond what `shape` holds are left
+/// in the iterator.
+fn assemble(shape: &PaneLayout, leaves: &mut impl Iterator<Item = PaneNode>) -> Option<PaneNode> {
+    match shape {
+        PaneLayout::Leaf { .. } => leaves.next(),
+        PaneLayout::Split { direction, ratio, first, second } => Some(PaneNode::Split {
+            direction: *direction,
+            ratio: *ratio,
+            first: Box::new(assemble(first, leaves)?),
+            second: Box::new(assemble(second, leaves)?),
+        }),
+    }
+}
+
+/// Whether `path` leads exactly to a leaf.
+fn is_leaf_path(node: &PaneNode, path: &[FocusChild]) -> bool {
+    match (node, path.split_first()) {
+        (PaneNode::Leaf { .. }, None) => true,
+        (PaneNode::Split { first, .. }, Some((FocusChild::First, rest))) => is_leaf_path(first, rest),
+        (PaneNode::Split { second, .. }, Some((FocusChild::Second, rest))) => is_leaf_path(second, rest),
+        _ => false,
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -264,21 +1192,21 @@ fn transform_split(
     path: &[FocusChild],
     depth: usize,
     direction: SplitDirection,
-    new_pane: TerminalPane,
+    new_leaf: PaneNode,
     first_w: f64,
     first_h: f64,
 ) -> PaneNode {
     if depth == path.len() {
         // This is the focused leaf — wrap it in a Split.
         let mut original = node;
-        if let PaneNode::Leaf(ref mut term) = original {
+        if let PaneNode::Leaf { ref mut term, .. } = original {
             term.resize(first_w, first_h);
         }
         return PaneNode::Split {
             direction,
             ratio: 0.5,
             first: Box::new(original),
-            second: Box::new(PaneNode::Leaf(new_pane)),
+            second: Box::new(new_leaf),
         };
     }
 
@@ -288,54 +1216,51 @@ fn transform_split(
                 FocusChild::First => PaneNode::Split {
                     direction: d,
                     ratio,
-                    first: Box::new(transform_split(*first, path, depth + 1, direction, new_pane, first_w, first_h)),
+                    first: Box::new(transform_split(*first, path, depth + 1, direction, new_leaf, first_w, first_h)),
                     second,
                 },
                 FocusChild::Second => PaneNode::Split {
                     direction: d,
                     ratio,
                     first,
-                    second: Box::new(transform_split(*second, path, depth + 1, direction, new_pane, first_w, first_h)),
+                    second: Box::new(transform_split(*second, path, depth + 1, direction, new_leaf, first_w, first_h)),
                 },
             }
         }
-        leaf @ PaneNode::Leaf(_) => leaf, // path invalid, return unchanged
+        leaf @ PaneNode::Leaf { .. } => leaf, // path invalid, return unchanged
     }
 }
 
-/// Remove the leaf at `path` and replace its parent Split with the surviving sibling.
+/// Remove the leaf at `path` and replace its parent Split with the surviving
+/// sibling. Returns the new tree and the removed leaf.
 fn transform_close(
     node: PaneNode,
     path: &[FocusChild],
     depth: usize,
     which_child: FocusChild,
-) -> PaneNode {
+) -> (PaneNode, Option<PaneNode>) {
     if depth == path.len() - 1 {
         // This node is the parent Split of the leaf being closed.
         match node {
             PaneNode::Split { first, second, .. } => match which_child {
-                FocusChild::First => *second,  // close first, keep second
-                FocusChild::Second => *first,  // close second, keep first
+                FocusChild::First => (*second, Some(*first)),  // close first, keep second
+                FocusChild::Second => (*first, Some(*second)), // close second, keep first
             },
-            other => other, // shouldn't happen
+            other => (other, None), // shouldn't happen
         }
     } else {
         match node {
             PaneNode::Split { direction, ratio, first, second } => match path[depth] {
-                FocusChild::First => PaneNode::Split {
-                    direction,
-                    ratio,
-                    first: Box::new(transform_close(*first, path, depth + 1, which_child)),
-                    second,
-                },
-                FocusChild::Second => PaneNode::Split {
-                    direction,
-                    ratio,
-                    first,
-                    second: Box::new(transform_close(*second, path, depth + 1, which_child)),
-                },
+                FocusChild::First => {
+                    let (first, removed) = transform_close(*first, path, depth + 1, which_child);
+                    (PaneNode::Split { direction, ratio, first: Box::new(first), second }, removed)
+                }
+                FocusChild::Second => {
+                    let (second, removed) = transform_close(*second, path, depth + 1, which_child);
+                    (PaneNode::Split { direction, ratio, first, second: Box::new(second) }, removed)
+                }
             },
-            other => other,
+            other => (other, None),
         }
     }
 }
@@ -352,7 +1277,7 @@ fn node_at_path_mut<'a>(root: &'a mut PaneNode, path: &[FocusChild]) -> &'a mut
                 FocusChild::First => first.as_mut(),
                 FocusChild::Second => second.as_mut(),
             },
-            PaneNode::Leaf(_) => return current,
+            PaneNode::Leaf { .. } => return current,
         };
     }
     current
@@ -366,7 +1291,7 @@ fn node_at_path<'a>(root: &'a PaneNode, path: &[FocusChild]) -> &'a PaneNode {
                 FocusChild::First => first.as_ref(),
                 FocusChild::Second => second.as_ref(),
             },
-            PaneNode::Leaf(_) => return current,
+            PaneNode::Leaf { .. } => return current,
         };
     }
     current
@@ -374,7 +1299,7 @@ fn node_at_path<'a>(root: &'a PaneNode, path: &[FocusChild]) -> &'a PaneNode {
 
 fn descend_first_leaf(node: &PaneNode, path: &mut Vec<FocusChild>) {
     match node {
-        PaneNode::Leaf(_) => {}
+        PaneNode::Leaf { .. } => {}
         PaneNode::Split { first, .. } => {
             path.push(FocusChild::First);
             descend_first_leaf(first, path);
@@ -384,7 +1309,7 @@ fn descend_first_leaf(node: &PaneNode, path: &mut Vec<FocusChild>) {
 
 fn collect_leaf_paths(node: &PaneNode, path: &mut Vec<FocusChild>, out: &mut Vec<Vec<FocusChild>>) {
     match node {
-        PaneNode::Leaf(_) => out.push(path.clone()),
+        PaneNode::Leaf { .. } => out.push(path.clone()),
         PaneNode::Split { first, second, .. } => {
             path.push(FocusChild::First);
             collect_leaf_paths(first, path, out);
@@ -402,14 +1327,63 @@ fn collect_leaf_paths(node: &PaneNode, path: &mut Vec<FocusChild>, out: &mut Vec
 
 fn drain_recursive(node: &mut PaneNode) {
     match node {
-        PaneNode::Leaf(term) => term.drain_output(),
+        PaneNode::Leaf { term, .. } => term.drain_output(),
         PaneNode::Split { first, second, .. } => { drain_recursive(first); drain_recursive(second); }
     }
 }
 
+/// Poll every pane's process, collecting the paths of those that just exited.
+fn poll_exits(node: &mut PaneNode, path: &mut Vec<FocusChild>, ended: &mut Vec<Vec<FocusChild>>) {
+    match node {
+        PaneNode::Leaf { term, .. } => {
+            if term.poll_exit() {
+                ended.push(path.clone());
+            }
+        }
+        PaneNode::Split { first, second, .. } => {
+            path.push(FocusChild::First);
+            poll_exits(first, path, ended);
+            path.pop();
+            path.push(FocusChild::Second);
+            poll_exits(second, path, ended);
+            path.pop();
+        }
+    }
+}
+
+/// Path of the first pane whose process is no longer running. Stand-ins
+/// are left alone (see `handle_exits`).
+fn first_exited(node: &PaneNode, path: &mut Vec<FocusChild>) -> Option<Vec<FocusChild>> {
+    match node {
+        PaneNode::Leaf { term, .. } => (*term.state() != PaneState::Running && !term.is_stand_in()).then(|| path.clone()),
+        PaneNode::Split { first, second, .. } => {
+            path.push(FocusChild::First);
+            let found = first_exited(first, path);
+            path.pop();
+            if found.is_some() {
+                return found;
+            }
+            path.push(FocusChild::Second);
+            let found = first_exited(second, path);
+            path.pop();
+            found
+        }
+    }
+}
+
+fn for_each_pane(node: &mut PaneNode, f: &mut impl FnMut(&mut TerminalPane)) {
+    match node {
+        PaneNode::Leaf { term, .. } => f(term),
+        PaneNode::Split { first, second, .. } => {
+            for_each_pane(first, f);
+            for_each_pane(second, f);
+        }
+    }
+}
+
 fn resize_recursive(node: &mut PaneNode, w: f64, h: f64) {
     match node {
-        PaneNode::Leaf(term) => term.resize(w, h),
+        PaneNode::Leaf { term, .. } => term.resize(w, h),
         PaneNode::Split { direction, ratio, first, second } => {
             let (r1, r2) = split_dims(w, h, *direction, *ratio);
             resize_recursive(first, r1.0, r1.1);
@@ -422,33 +1396,90 @@ fn render_recursive(
     node: &PaneNode, scene: &mut Scene,
     x: f64, y: f64, w: f64, h: f64,
     focus_path: &[FocusChild], current_path: &[FocusChild],
-    multi: bool,
+    multi: bool, window_focused: bool, sync: SyncInput,
 ) {
     match node {
-        PaneNode::Leaf(term) => {
-            term.render_into_scene(scene, x, y, w, h);
-            if multi && current_path == focus_path {
+        PaneNode::Leaf { term, .. } => {
+            let focused = current_path == focus_path;
+            term.render_into_scene(scene, x, y, w, h, focused && window_focused);
+            if multi && focused {
                 draw_focus_border(scene, x, y, w, h);
             }
+            // Every pane typing reaches, the focused one included, even
+            // when zoomed: the others still receive it.
+            let typed_here = focused && sync != SyncInput::Off && *term.state() == PaneState::Running;
+            if typed_here || sync.reaches(term) {
+                draw_sync_border(scene, x, y, w, h);
+            }
         }
         PaneNode::Split { direction, ratio, first, second } => {
             let (r1, r2) = split_dims(w, h, *direction, *ratio);
             let mut p1 = current_path.to_vec(); p1.push(FocusChild::First);
-            render_recursive(first, scene, x, y, r1.0, r1.1, focus_path, &p1, multi);
+            render_recursive(first, scene, x, y, r1.0, r1.1, focus_path, &p1, multi, window_focused, sync);
 
             let sep = separator_rect(x, y, w, h, *direction, *ratio);
             scene.fill(Fill::NonZero, Affine::IDENTITY, SEPARATOR_COLOR, None, &sep);
 
             let (x2, y2) = second_origin(x, y, *direction, r1);
             let mut p2 = current_path.to_vec(); p2.push(FocusChild::Second);
-            render_recursive(second, scene, x2, y2, r2.0, r2.1, focus_path, &p2, multi);
+            render_recursive(second, scene, x2, y2, r2.0, r2.1, focus_path, &p2, multi, window_focused, sync);
+        }
+    }
+}
+
+/// Reset ratios so each pane in a run of same-direction splits gets the
+/// same share, counting the panes side by side under each child.
+fn equalize_recursive(node: &mut PaneNode) {
+    if let PaneNode::Split { direction, ratio, first, second } = node {
+        let (a, b) = (panes_across(first, *direction), panes_across(second, *direction));
+        *ratio = a as f64 / (a + b) as f64;
+        equalize_recursive(first);
+        equalize_recursive(second);
+    }
+}
+
+/// Number of panes `node` lays out side by side along `direction`.
+fn panes_across(node: &PaneNode, direction: SplitDirection) -> usize {
+    match node {
+        PaneNode::Leaf { .. } => 1,
+        PaneNode::Split { direction: d, first, second, .. } if *d == direction => {
+            panes_across(first, direction) + panes_across(second, direction)
+        }
+        PaneNode::Split { first, second, .. } => {
+            panes_across(first, direction).max(panes_across(second, direction))
         }
     }
 }
 
+fn separator_hit(node: &PaneNode, rect: Rect, p: Point, path: &mut Vec<FocusChild>) -> Option<(Vec<FocusChild>, SplitDirection)> {
+    let PaneNode::Split { direction, ratio, first, second } = node else { return None };
+    let (x, y, w, h) = (rect.x0, rect.y0, rect.width(), rect.height());
+    let sep = separator_rect(x, y, w, h, *direction, *ratio);
+    let grab = match direction {
+        SplitDirection::Vertical => sep.inflate(SEPARATOR_GRAB_PX, 0.0),
+        SplitDirection::Horizontal => sep.inflate(0.0, SEPARATOR_GRAB_PX),
+    };
+    if grab.contains(p) {
+        return Some((path.clone(), *direction));
+    }
+
+    let (r1, r2) = split_dims(w, h, *direction, *ratio);
+    let (x2, y2) = second_origin(x, y, *direction, r1);
+    path.push(FocusChild::First);
+    let hit = separator_hit(first, Rect::new(x, y, x + r1.0, y + r1.1), p, path);
+    path.pop();
+    if hit.is_some() {
+        return hit;
+    }
+    path.push(FocusChild::Second);
+    let hit = separator_hit(second, Rect::new(x2, y2, x2 + r2.0, y2 + r2.1), p, path);
+    path.pop();
+    hit
+}
+
 fn count_leaves(node: &PaneNode) -> usize {
     match node {
-        PaneNode::Leaf(_) => 1,
+        PaneNode::Leaf { .. } => 1,
         PaneNode::Split { first, second, .. } => count_leaves(first) + count_leaves(second),
     }
 }
@@ -466,7 +1497,7 @@ fn rect_at_path(node: &PaneNode, path: &[FocusChild], x: f64, y: f64, w: f64, h:
                 }
             }
         }
-        PaneNode::Leaf(_) => Rect::new(x, y, x + w, y + h),
+        PaneNode::Leaf { .. } => Rect::new(x, y, x + w, y + h),
     }
 }
 
@@ -474,50 +1505,659 @@ fn rect_at_path(node: &PaneNode, path: &[FocusChild], x: f64, y: f64, w: f64, h:
 // Backend-aware pane spawning
 // ---------------------------------------------------------------------------
 
-/// Spawn a terminal pane using the given backend.
-fn spawn_pane_for_backend(
-    backend: &TerminalBackend,
-    width: f64,
-    height: f64,
-) -> Option<TerminalPane> {
-    match backend {
-        TerminalBackend::Local => TerminalPane::spawn(width, height),
-        TerminalBackend::Docker => {
-            if !ensure_docker_image() {
-                warn!("Docker image unavailable — falling back to local PTY");
-                return TerminalPane::spawn(width, height);
+/// Spawn a pane as `spec` describes, badged with its backend. `cwd` is
+/// where a local shell starts; containers and the VM only use the spec's
+/// own directory.
+fn spawn_pane(spec: &PaneSpec, width: f64, height: f64, cwd: Option<&Path>) -> Option<TerminalPane> {
+    if let Some(project) = spec.project.as_ref().filter(|project| !project.has_safe_name()) {
+        warn!(target: "terminal", "Not opening project {:?}: its name is not a plain directory name", project.name);
+        return None;
+    }
+    let env: Vec<(&str, &str)> = spec.env.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+    let mut pane = match &spec.backend {
+        TerminalBackend::Local => TerminalPane::spawn(width, height, cwd, &env)?,
+        // Never a local shell in its place: a Docker pane is sandboxed
+        // or it does not start.
+        TerminalBackend::Docker if !DOCKER_IMAGE_READY.load(Ordering::Relaxed) => return image_build_pane(width, height),
+        TerminalBackend::Docker => spawn_docker(spec, width, height)?,
+        TerminalBackend::Vm => spawn_vm_shell(spec, width, height)?,
+        TerminalBackend::Ssh { host, .. } => {
+            info!(target: "terminal", host = host.as_str(), "Opening an ssh session");
+            spawn_remote_shell(spec, width, height)?
+        }
+        TerminalBackend::Playback => {
+            warn!("Playback panes are not respawned");
+            return None;
+        }
+    };
+    pane.set_badge(spec.label());
+    Some(pane)
+}
+
+/// Start Claude Code in a fresh container named for this pane. Closing
+/// the pane removes the container, even if its `docker run` was killed
+/// before `--rm` could act; a project pane gets a last snapshot first.
+fn spawn_docker(spec: &PaneSpec, width: f64, height: f64) -> Option<TerminalPane> {
+    let image = docker_image();
+    let name = format!("bisque-{}-{}", std::process::id(), DOCKER_PANES.fetch_add(1, Ordering::Relaxed) + 1);
+    info!(image = image.as_str(), container = name.as_str(), "Spawning Docker container for Claude Code");
+
+    // Pass ANTHROPIC_API_KEY from host env so Claude boots authenticated.
+    // Generate the token on the host via `claude setup-token`. It goes in
+    // a private env-file with the pane's variables, so none of them show
+    // up in `ps` or `docker inspect`'s command line, and the file is
+    // deleted as soon as `docker run` has read it.
+    let mut vars = spec.env.clone();
+    match std::env::var("ANTHROPIC_API_KEY") {
+        Ok(key) if !key.is_empty() => {
+            vars.insert("ANTHROPIC_API_KEY".into(), key);
+        }
+        _ => warn!("ANTHROPIC_API_KEY not set — run `claude setup-token` and export the token"),
+    }
+    let env_file = if vars.is_empty() {
+        None
+    } else {
+        match write_env_file(&name, &vars) {
+            Ok(path) => Some(path),
+            Err(e) => {
+                warn!("Failed to write Docker env-file: {}", e);
+                return None;
             }
+        }
+    };
 
-            info!(image = DOCKER_IMAGE, "Spawning Docker container for Claude Code");
-
-            // Pass ANTHROPIC_API_KEY from host env so Claude boots authenticated.
-            // Generate the token on the host via `claude setup-token`.
-            let api_key = std::env::var("ANTHROPIC_API_KEY").unwrap_or_default();
-            let mut args: Vec<String> = vec![
-                "run".into(), "-it".into(), "--rm".into(),
-                "-v".into(), "bisque-claude-config:/home/claude/.claude".into(),
-            ];
-            if !api_key.is_empty() {
-                args.push("-e".into());
-                args.push(format!("ANTHROPIC_API_KEY={}", api_key));
-            } else {
-                warn!("ANTHROPIC_API_KEY not set — run `claude setup-token` and export the token");
+    let backup = spec.project.as_ref().map(|project| Backup::start(project, &spec.backend));
+    let options = spec.docker.clone().unwrap_or_else(crate::pane_profiles::docker_defaults);
+    let args = docker_args(spec, &options, &image, &name, env_file.as_deref(), backup.as_ref());
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let Some(mut pane) = TerminalPane::spawn_command(width, height, "docker", &args_refs, &[]) else {
+        remove_container(name, env_file, None);
+        return None;
+    };
+    if let Some(path) = &env_file {
+        remove_env_file_once_read(name.clone(), path.clone());
+    }
+    if let (Some(project), Some(backup)) = (&spec.project, &backup) {
+        let query = vec!["exec".to_string(), name.clone(), "sh".into(), "-c".into(), backup_report_command(project, backup)];
+        watch_backup(pane.backup_status(), "docker", query);
+    }
+    let final_backup = spec.project.as_ref().zip(backup.as_ref()).map(|(p, b)| final_backup_command(p, b));
+    pane.on_close(move || remove_container(name, env_file, final_backup));
+    Some(pane)
+}
+
+/// Arguments to `docker run` for a pane's container.
+fn docker_args(
+    spec: &PaneSpec,
+    options: &DockerOptions,
+    image: &str,
+    name: &str,
+    env_file: Option<&Path>,
+    backup: Option<&Backup>,
+) -> Vec<String> {
+    let mut args: Vec<String> = ["run", "-it", "--rm", "--name", name, "--label", DOCKER_LABEL]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    if let Some(profile) = &spec.profile {
+        args.push("--label".into());
+        args.push(format!("{}.profile={}", DOCKER_LABEL, profile));
+    }
+    args.push("-v".into());
+    args.push("bisque-claude-config:/home/claude/.claude".into());
+    if let Some(backup) = backup {
+        if let Some(repo) = &backup.host_repo {
+            args.push("-v".into());
+            args.push(format!("{}:{}", repo.to_string_lossy(), BACKUP_MOUNT));
+        }
+        // The repo is only group-writable; the container's user joins its
+        // group to push.
+        if let Some(gid) = backup.host_group {
+            args.push("--group-add".into());
+            args.push(gid.to_string());
+        }
+    }
+    if let Some(path) = env_file {
+        args.push("--env-file".into());
+        args.push(path.to_string_lossy().into_owned());
+    }
+    if let Some(cpus) = options.cpus {
+        args.push("--cpus".into());
+        args.push(cpus.to_string());
+    }
+    if let Some(memory) = &options.memory {
+        args.push("--memory".into());
+        args.push(memory.clone());
+    }
+    if let Some(pids) = options.pids {
+        args.push("--pids-limit".into());
+        args.push(pids.to_string());
+    }
+    if let Some(network) = &options.network {
+        args.push("--network".into());
+        args.push(network.clone());
+    }
+    if options.read_only {
+        // 1001 is the image's `claude` user; `node` already has 1000.
+        // Each mount point is made by the Dockerfile, and the image tag
+        // hashes the Dockerfile, so adding one here means rebuilding.
+        for arg in [
+            "--read-only",
+            "--tmpfs", "/tmp:exec",
+            "--tmpfs", "/home/claude:exec,uid=1001,gid=1001",
+            "--tmpfs", "/workspace:exec,uid=1001,gid=1001",
+        ] {
+            args.push(arg.into());
+        }
+    }
+    match &spec.project {
+        // The clone sets the directory; the pane's variables are already
+        // in the env-file.
+        Some(project) => {
+            args.extend(["--entrypoint".to_string(), "sh".to_string(), image.to_string(), "-c".to_string()]);
+            args.push(project_command(project, None, backup));
+        }
+        None => {
+            if let Some(dir) = &spec.cwd {
+                args.push("-w".into());
+                args.push(dir.to_string_lossy().into_owned());
             }
-            args.push(DOCKER_IMAGE.into());
+            args.push(image.into());
             args.push("--dangerously-skip-permissions".into());
-            let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        }
+    }
+    args
+}
+
+/// Write `vars` to an env-file in `secrets_dir`, one `NAME=value` per
+/// line. Docker's format has no quoting, so a value with a line break is
+/// left out.
+fn write_env_file(name: &str, vars: &BTreeMap<String, String>) -> std::io::Result<PathBuf> {
+    use std::io::Write;
+
+    let path = secrets_dir()?.join(format!("{}.env", name));
+    // Only this user can write here: a file of the same name is one an
+    // earlier, crashed run left behind.
+    if let Err(e) = std::fs::remove_file(&path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            return Err(e);
+        }
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(&path)?;
+    for (key, value) in vars {
+        if value.contains(['\n', '\r']) {
+            warn!("Leaving {} out of the container: its value spans lines", key);
+            continue;
+        }
+        writeln!(file, "{}={}", key, value)?;
+    }
+    Ok(path)
+}
+
+/// A directory only this user can open, for env-files:
+/// `$XDG_RUNTIME_DIR/bisque-computer`, or `secrets` in the data directory.
+fn secrets_dir() -> std::io::Result<PathBuf> {
+    let dir = match std::env::var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty()) {
+        Some(runtime) => PathBuf::from(runtime).join("bisque-computer"),
+        None => BisquePaths::resolve()
+            .map(|paths| paths.data.join("secrets"))
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "HOME is not set"))?,
+    };
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(&dir)?;
+    // It may have been created before, with looser permissions.
+    #[cfg(unix)]
+    std::fs::set_permissions(&dir, std::os::unix::fs::PermissionsExt::from_mode(0o700))?;
+    Ok(dir)
+}
+
+/// Delete a pane's env-file once its container exists: `docker run` reads
+/// the file before creating it. If the container never appears the file
+/// goes after `ENV_FILE_WAIT`, and closing the pane removes it too.
+fn remove_env_file_once_read(container: String, path: PathBuf) {
+    std::thread::spawn(move || {
+        let deadline = Instant::now() + ENV_FILE_WAIT;
+        while Instant::now() < deadline && !container_exists(&container) {
+            std::thread::sleep(Duration::from_millis(200));
+        }
+        let _ = std::fs::remove_file(&path);
+    });
+}
+
+fn container_exists(name: &str) -> bool {
+    std::process::Command::new("docker")
+        .args(["container", "inspect", "--format", "{{.Id}}", name])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Remove a pane's env-file, then in the background run `final_backup`
+/// in its container and remove the container.
+fn remove_container(name: String, env_file: Option<PathBuf>, final_backup: Option<String>) {
+    if let Some(path) = env_file {
+        let _ = std::fs::remove_file(path);
+    }
+    run_cleanup(move || {
+        if let Some(command) = final_backup {
+            let _ = std::process::Command::new("docker")
+                .args(["exec", &name, "sh", "-c", &command])
+                .stdin(std::process::Stdio::null())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status();
+        }
+        let _ = std::process::Command::new("docker")
+            .args(["rm", "-f", &name])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status();
+    });
+}
+
+/// Run a closed pane's cleanup on its own thread, so closing never waits
+/// on docker or ssh.
+fn run_cleanup(cleanup: impl FnOnce() + Send + 'static) {
+    let mut running = CLEANUPS.lock().unwrap();
+    running.retain(|handle| !handle.is_finished());
+    running.push(std::thread::spawn(cleanup));
+}
+
+/// Wait up to `timeout` for the cleanup of closed panes (final backups,
+/// container removal), so quitting does not cut it short.
+pub fn wait_for_cleanup(timeout: Duration) {
+    let deadline = Instant::now() + timeout;
+    let mut running = std::mem::take(&mut *CLEANUPS.lock().unwrap());
+    running.retain(|handle| !handle.is_finished());
+    if !running.is_empty() {
+        info!(target: "terminal", panes = running.len(), "Saving closed panes before exit");
+    }
+    for handle in running {
+        while !handle.is_finished() {
+            if Instant::now() >= deadline {
+                warn!(target: "terminal", "Gave up waiting for closed panes to clean up");
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+}
+
+/// Open a login shell in the Lobster VM, or Claude on a project. Gives up
+/// straight away if its sshd is not listening, rather than leaving ssh to
+/// time out in the pane.
+fn spawn_vm_shell(spec: &PaneSpec, width: f64, height: f64) -> Option<TerminalPane> {
+    let addr = SocketAddr::from(([127, 0, 0, 1], VM_SSH_PORT));
+    if TcpStream::connect_timeout(&addr, Duration::from_secs(1)).is_err() {
+        warn!(target: "terminal", "Lobster VM is not reachable on port {} — is it running?", VM_SSH_PORT);
+        return None;
+    }
+    info!(target: "terminal", port = VM_SSH_PORT, "Opening a shell in the Lobster VM");
+    let Some(project) = &spec.project else {
+        return spawn_remote_shell(spec, width, height);
+    };
+
+    let env_file = send_env(spec)?;
+    let backup = Backup::start(project, &spec.backend);
+    let mut args = vec!["-t".to_string()];
+    args.extend(ssh_connection(spec));
+    args.push(project_command(project, env_file.as_deref(), Some(&backup)));
+    let mut pane = spawn_ssh(&args, width, height)?;
+    let mut query = ssh_connection(spec);
+    query.push(backup_report_command(project, &backup));
+    watch_backup(pane.backup_status(), "ssh", query);
+    // The snapshot loop ends with the ssh session; take one last snapshot
+    // over a connection of its own.
+    let mut close = ssh_connection(spec);
+    close.push(final_backup_command(project, &backup));
+    pane.on_close(move || {
+        run_cleanup(move || {
+            let _ = std::process::Command::new("ssh")
+                .args(&close)
+                .stdin(std::process::Stdio::null())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status();
+        })
+    });
+    Some(pane)
+}
+
+/// Run an interactive `ssh` on a local PTY. Resizing the pane resizes
+/// that PTY (`LocalPtyResize`), and ssh passes the new size on to the
+/// remote shell.
+fn spawn_ssh(args: &[String], width: f64, height: f64) -> Option<TerminalPane> {
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    TerminalPane::spawn_command(width, height, "ssh", &args_refs, &[])
+}
+
+/// A login shell over ssh, with the spec's variables sent ahead.
+fn spawn_remote_shell(spec: &PaneSpec, width: f64, height: f64) -> Option<TerminalPane> {
+    let env_file = send_env(spec)?;
+    spawn_ssh(&ssh_args(spec, env_file.as_deref()), width, height)
+}
+
+/// `ssh` arguments for a VM or ssh spec: a forced tty, the connection,
+/// then the remote command if the spec sets a directory or has an
+/// env-file (see `send_env`) to load.
+fn ssh_args(spec: &PaneSpec, env_file: Option<&str>) -> Vec<String> {
+    let mut args = vec!["-t".to_string()];
+    args.extend(ssh_connection(spec));
+    args.extend(remote_shell_command(spec, env_file));
+    args
+}
+
+/// Copy the spec's variables to the ssh host, if it has any (see
+/// `upload_env_file`), returning the file's remote path. `None` if that
+/// fails: the pane does not start without them.
+fn send_env(spec: &PaneSpec) -> Option<Option<String>> {
+    if spec.env.is_empty() {
+        return Some(None);
+    }
+    let name = format!("bisque-{}-{}", std::process::id(), SSH_ENV_FILES.fetch_add(1, Ordering::Relaxed) + 1);
+    match upload_env_file(&ssh_connection(spec), &name, &spec.env) {
+        Ok(path) => Some(Some(path)),
+        Err(e) => {
+            warn!(target: "terminal", "Failed to send the pane's variables over ssh: {}", e);
+            None
+        }
+    }
+}
+
+/// Write `vars` to a private file named `name` on the far side of
+/// `connection`, as `export` lines for the remote command to load and
+/// delete (`load_env_file`). They travel on the connection's stdin, so
+/// unlike a command line they show up neither in `ps` nor in the remote
+/// shell's logs: the same care as Docker's env-files.
+fn upload_env_file(connection: &[String], name: &str, vars: &BTreeMap<String, String>) -> std::io::Result<String> {
+    use std::io::Write;
+
+    let path = format!("\"$HOME\"/.bisque-env/{}", name);
+    let mut child = std::process::Command::new("ssh")
+        .args(["-o", "BatchMode=yes"])
+        .args(connection)
+        .arg(format!("umask 077 && mkdir -p \"$HOME\"/.bisque-env && cat > {}", path))
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(env_file_contents(vars).as_bytes())?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(std::io::Error::other(format!("ssh {}", status)));
+    }
+    Ok(path)
+}
+
+/// `export` lines setting `vars`, for a POSIX shell to source.
+fn env_file_contents(vars: &BTreeMap<String, String>) -> String {
+    vars.iter()
+        .map(|(name, value)| format!("export {}\n", shell_quote(&format!("{}={}", name, value))))
+        .collect()
+}
+
+/// Shell command that loads the env-file at `path` and deletes it, ending
+/// the shell if it could not be read.
+fn load_env_file(path: &str) -> String {
+    format!("f={}; . \"$f\"; loaded=$?; rm -f \"$f\"; [ $loaded -eq 0 ] || exit $loaded; ", path)
+}
+
+/// The `ssh` options and destination for a VM or ssh spec.
+fn ssh_connection(spec: &PaneSpec) -> Vec<String> {
+    let mut args = Vec::new();
+    match &spec.backend {
+        TerminalBackend::Vm => {
+            // The VM's host key changes with every image; don't record it.
+            args.extend(["-p".to_string(), VM_SSH_PORT.to_string()]);
+            args.extend(
+                [
+                    "-o", "StrictHostKeyChecking=no",
+                    "-o", "UserKnownHostsFile=/dev/null",
+                    "-o", "LogLevel=ERROR",
+                ]
+                .map(String::from),
+            );
+            args.push(format!("{}@127.0.0.1", VM_SSH_USER));
+        }
+        TerminalBackend::Ssh { host, port, user, identity } => {
+            if let Some(port) = port {
+                args.extend(["-p".to_string(), port.to_string()]);
+            }
+            if let Some(identity) = identity {
+                args.extend(["-i".to_string(), identity.to_string_lossy().into_owned()]);
+            }
+            args.push(match user {
+                Some(user) => format!("{}@{}", user, host),
+                None => host.clone(),
+            });
+        }
+        _ => {}
+    }
+    args
+}
+
+/// Where a project pane's auto-commit loop keeps its snapshots.
+#[derive(Debug, Clone, PartialEq)]
+struct Backup {
+    /// `bisque/backup/<project>/<session>`.
+    branch: String,
+    /// What the loop pushes to: `origin`, or `BACKUP_MOUNT`.
+    remote: String,
+    /// Host-side bare repo mounted at `BACKUP_MOUNT`.
+    host_repo: Option<PathBuf>,
+    /// Group owning `host_repo`.
+    host_group: Option<u32>,
+}
+
+impl Backup {
+    /// A new session's backup for `project`, creating the host-side repo
+    /// if it is used.
+    fn start(project: &Project, backend: &TerminalBackend) -> Self {
+        let session = format!(
+            "{}-{}-{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S"),
+            std::process::id(),
+            BACKUP_SESSIONS.fetch_add(1, Ordering::Relaxed) + 1,
+        );
+        let branch = format!("bisque/backup/{}/{}", project.name, session);
+        let host_repo = match (project.backup, backend) {
+            (BackupTarget::Host, TerminalBackend::Docker) => project.host_backup_repo().filter(|repo| init_bare_repo(repo)),
+            (BackupTarget::Host, _) => {
+                warn!(target: "terminal", "Only Docker panes can back up to this machine — {} pushes to origin", project.name);
+                None
+            }
+            (BackupTarget::Remote, _) => None,
+        };
+        let host_group = host_repo.as_deref().and_then(owning_group);
+        let remote = if host_repo.is_some() { BACKUP_MOUNT } else { "origin" };
+        info!(target: "terminal", branch = branch.as_str(), remote, "Backing up project pane");
+        Self { branch, remote: remote.to_string(), host_repo, host_group }
+    }
+}
+
+/// Create a bare repo at `repo` unless there is one. It is writable by
+/// its group, which containers pushing to it join (`--group-add`): the
+/// container's user is not the host's.
+fn init_bare_repo(repo: &Path) -> bool {
+    if repo.join("HEAD").is_file() {
+        return true;
+    }
+    let created = std::fs::create_dir_all(repo).is_ok()
+        && std::process::Command::new("git")
+            .args(["init", "--quiet", "--bare", "--shared=group"])
+            .arg(repo)
+            .status()
+            .is_ok_and(|status| status.success());
+    if !created {
+        warn!(target: "terminal", "Failed to create backup repo {} — pushing to origin", repo.display());
+    }
+    created
+}
+
+#[cfg(unix)]
+fn owning_group(path: &Path) -> Option<u32> {
+    std::fs::metadata(path).ok().map(|meta| std::os::unix::fs::MetadataExt::gid(&meta))
+}
+
+#[cfg(not(unix))]
+fn owning_group(_path: &Path) -> Option<u32> {
+    None
+}
+
+/// Shell command that clones `project` into its workspace directory and
+/// runs Claude Code there, with the variables from `env_file` (see
+/// `send_env`) set. A clone left by an earlier pane (the VM outlives its
+/// panes) is fetched rather than replaced; a failed clone ends the pane
+/// with git's error on screen.
+///
+/// With a `backup`, a loop beside Claude snapshots the work tree every
+/// `BACKUP_INTERVAL_SECS` and pushes every `BACKUP_PUSH_EVERY` snapshots,
+/// and a last snapshot is pushed when Claude exits.
+fn project_command(project: &Project, env_file: Option<&str>, backup: Option<&Backup>) -> String {
+    let dir = shell_quote(&project.workspace_dir());
+    let clone = format!(
+        "{load}mkdir -p /workspace && if [ -d {dir}/.git ]; then git -C {dir} fetch --quiet origin || true; \
+         else git clone --branch {branch} {url} {dir}; fi && cd {dir}",
+        load = env_file.map(load_env_file).unwrap_or_default(),
+        dir = dir,
+        branch = shell_quote(&project.branch),
+        url = shell_quote(&project.url),
+    );
+    let claude = "claude --dangerously-skip-permissions";
+    let Some(backup) = backup else {
+        return format!("{} && exec {}", clone, claude);
+    };
+    format!(
+        "{clone} || exit\n\
+         {functions}\
+         (n=0; while sleep {interval}; do bisque_backup; n=$((n + 1)); [ $((n % {every})) -ne 0 ] || bisque_push; done) &\n\
+         backup_loop=$!\n\
+         {claude}\n\
+         status=$?\n\
+         kill $backup_loop 2>/dev/null\n\
+         bisque_backup; bisque_push\n\
+         exit $status",
+        clone = clone,
+        functions = backup_functions(backup),
+        interval = BACKUP_INTERVAL_SECS,
+        every = BACKUP_PUSH_EVERY,
+        claude = claude,
+    )
+}
+
+/// Shell command for a closing pane: snapshot and push what is left.
+fn final_backup_command(project: &Project, backup: &Backup) -> String {
+    format!(
+        "cd {} || exit\n{}bisque_backup; bisque_push",
+        shell_quote(&project.workspace_dir()),
+        backup_functions(backup),
+    )
+}
+
+/// Shell functions run in the clone: `bisque_backup` commits the work
+/// tree to the backup branch through an index of its own, leaving the
+/// checkout and its staging area alone; `bisque_push` pushes the branch.
+/// Git's output goes to `.git/bisque-backup.log`, off the screen.
+fn backup_functions(backup: &Backup) -> String {
+    format!(
+        r#"bisque_backup() {{
+  (
+    export GIT_INDEX_FILE=.git/bisque-backup-index \
+      GIT_AUTHOR_NAME=bisque GIT_AUTHOR_EMAIL=bisque@localhost \
+      GIT_COMMITTER_NAME=bisque GIT_COMMITTER_EMAIL=bisque@localhost
+    parent=$(git rev-parse -q --verify {branch} || git rev-parse HEAD) || exit
+    git add -A && tree=$(git write-tree) || exit
+    [ "$tree" = "$(git rev-parse "$parent^{{tree}}")" ] && exit
+    commit=$(git commit-tree "$tree" -p "$parent" -m "auto-backup $(date -u +%Y-%m-%dT%H:%M:%SZ)") || exit
+    git update-ref {branch} "$commit"
+  ) >/dev/null 2>>.git/bisque-backup.log
+}}
+bisque_push() {{
+  if git push -q {remote} {branch} >/dev/null 2>>.git/bisque-backup.log; then echo ok; else echo failed; fi >.git/bisque-push-status
+}}
+"#,
+        branch = shell_quote(&format!("refs/heads/{}", backup.branch)),
+        remote = shell_quote(&backup.remote),
+    )
+}
+
+/// Shell command reporting on the project's backups: the Unix time of
+/// the backup branch's last snapshot, then `ok` or `failed` for its last
+/// push (left in `.git/bisque-push-status` by `bisque_push`).
+fn backup_report_command(project: &Project, backup: &Backup) -> String {
+    let dir = shell_quote(&project.workspace_dir());
+    format!(
+        "git -C {dir} log -1 --format=%ct {branch}; cat {dir}/.git/bisque-push-status",
+        dir = dir,
+        branch = shell_quote(&format!("refs/heads/{}", backup.branch)),
+    )
+}
+
+/// Every `BACKUP_INTERVAL_SECS` while the pane lives, run `program` with
+/// `query` (see `backup_report_command`) and record what it prints in the
+/// pane's `status`. The question goes over a connection of its own,
+/// never through the pane's terminal, so nothing running there can fake
+/// or garble the answer.
+fn watch_backup(status: Weak<BackupStatus>, program: &'static str, query: Vec<String>) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(BACKUP_INTERVAL_SECS.into()));
+        let Some(status) = status.upgrade() else { return };
+        let output = std::process::Command::new(program)
+            .args(&query)
+            .stdin(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .output();
+        if let Ok(output) = output {
+            read_backup_report(&String::from_utf8_lossy(&output.stdout), &status);
+        }
+    });
+}
 
-            TerminalPane::spawn_command(
-                width,
-                height,
-                "docker",
-                &args_refs,
-                &[],
-            )
+/// Apply the output of `backup_report_command` to `status`. Either line
+/// may be missing: no snapshot yet, or no push.
+fn read_backup_report(report: &str, status: &BackupStatus) {
+    for line in report.lines().map(str::trim) {
+        match line {
+            "ok" => status.push_failed.store(false, Ordering::Relaxed),
+            "failed" => status.push_failed.store(true, Ordering::Relaxed),
+            _ => {
+                if let Ok(secs) = line.parse() {
+                    status.saved.store(secs, Ordering::Relaxed);
+                }
+            }
         }
     }
 }
 
+/// Remote command that starts a login shell in the spec's directory with
+/// the variables from `env_file` set; `None` when there is neither.
+fn remote_shell_command(spec: &PaneSpec, env_file: Option<&str>) -> Option<String> {
+    if spec.cwd.is_none() && env_file.is_none() {
+        return None;
+    }
+    let mut command = env_file.map(load_env_file).unwrap_or_default();
+    if let Some(dir) = &spec.cwd {
+        command.push_str(&format!("cd {} && ", shell_quote(&dir.to_string_lossy())));
+    }
+    command.push_str("exec \"${SHELL:-/bin/sh}\" -l");
+    Some(command)
+}
+
+/// Quote `s` as a single word for a POSIX shell.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
 // ---------------------------------------------------------------------------
 // Geometry helpers
 // ---------------------------------------------------------------------------
@@ -535,6 +2175,21 @@ fn split_dims(w: f64, h: f64, dir: SplitDirection, ratio: f64) -> ((f64, f64), (
     }
 }
 
+/// Length of the overlap between spans `a0..a1` and `b0..b1`.
+fn span_overlap(a0: f64, a1: f64, b0: f64, b1: f64) -> f64 {
+    (a1.min(b1) - a0.max(b0)).max(0.0)
+}
+
+/// Keep both sides of a split in `rect` at least the minimum pane size.
+fn clamp_ratio(ratio: f64, dir: SplitDirection, rect: Rect) -> f64 {
+    let (extent, min) = match dir {
+        SplitDirection::Vertical => (rect.width() - SEPARATOR_PX, MIN_PANE_WIDTH),
+        SplitDirection::Horizontal => (rect.height() - SEPARATOR_PX, MIN_PANE_HEIGHT),
+    };
+    let limit = (min / extent).clamp(0.05, 0.5);
+    ratio.clamp(limit, 1.0 - limit)
+}
+
 fn second_origin(x: f64, y: f64, dir: SplitDirection, r1: (f64, f64)) -> (f64, f64) {
     match dir {
         SplitDirection::Vertical => (x + r1.0 + SEPARATOR_PX, y),
@@ -563,3 +2218,389 @@ fn draw_focus_border(scene: &mut Scene, x: f64, y: f64, w: f64, h: f64) {
     scene.fill(Fill::NonZero, Affine::IDENTITY, FOCUS_BORDER_COLOR, None, &Rect::new(x + w - b, y, x + w, y + h));
 }
 
+/// Border inside the focus border, so a focused receiving pane shows both.
+fn draw_sync_border(scene: &mut Scene, x: f64, y: f64, w: f64, h: f64) {
+    let (x, y, w, h) = (x + FOCUS_BORDER_PX, y + FOCUS_BORDER_PX, w - FOCUS_BORDER_PX * 2.0, h - FOCUS_BORDER_PX * 2.0);
+    let b = SYNC_BORDER_PX;
+    scene.fill(Fill::NonZero, Affine::IDENTITY, SYNC_BORDER_COLOR, None, &Rect::new(x, y, x + w, y + b));
+    scene.fill(Fill::NonZero, Affine::IDENTITY, SYNC_BORDER_COLOR, None, &Rect::new(x, y + h - b, x + w, y + h));
+    scene.fill(Fill::NonZero, Affine::IDENTITY, SYNC_BORDER_COLOR, None, &Rect::new(x, y, x + b, y + h));
+    scene.fill(Fill::NonZero, Affine::IDENTITY, SYNC_BORDER_COLOR, None, &Rect::new(x + w - b, y, x + w, y + h));
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ratios_keep_panes_above_minimum_size() {
+        let rect = Rect::new(0.0, 0.0, 802.0, 402.0);
+        assert_eq!(clamp_ratio(0.3, SplitDirection::Vertical, rect), 0.3);
+        assert_eq!(clamp_ratio(0.01, SplitDirection::Vertical, rect), 0.1);
+        assert_eq!(clamp_ratio(0.99, SplitDirection::Horizontal, rect), 0.9);
+        // Too small for two minimum panes: stay centred.
+        let narrow = Rect::new(0.0, 0.0, 100.0, 402.0);
+        assert_eq!(clamp_ratio(0.2, SplitDirection::Vertical, narrow), 0.5);
+    }
+
+    /// A playback pane told apart by its spec's directory.
+    fn named_leaf(name: &str) -> PaneNode {
+        let (_, rx) = tokio::sync::mpsc::unbounded_channel();
+        let spec = PaneSpec { cwd: Some(PathBuf::from(name)), ..PaneSpec::from(TerminalBackend::Local) };
+        PaneNode::Leaf { term: TerminalPane::playback(rx, 80, 24), spec }
+    }
+
+    fn split(direction: SplitDirection, ratio: f64, first: PaneNode, second: PaneNode) -> PaneNode {
+        PaneNode::Split { direction, ratio, first: Box::new(first), second: Box::new(second) }
+    }
+
+    fn tree_of(root: PaneNode, focus_path: &[FocusChild]) -> PaneTree {
+        let (_, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut tree = PaneTree::from_pane(TerminalPane::playback(rx, 80, 24), PaneSpec::from(TerminalBackend::Local));
+        tree.root = Some(root);
+        tree.focus_path = focus_path.to_vec();
+        tree
+    }
+
+    fn name_at(root: &PaneNode, path: &[FocusChild]) -> String {
+        match node_at_path(root, path) {
+            PaneNode::Leaf { spec, .. } => spec.cwd.as_ref().unwrap().display().to_string(),
+            PaneNode::Split { .. } => panic!("no leaf at {:?}", path),
+        }
+    }
+
+    /// Pane names in tree order.
+    fn names(tree: &PaneTree) -> Vec<String> {
+        let root = tree.root.as_ref().unwrap();
+        let mut paths = Vec::new();
+        collect_leaf_paths(root, &mut Vec::new(), &mut paths);
+        paths.iter().map(|path| name_at(root, path)).collect()
+    }
+
+    fn focused_name(tree: &PaneTree) -> String {
+        name_at(tree.root.as_ref().unwrap(), &tree.focus_path)
+    }
+
+    #[test]
+    fn directional_focus_follows_geometry() {
+        use FocusChild::{First, Second};
+        // An L: `a` the full height on the left, `b` over `c` on the right.
+        let l_shape = split(
+            SplitDirection::Vertical,
+            0.5,
+            named_leaf("a"),
+            split(SplitDirection::Horizontal, 0.5, named_leaf("b"), named_leaf("c")),
+        );
+        let mut tree = tree_of(l_shape, &[Second, Second]);
+        assert!(tree.focus_direction(FocusDirection::Left, 802.0, 602.0));
+        assert_eq!(focused_name(&tree), "a");
+        assert!(!tree.focus_direction(FocusDirection::Up, 802.0, 602.0));
+        assert_eq!(focused_name(&tree), "a");
+
+        // Left column split at 0.3, right at 0.5: going across picks the
+        // pane with the most overlap.
+        let grid = split(
+            SplitDirection::Vertical,
+            0.5,
+            split(SplitDirection::Horizontal, 0.3, named_leaf("a"), named_leaf("b")),
+            split(SplitDirection::Horizontal, 0.5, named_leaf("c"), named_leaf("d")),
+        );
+        let mut tree = tree_of(grid, &[Second, Second]);
+        assert!(tree.focus_direction(FocusDirection::Left, 802.0, 602.0));
+        assert_eq!(focused_name(&tree), "b");
+        assert!(tree.focus_direction(FocusDirection::Right, 802.0, 602.0));
+        assert_eq!(focused_name(&tree), "d");
+        assert!(tree.focus_direction(FocusDirection::Up, 802.0, 602.0));
+        assert_eq!(focused_name(&tree), "c");
+        assert!(tree.focus_direction(FocusDirection::Left, 802.0, 602.0));
+        assert_eq!(focused_name(&tree), "a");
+    }
+
+    #[test]
+    fn swap_and_rotate_keep_layout_and_follow_the_focused_pane() {
+        use FocusChild::{First, Second};
+        let root = split(
+            SplitDirection::Vertical,
+            0.4,
+            named_leaf("a"),
+            split(SplitDirection::Horizontal, 0.7, named_leaf("b"), named_leaf("c")),
+        );
+        let shape = layout_of(&root);
+        let mut tree = tree_of(root, &[Second, First]);
+
+        tree.swap_focused(true);
+        assert_eq!(names(&tree), ["a", "c", "b"]);
+        assert_eq!(tree.focus_path, [Second, Second]);
+        assert_eq!(focused_name(&tree), "b");
+
+        // Wraps round from the first pane to the last.
+        tree.focus_path = vec![First];
+        tree.swap_focused(false);
+        assert_eq!(names(&tree), ["b", "c", "a"]);
+        assert_eq!(focused_name(&tree), "a");
+
+        tree.rotate_panes();
+        assert_eq!(names(&tree), ["a", "b", "c"]);
+        assert_eq!(tree.focus_path, [First]);
+        assert_eq!(layout_of(tree.root.as_ref().unwrap()), shape);
+
+        // A reorder that would drop a pane is refused.
+        tree.reorder_leaves(|order, _| order[1] = 0);
+        assert_eq!(names(&tree), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn assemble_needs_a_leaf_for_every_slot() {
+        let root = split(SplitDirection::Vertical, 0.5, named_leaf("a"), named_leaf("b"));
+        let shape = layout_of(&root);
+        assert!(assemble(&shape, &mut [named_leaf("a")].into_iter()).is_none());
+
+        let mut leaves = [named_leaf("a"), named_leaf("b"), named_leaf("c")].into_iter();
+        let node = assemble(&shape, &mut leaves).unwrap();
+        assert_eq!(name_at(&node, &[FocusChild::Second]), "b");
+        assert_eq!(leaves.count(), 1);
+    }
+
+    #[test]
+    fn overlap_of_spans() {
+        assert_eq!(span_overlap(0.0, 100.0, 50.0, 200.0), 50.0);
+        assert_eq!(span_overlap(0.0, 100.0, 102.0, 200.0), 0.0);
+        assert_eq!(span_overlap(10.0, 20.0, 0.0, 100.0), 10.0);
+    }
+
+    #[test]
+    fn vm_shell_command_quotes_directory_and_environment() {
+        let mut spec = PaneSpec::from(TerminalBackend::Vm);
+        assert_eq!(remote_shell_command(&spec, None), None);
+        spec.cwd = Some(PathBuf::from("/srv/it's here"));
+        spec.env.insert("RUST_LOG".into(), "debug".into());
+        assert_eq!(
+            remote_shell_command(&spec, None).unwrap(),
+            r#"cd '/srv/it'\''s here' && exec "${SHELL:-/bin/sh}" -l"#,
+        );
+        // Variables come from the env-file, never the command line.
+        let command = remote_shell_command(&spec, Some(r#""$HOME"/.bisque-env/bisque-1-1"#)).unwrap();
+        assert_eq!(
+            command,
+            r#"f="$HOME"/.bisque-env/bisque-1-1; . "$f"; loaded=$?; rm -f "$f"; [ $loaded -eq 0 ] || exit $loaded; cd '/srv/it'\''s here' && exec "${SHELL:-/bin/sh}" -l"#,
+        );
+        assert!(!command.contains("debug"));
+        assert_eq!(env_file_contents(&spec.env), "export 'RUST_LOG=debug'\n");
+    }
+
+    #[test]
+    fn dockerfile_hash_is_stable() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert!(docker_image().starts_with("bisque-claude-code:"));
+    }
+
+    #[test]
+    fn image_build_checks_before_building() {
+        let script = image_build_script("img:1", Path::new("/tmp/build"));
+        assert!(script.starts_with("docker image inspect 'img:1' >/dev/null 2>&1 && exit 0\n"));
+        assert!(script.ends_with("exec docker build -t 'img:1' '/tmp/build'"));
+    }
+
+    #[test]
+    fn docker_args_apply_limits_and_isolation() {
+        let mut spec = PaneSpec::from(TerminalBackend::Docker);
+        spec.profile = Some("api".into());
+        let options = DockerOptions {
+            cpus: Some(2.5),
+            memory: Some("4g".into()),
+            pids: Some(256),
+            network: Some("none".into()),
+            read_only: true,
+        };
+        let args = docker_args(&spec, &options, "img:tag", "bisque-1-1", Some(Path::new("/tmp/bisque-1-1.env")), None);
+        let joined = args.join(" ");
+        assert!(joined.starts_with("run -it --rm --name bisque-1-1 --label com.fullyparsed.bisque-computer"));
+        assert!(joined.contains("--label com.fullyparsed.bisque-computer.profile=api"));
+        assert!(joined.contains("--env-file /tmp/bisque-1-1.env"));
+        assert!(joined.contains("--cpus 2.5 --memory 4g --pids-limit 256 --network none --read-only"));
+        assert!(joined.ends_with("img:tag --dangerously-skip-permissions"));
+        // No key or variable is ever on the command line.
+        assert!(!args.iter().any(|a| a == "-e"));
+
+        let plain = docker_args(&PaneSpec::from(TerminalBackend::Docker), &DockerOptions::default(), "img:tag", "n", None, None);
+        assert!(!plain.iter().any(|a| a.starts_with("--cpus") || a == "--read-only" || a == "--env-file"));
+    }
+
+    #[test]
+    fn read_only_mount_points_come_from_the_image() {
+        let options = DockerOptions { read_only: true, ..DockerOptions::default() };
+        let args = docker_args(&PaneSpec::from(TerminalBackend::Docker), &options, "img:tag", "n", None, None);
+        let targets: Vec<&str> = args
+            .windows(2)
+            .filter(|pair| pair[0] == "--tmpfs")
+            .map(|pair| pair[1].split(':').next().unwrap())
+            .collect();
+        assert!(targets.contains(&"/workspace"));
+        for target in targets.into_iter().filter(|&t| t != "/tmp") {
+            assert!(DOCKERFILE.contains(target), "the Dockerfile does not make {}", target);
+        }
+        // An image from an older Dockerfile is never reused.
+        assert!(docker_image().ends_with(&format!("{:016x}", fnv1a(DOCKERFILE.as_bytes()))));
+    }
+
+    #[test]
+    fn project_panes_clone_before_starting_claude() {
+        let project = Project {
+            name: "bisque".into(),
+            url: "git@host:me/bisque.git".into(),
+            branch: "main".into(),
+            backup: BackupTarget::Remote,
+        };
+        let mut spec = PaneSpec { project: Some(project.clone()), ..PaneSpec::from(TerminalBackend::Vm) };
+        spec.env.insert("RUST_LOG".into(), "debug".into());
+        let command = project_command(&project, None, None);
+        assert_eq!(
+            command,
+            "mkdir -p /workspace && if [ -d '/workspace/bisque'/.git ]; then git -C '/workspace/bisque' fetch --quiet origin || true; \
+             else git clone --branch 'main' 'git@host:me/bisque.git' '/workspace/bisque'; fi && cd '/workspace/bisque' \
+             && exec claude --dangerously-skip-permissions",
+        );
+        let command = project_command(&project, Some("/e"), None);
+        assert!(command.starts_with("f=/e; . \"$f\"; loaded=$?; rm -f \"$f\"; [ $loaded -eq 0 ] || exit $loaded; mkdir -p /workspace"));
+        assert!(!command.contains("RUST_LOG"));
+        assert_eq!(spec.label(), "vm · bisque · main");
+
+        let docker = PaneSpec { project: Some(project), ..PaneSpec::from(TerminalBackend::Docker) };
+        let args = docker_args(&docker, &DockerOptions::default(), "img:tag", "n", None, None);
+        assert_eq!(args[args.len() - 5..args.len() - 2], ["--entrypoint".to_string(), "sh".into(), "img:tag".into()]);
+        // Variables go in the env-file, not the command.
+        assert!(args.last().unwrap().ends_with("exec claude --dangerously-skip-permissions"));
+    }
+
+    #[test]
+    fn project_panes_back_up_beside_claude() {
+        let project = Project {
+            name: "bisque".into(),
+            url: "https://example.com/bisque.git".into(),
+            branch: "main".into(),
+            backup: BackupTarget::Host,
+        };
+        let backup = Backup {
+            branch: "bisque/backup/bisque/20261018-120000-1-1".into(),
+            remote: BACKUP_MOUNT.into(),
+            host_repo: Some(PathBuf::from("/data/projects/backups/bisque.git")),
+            host_group: Some(1000),
+        };
+        let spec = PaneSpec { project: Some(project.clone()), ..PaneSpec::from(TerminalBackend::Docker) };
+        let args = docker_args(&spec, &DockerOptions::default(), "img:tag", "n", None, Some(&backup));
+        assert!(args.join(" ").contains("-v /data/projects/backups/bisque.git:/bisque-backup --group-add 1000"));
+
+        let command = args.last().unwrap();
+        // Claude runs in the foreground; the loop is stopped and a last
+        // snapshot pushed once it exits.
+        assert!(command.contains("\nclaude --dangerously-skip-permissions\nstatus=$?\n"));
+        assert!(command.contains("while sleep 30; do bisque_backup;"));
+        assert!(command.ends_with("bisque_backup; bisque_push\nexit $status"));
+        assert!(command.contains("git push -q '/bisque-backup' 'refs/heads/bisque/backup/bisque/20261018-120000-1-1'"));
+
+        let close = final_backup_command(&project, &backup);
+        assert!(close.starts_with("cd '/workspace/bisque' || exit\nbisque_backup() {"));
+
+        // The host asks the clone for the branch's time; nothing is
+        // reported on Claude's terminal.
+        assert!(!command.contains("\\033]"));
+        assert_eq!(
+            backup_report_command(&project, &backup),
+            "git -C '/workspace/bisque' log -1 --format=%ct 'refs/heads/bisque/backup/bisque/20261018-120000-1-1'; \
+             cat '/workspace/bisque'/.git/bisque-push-status"
+        );
+        assert!(command.contains("then echo ok; else echo failed; fi >.git/bisque-push-status"));
+
+        let status = BackupStatus::default();
+        read_backup_report("1760788800\nfailed\n", &status);
+        assert_eq!(status.saved.load(Ordering::Relaxed), 1760788800);
+        assert!(status.push_failed.load(Ordering::Relaxed));
+        // Before the first snapshot only the push is reported.
+        read_backup_report("ok\n", &status);
+        assert!(!status.push_failed.load(Ordering::Relaxed));
+        assert_eq!(status.saved.load(Ordering::Relaxed), 1760788800);
+    }
+
+    /// Collects what a pane writes to its backend.
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn captured_pane() -> (TerminalPane, Arc<Mutex<Vec<u8>>>) {
+        let (_, rx) = tokio::sync::mpsc::unbounded_channel();
+        let written = Arc::default();
+        let pane = TerminalPane::from_streams(rx, Box::new(Captured(Arc::clone(&written))), Box::new(terminal::NoResize), 80, 24);
+        (pane, written)
+    }
+
+    #[test]
+    fn synchronized_input_reaches_all_or_marked_panes() {
+        let spec = PaneSpec::from(TerminalBackend::Local);
+        let (a, a_in) = captured_pane();
+        let (b, b_in) = captured_pane();
+        let (c, c_in) = captured_pane();
+        let mut tree = PaneTree::from_pane(a, spec.clone());
+        tree.insert_pane(b, spec.clone(), 900.0, 600.0);
+        tree.insert_pane(c, spec, 900.0, 600.0);
+        let taken = |input: &Arc<Mutex<Vec<u8>>>| {
+            String::from_utf8(std::mem::take(&mut *input.lock().unwrap())).unwrap()
+        };
+
+        tree.paste("ls");
+        assert_eq!((taken(&a_in), taken(&b_in), taken(&c_in)), (String::new(), String::new(), "ls".into()));
+
+        tree.toggle_sync_all();
+        tree.commit_text("pwd");
+        assert_eq!((taken(&a_in), taken(&b_in), taken(&c_in)), ("pwd".into(), "pwd".into(), "pwd".into()));
+
+        // Mark the focused pane, then type from another: only the marked
+        // pane follows.
+        tree.toggle_sync_mark();
+        assert_eq!(tree.sync_input(), SyncInput::Marked);
+        tree.cycle_focus(true);
+        tree.paste("x");
+        assert_eq!((taken(&a_in), taken(&b_in), taken(&c_in)), ("x".into(), String::new(), "x".into()));
+
+        tree.cycle_focus(false);
+        tree.toggle_sync_mark();
+        assert_eq!(tree.sync_input(), SyncInput::Off);
+
+        // Closing the only marked pane stops synchronizing, too.
+        tree.toggle_sync_mark();
+        assert!(tree.close_focused());
+        assert_eq!(tree.sync_input(), SyncInput::Off);
+    }
+
+    #[test]
+    fn ssh_args_leave_unset_fields_to_ssh() {
+        let alias = PaneSpec::from(TerminalBackend::Ssh { host: "lobster".into(), port: None, user: None, identity: None });
+        assert_eq!(ssh_args(&alias, None), ["-t", "lobster"]);
+        assert_eq!(alias.label(), "ssh · lobster");
+
+        let full = PaneSpec::from(TerminalBackend::Ssh {
+            host: "10.0.0.5".into(),
+            port: Some(2200),
+            user: Some("admin".into()),
+            identity: Some(PathBuf::from("/keys/lobster")),
+        });
+        assert_eq!(ssh_args(&full, None), ["-t", "-p", "2200", "-i", "/keys/lobster", "admin@10.0.0.5"]);
+    }
+
+    #[test]
+    fn separator_sits_at_the_ratio() {
+        let sep = separator_rect(10.0, 0.0, 402.0, 300.0, SplitDirection::Vertical, 0.25);
+        assert_eq!(sep, Rect::new(110.0, 0.0, 112.0, 300.0));
+        let ((w1, _), (w2, _)) = split_dims(402.0, 300.0, SplitDirection::Vertical, 0.25);
+        assert_eq!((w1, w2), (100.0, 300.0));
+    }
+}
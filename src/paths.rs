@@ -33,6 +33,8 @@ pub struct BisquePaths {
     pub models: PathBuf,
     /// SQLite databases, indexes
     pub db: PathBuf,
+    /// Terminal session recordings (asciicast v2)
+    pub recordings: PathBuf,
     /// Regenerable cache data
     pub cache: PathBuf,
     /// Application logs
@@ -57,6 +59,7 @@ impl BisquePaths {
             vms: data.join("vms"),
             models: data.join("models"),
             db: data.join("db"),
+            recordings: data.join("recordings"),
             data,
             cache,
             logs,
@@ -74,6 +77,7 @@ impl BisquePaths {
             &self.vms,
             &self.models,
             &self.db,
+            &self.recordings,
             &self.cache,
             &self.logs,
         ];
@@ -190,6 +194,7 @@ mod tests {
         assert!(paths.vms.ends_with("vms"));
         assert!(paths.models.ends_with("models"));
         assert!(paths.db.ends_with("db"));
+        assert!(paths.recordings.ends_with("recordings"));
     }
 
     #[test]
@@ -210,6 +215,7 @@ mod tests {
             vms: tmp.join("data/vms"),
             models: tmp.join("data/models"),
             db: tmp.join("data/db"),
+            recordings: tmp.join("data/recordings"),
             cache: tmp.join("cache"),
             logs: tmp.join("logs"),
         };
@@ -222,6 +228,7 @@ mod tests {
         assert!(paths.vms.is_dir());
         assert!(paths.models.is_dir());
         assert!(paths.db.is_dir());
+        assert!(paths.recordings.is_dir());
         assert!(paths.cache.is_dir());
         assert!(paths.logs.is_dir());
 
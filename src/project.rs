@@ -0,0 +1,213 @@
+//! Project registry: git repositories to open Claude Code on.
+//!
+//! A project is a name and a git URL, not a local path, so it opens the
+//! same way on any machine. The registry is `projects.toml` in
+//! `BisquePaths::projects`:
+//!
+//! ```toml
+//! [[project]]
+//! name = "bisque-computer"
+//! url = "git@github.com:fullyparsed/bisque-computer.git"
+//! branch = "main"                # the default
+//! backend = "docker"             # docker (the default) or vm
+//! backup = "host"                # the default: a bare repo on this machine;
+//!                                # or "remote", the project's origin
+//! ```
+//!
+//! Opening a project starts a pane that clones it into
+//! `/workspace/<name>` inside the container or VM, then runs `claude`
+//! there. Alongside it an auto-commit loop snapshots the work tree to
+//! `bisque/backup/<name>/<session>` and pushes that branch, so tearing
+//! the sandbox down loses nothing. Like `panes.toml`, the file is read
+//! each time the list opens.
+
+use std::path::PathBuf;
+
+use tracing::warn;
+
+use crate::pane_profiles::PaneProfile;
+use crate::pane_tree::{PaneSpec, TerminalBackend};
+use crate::paths::BisquePaths;
+
+/// A repository a pane can be opened on.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Project {
+    pub name: String,
+    /// Anything `git clone` accepts.
+    pub url: String,
+    #[serde(default = "default_branch")]
+    pub branch: String,
+    #[serde(default)]
+    pub backup: BackupTarget,
+}
+
+/// Where a project pane pushes its backup branch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupTarget {
+    /// The project's own remote, `origin`. The sandbox needs credentials
+    /// for it, which it is not given by default.
+    Remote,
+    /// A bare repo in `BisquePaths::projects`, which works with no
+    /// credentials at all. Docker panes only: the VM can't reach it.
+    #[default]
+    Host,
+}
+
+fn default_branch() -> String {
+    "main".into()
+}
+
+impl Project {
+    /// Where the project is cloned inside the container or VM.
+    pub fn workspace_dir(&self) -> String {
+        format!("/workspace/{}", self.name)
+    }
+
+    /// Whether the name is safe to build paths from. The registry only
+    /// lets such names in, but a project can also come back from a saved
+    /// session, so the paths check again.
+    pub fn has_safe_name(&self) -> bool {
+        is_directory_name(&self.name)
+    }
+
+    /// The bare repo `BackupTarget::Host` pushes to; `None` for a name
+    /// that would lead outside the backups directory.
+    pub fn host_backup_repo(&self) -> Option<PathBuf> {
+        if !self.has_safe_name() {
+            warn!(target: "terminal", "Not backing up project {:?} to this machine: unsafe name", self.name);
+            return None;
+        }
+        BisquePaths::resolve().map(|p| p.projects.join("backups").join(format!("{}.git", self.name)))
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RegistryFile {
+    #[serde(default)]
+    project: Vec<ProjectEntry>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ProjectEntry {
+    #[serde(flatten)]
+    project: Project,
+    #[serde(default = "default_backend")]
+    backend: TerminalBackend,
+}
+
+fn default_backend() -> TerminalBackend {
+    TerminalBackend::Docker
+}
+
+/// Path to `projects.toml`.
+pub fn registry_path() -> Option<PathBuf> {
+    BisquePaths::resolve().map(|p| p.projects.join("projects.toml"))
+}
+
+/// The registered projects, as choices for the split picker. A missing or
+/// unreadable registry gives none.
+pub fn load() -> Vec<PaneProfile> {
+    let Some(path) = registry_path() else { return Vec::new() };
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        warn!(target: "terminal", "No projects registered — add them to {}", path.display());
+        return Vec::new();
+    };
+    let (projects, warnings) = parse(&content);
+    for w in &warnings {
+        warn!(target: "terminal", "{}: {}", path.display(), w);
+    }
+    projects
+}
+
+/// Read the projects in a `projects.toml` document, with warnings for the
+/// entries that were skipped.
+pub fn parse(toml_str: &str) -> (Vec<PaneProfile>, Vec<String>) {
+    let file: RegistryFile = match toml::from_str(toml_str) {
+        Ok(f) => f,
+        Err(e) => return (Vec::new(), vec![format!("parse error, ignoring projects: {}", e)]),
+    };
+    let mut warnings = Vec::new();
+    let projects = file
+        .project
+        .into_iter()
+        .filter_map(|entry| {
+            let ProjectEntry { project, backend } = entry;
+            if !is_directory_name(&project.name) {
+                warnings.push(format!("project {:?}: names may only use letters, digits, '.', '_' and '-'", project.name));
+                return None;
+            }
+            if !matches!(backend, TerminalBackend::Docker | TerminalBackend::Vm) {
+                warnings.push(format!("project {:?}: opens in docker or vm, not {}", project.name, backend.label()));
+                return None;
+            }
+            Some(PaneProfile {
+                name: project.name.clone(),
+                spec: PaneSpec { project: Some(project), ..PaneSpec::from(backend) },
+            })
+        })
+        .collect();
+    (projects, warnings)
+}
+
+/// A name that is safe as the last component of the clone's path.
+fn is_directory_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != "."
+        && name != ".."
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_projects_and_skips_bad_ones() {
+        let (projects, warnings) = parse(
+            r#"
+            [[project]]
+            name = "bisque-computer"
+            url = "git@github.com:fullyparsed/bisque-computer.git"
+
+            [[project]]
+            name = "lobster"
+            url = "https://example.com/lobster.git"
+            branch = "dev"
+            backend = "vm"
+            backup = "remote"
+
+            [[project]]
+            name = "../escape"
+            url = "https://example.com/x.git"
+
+            [[project]]
+            name = "here"
+            url = "https://example.com/here.git"
+            backend = "local"
+            "#,
+        );
+        assert_eq!(warnings.len(), 2, "{:?}", warnings);
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].spec.backend, TerminalBackend::Docker);
+        let project = projects[0].spec.project.as_ref().unwrap();
+        assert_eq!(project.branch, "main");
+        assert_eq!(project.workspace_dir(), "/workspace/bisque-computer");
+        assert_eq!(project.backup, BackupTarget::Host);
+        assert_eq!(projects[1].spec.label(), "vm · lobster · dev");
+        assert_eq!(projects[1].spec.project.as_ref().unwrap().backup, BackupTarget::Remote);
+    }
+
+    #[test]
+    fn unsafe_names_build_no_paths() {
+        // As from a hand-edited session, which the registry never saw.
+        let project = Project {
+            name: "../escape".into(),
+            url: "https://example.com/x.git".into(),
+            branch: "main".into(),
+            backup: BackupTarget::Host,
+        };
+        assert!(!project.has_safe_name());
+        assert_eq!(project.host_backup_repo(), None);
+    }
+}
@@ -0,0 +1,191 @@
+//! Vertical scrolling for the typographic screens (dashboard and info).
+//!
+//! Each screen owns a `ScrollState` on `App`. The renderer reports how tall
+//! the laid-out content is and how much room it had; input handlers (mouse
+//! wheel, trackpad, PageUp/PageDown) move the offset, which is always clamped
+//! to the scrollable range.
+//!
+//! Rendering follows the same pattern on every screen:
+//!
+//! ```text
+//! content drawn into its own Scene at unscrolled coordinates
+//!       │
+//!       ▼
+//! push_clip(viewport) → scene.append(content, translate(0, -offset)) → pop_layer
+//!       │
+//!       ▼
+//! draw_overflow_indicator(viewport)   (only when content overflows)
+//! ```
+
+use vello::Scene;
+use vello::kurbo::{Affine, Rect};
+use vello::peniko::{Color, Fill};
+
+/// Pixels scrolled per wheel "line" (mouse wheels report line deltas).
+pub const LINE_SCROLL_PX: f64 = 40.0;
+
+/// Fraction of the viewport moved by PageUp / PageDown (keeps a little context).
+const PAGE_FRACTION: f64 = 0.9;
+
+/// Overflow indicator: thin thumb hugging the right edge.
+const INDICATOR_WIDTH: f64 = 3.0;
+const INDICATOR_INSET: f64 = 8.0;
+const INDICATOR_MIN_THUMB: f64 = 24.0;
+const INDICATOR_COLOR: Color = Color::new([0.0, 0.0, 0.0, 0.18]);
+/// Hairline drawn at the bottom edge while more content lies below.
+const MORE_BELOW_COLOR: Color = Color::new([0.0, 0.0, 0.0, 0.10]);
+
+/// Scroll position and extents for one screen.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScrollState {
+    /// Current offset in pixels from the top of the content.
+    pub offset: f64,
+    /// Total height of the laid-out content (reported by the renderer).
+    content_height: f64,
+    /// Height of the visible viewport (reported by the renderer).
+    viewport_height: f64,
+}
+
+impl ScrollState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the latest layout extents and re-clamp the offset.
+    ///
+    /// Called after each render so a window resize or shrinking data set
+    /// never leaves the viewport scrolled past the end of the content.
+    pub fn set_extents(&mut self, content_height: f64, viewport_height: f64) {
+        self.content_height = content_height.max(0.0);
+        self.viewport_height = viewport_height.max(0.0);
+        self.clamp();
+    }
+
+    /// Largest valid offset for the current extents.
+    pub fn max_offset(&self) -> f64 {
+        (self.content_height - self.viewport_height).max(0.0)
+    }
+
+    /// Whether the content is taller than the viewport.
+    pub fn is_overflowing(&self) -> bool {
+        self.max_offset() > 0.5
+    }
+
+    /// Scroll by `dy` pixels (positive = towards the end of the content).
+    pub fn scroll_by(&mut self, dy: f64) {
+        self.offset += dy;
+        self.clamp();
+    }
+
+    /// Scroll by one page down (`forward = true`) or up.
+    pub fn page(&mut self, forward: bool) {
+        let step = self.viewport_height * PAGE_FRACTION;
+        self.scroll_by(if forward { step } else { -step });
+    }
+
+    fn clamp(&mut self) {
+        self.offset = self.offset.clamp(0.0, self.max_offset());
+    }
+}
+
+/// Convert a winit wheel delta into a pixel scroll amount.
+///
+/// winit reports positive `y` when the user scrolls up (content should move
+/// down), so the sign is flipped to match `ScrollState::scroll_by`.
+pub fn wheel_delta_px(delta: &winit::event::MouseScrollDelta) -> f64 {
+    match delta {
+        winit::event::MouseScrollDelta::LineDelta(_x, y) => -(*y as f64) * LINE_SCROLL_PX,
+        winit::event::MouseScrollDelta::PixelDelta(pos) => -pos.y,
+    }
+}
+
+/// Push a clipping layer restricting subsequent drawing to `rect`.
+///
+/// Must be balanced with `scene.pop_layer()`.
+pub fn push_clip(scene: &mut Scene, rect: &Rect) {
+    scene.push_clip_layer(Affine::IDENTITY, rect);
+}
+
+/// Composite `content` (laid out at unscrolled coordinates) into `scene`,
+/// clipped to `viewport` and shifted up by the scroll offset.
+pub fn append_scrolled(scene: &mut Scene, content: &Scene, viewport: &Rect, state: &ScrollState) {
+    push_clip(scene, viewport);
+    scene.append(content, Some(Affine::translate((0.0, -state.offset))));
+    scene.pop_layer();
+}
+
+/// Draw the overflow indicator along the right edge of `viewport`.
+///
+/// Typographic and quiet: a 3px thumb showing position within the content,
+/// plus a hairline at the bottom edge while there is more content below.
+/// Draws nothing when the content fits.
+pub fn draw_overflow_indicator(scene: &mut Scene, viewport: &Rect, state: &ScrollState) {
+    if !state.is_overflowing() {
+        return;
+    }
+
+    let track_h = viewport.height();
+    let thumb_h = (track_h * state.viewport_height / state.content_height.max(1.0))
+        .clamp(INDICATOR_MIN_THUMB.min(track_h), track_h);
+    let frac = state.offset / state.max_offset();
+    let thumb_y = viewport.y0 + frac * (track_h - thumb_h);
+    let x = viewport.x1 - INDICATOR_INSET;
+    let thumb = Rect::new(x, thumb_y, x + INDICATOR_WIDTH, thumb_y + thumb_h);
+    scene.fill(Fill::NonZero, Affine::IDENTITY, INDICATOR_COLOR, None, &thumb);
+
+    if state.offset < state.max_offset() - 0.5 {
+        let rule = Rect::new(viewport.x0, viewport.y1 - 1.0, viewport.x1, viewport.y1);
+        scene.fill(Fill::NonZero, Affine::IDENTITY, MORE_BELOW_COLOR, None, &rule);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_that_fits_does_not_scroll() {
+        let mut s = ScrollState::new();
+        s.set_extents(300.0, 600.0);
+        s.scroll_by(100.0);
+        assert_eq!(s.offset, 0.0);
+        assert!(!s.is_overflowing());
+    }
+
+    #[test]
+    fn scroll_is_clamped_to_range() {
+        let mut s = ScrollState::new();
+        s.set_extents(1000.0, 400.0);
+        s.scroll_by(5000.0);
+        assert_eq!(s.offset, 600.0);
+        s.scroll_by(-9000.0);
+        assert_eq!(s.offset, 0.0);
+    }
+
+    #[test]
+    fn page_moves_most_of_a_viewport() {
+        let mut s = ScrollState::new();
+        s.set_extents(2000.0, 500.0);
+        s.page(true);
+        assert!((s.offset - 450.0).abs() < 1e-9);
+        s.page(false);
+        assert_eq!(s.offset, 0.0);
+    }
+
+    #[test]
+    fn shrinking_content_reclamps_offset() {
+        let mut s = ScrollState::new();
+        s.set_extents(2000.0, 500.0);
+        s.scroll_by(1500.0);
+        s.set_extents(800.0, 500.0);
+        assert_eq!(s.offset, 300.0);
+    }
+
+    #[test]
+    fn wheel_up_scrolls_towards_top() {
+        let up = winit::event::MouseScrollDelta::LineDelta(0.0, 1.0);
+        assert!(wheel_delta_px(&up) < 0.0);
+        let px = winit::event::MouseScrollDelta::PixelDelta(winit::dpi::PhysicalPosition::new(0.0, -12.0));
+        assert_eq!(wheel_delta_px(&px), 12.0);
+    }
+}
@@ -0,0 +1,247 @@
+//! Session state persisted across launches.
+//!
+//! Written to `<data>/session.json` when the app exits and read back at
+//! startup: the visible screen, the terminal workspaces (name, backend,
+//! and pane layout: split ratios, the backend each pane runs, per-pane font
+//! size), window geometry and fullscreen state, and the design REPL history.
+//!
+//! Restoring is best-effort. A missing or unreadable file starts a fresh
+//! session, and unknown or missing fields fall back to their defaults so
+//! older files keep loading as the format grows.
+
+use std::path::{Path, PathBuf};
+
+use tracing::{info, warn};
+
+use crate::ScreenIndex;
+use crate::pane_tree::{FocusChild, PaneLayout, TerminalBackend};
+use crate::paths::BisquePaths;
+
+const SESSION_FILE: &str = "session.json";
+
+/// Most recent REPL lines kept in the session file.
+const MAX_REPL_HISTORY: usize = 200;
+
+/// Smallest restored window, so a bad file can't produce an unusable window.
+const MIN_WINDOW_WIDTH: u32 = 320;
+const MIN_WINDOW_HEIGHT: u32 = 200;
+
+/// Outer position and inner size of the window, in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub fullscreen: bool,
+}
+
+impl WindowGeometry {
+    /// Whether the saved size is large enough to restore.
+    pub fn is_usable(&self) -> bool {
+        self.width >= MIN_WINDOW_WIDTH && self.height >= MIN_WINDOW_HEIGHT
+    }
+}
+
+/// One terminal workspace.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WorkspaceState {
+    pub name: String,
+    /// Backend new panes in the workspace run on.
+    pub backend: TerminalBackend,
+    /// `None` when every pane had been closed.
+    #[serde(default)]
+    pub panes: Option<PaneLayout>,
+    #[serde(default)]
+    pub focus_path: Vec<FocusChild>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct SessionState {
+    pub screen: ScreenIndex,
+    pub workspaces: Vec<WorkspaceState>,
+    /// Index into `workspaces` of the one showing.
+    pub active_workspace: usize,
+    pub window: Option<WindowGeometry>,
+    pub repl_history: Vec<String>,
+}
+
+/// `<data>/session.json`, or `None` if HOME cannot be resolved.
+pub fn session_path() -> Option<PathBuf> {
+    BisquePaths::resolve().map(|p| p.data.join(SESSION_FILE))
+}
+
+impl SessionState {
+    /// Load the previous session, if there is one.
+    pub fn load() -> Option<Self> {
+        let path = session_path()?;
+        let state = Self::load_from(&path)?;
+        info!(target: "session", "Restoring session from {}", path.display());
+        Some(state)
+    }
+
+    pub fn load_from(path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        match serde_json::from_str(&content) {
+            Ok(state) => Some(state),
+            Err(e) => {
+                warn!(target: "session", "Ignoring unreadable {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Save to `<data>/session.json`, logging (not returning) failures.
+    pub fn save(&self) {
+        let Some(path) = session_path() else {
+            warn!(target: "session", "Could not resolve HOME — session not saved");
+            return;
+        };
+        match self.save_to(&path) {
+            Ok(()) => info!(target: "session", "Saved session to {}", path.display()),
+            Err(e) => warn!(target: "session", "Failed to save {}: {}", path.display(), e),
+        }
+    }
+
+    /// Write atomically (temp file + rename) so a crash mid-write can't
+    /// leave a truncated session behind.
+    pub fn save_to(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut state = self.clone();
+        let excess = state.repl_history.len().saturating_sub(MAX_REPL_HISTORY);
+        state.repl_history.drain(..excess);
+
+        let json = serde_json::to_string_pretty(&state)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pane_tree::{DockerOptions, SplitDirection};
+    use crate::project::{BackupTarget, Project};
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!(
+                "bisque_session_test_{}_{}",
+                std::process::id(),
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_nanos()
+            ))
+            .join(name)
+    }
+
+    fn leaf(backend: TerminalBackend, font_size: f32) -> PaneLayout {
+        PaneLayout::Leaf { backend, font_size, profile: None, cwd: None, env: Default::default(), docker: None, project: None }
+    }
+
+    fn sample() -> SessionState {
+        SessionState {
+            screen: ScreenIndex::Terminal,
+            workspaces: vec![
+                WorkspaceState {
+                    name: "bisque".into(),
+                    backend: TerminalBackend::Local,
+                    panes: Some(PaneLayout::Split {
+                        direction: SplitDirection::Vertical,
+                        ratio: 0.3,
+                        first: Box::new(leaf(TerminalBackend::Local, 24.0)),
+                        second: Box::new(PaneLayout::Leaf {
+                            backend: TerminalBackend::Vm,
+                            font_size: 28.0,
+                            profile: Some("lobster".into()),
+                            cwd: Some("/srv/lobster".into()),
+                            env: [("RUST_LOG".to_string(), "debug".to_string())].into(),
+                            docker: None,
+                            project: None,
+                        }),
+                    }),
+                    focus_path: vec![FocusChild::Second],
+                },
+                WorkspaceState {
+                    name: "claude".into(),
+                    backend: TerminalBackend::Docker,
+                    panes: Some(PaneLayout::Leaf {
+                        backend: TerminalBackend::Docker,
+                        font_size: 24.0,
+                        profile: None,
+                        cwd: None,
+                        env: Default::default(),
+                        docker: Some(DockerOptions { memory: Some("4g".into()), read_only: true, ..Default::default() }),
+                        project: Some(Project {
+                            name: "bisque".into(),
+                            url: "https://example.com/bisque.git".into(),
+                            branch: "main".into(),
+                            backup: BackupTarget::Host,
+                        }),
+                    }),
+                    focus_path: Vec::new(),
+                },
+                WorkspaceState {
+                    name: "lobster".into(),
+                    backend: TerminalBackend::Ssh { host: "lobster.local".into(), port: Some(22), user: None, identity: None },
+                    panes: None,
+                    focus_path: Vec::new(),
+                },
+            ],
+            active_workspace: 1,
+            window: Some(WindowGeometry { x: 40, y: 60, width: 1440, height: 900, fullscreen: false }),
+            repl_history: vec!["get ink.primary".into(), "set type.base 20".into()],
+        }
+    }
+
+    #[test]
+    fn roundtrips_through_disk() {
+        let path = temp_path(SESSION_FILE);
+        let state = sample();
+        state.save_to(&path).unwrap();
+        assert_eq!(SessionState::load_from(&path), Some(state));
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn missing_fields_fall_back_to_defaults() {
+        let state: SessionState = serde_json::from_str(r#"{ "screen": "info" }"#).unwrap();
+        assert_eq!(state.screen, ScreenIndex::Info);
+        assert!(state.workspaces.is_empty());
+        assert!(state.repl_history.is_empty());
+    }
+
+    #[test]
+    fn unreadable_file_is_ignored() {
+        let path = temp_path(SESSION_FILE);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(SessionState::load_from(&path), None);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn repl_history_is_capped_on_save() {
+        let path = temp_path(SESSION_FILE);
+        let state = SessionState {
+            repl_history: (0..MAX_REPL_HISTORY + 25).map(|i| format!("get {}", i)).collect(),
+            ..SessionState::default()
+        };
+        state.save_to(&path).unwrap();
+        let loaded = SessionState::load_from(&path).unwrap();
+        assert_eq!(loaded.repl_history.len(), MAX_REPL_HISTORY);
+        assert_eq!(loaded.repl_history.last().map(String::as_str), Some("get 224"));
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn tiny_window_geometry_is_not_usable() {
+        let g = WindowGeometry { x: 0, y: 0, width: 10, height: 10, fullscreen: false };
+        assert!(!g.is_usable());
+    }
+}
@@ -0,0 +1,325 @@
+//! Shell integration escapes that `alacritty_terminal` does not handle.
+//!
+//! - OSC 7 (`ESC ] 7 ; file://host/path ST`) reports the shell's working
+//!   directory; new splits open there.
+//! - OSC 133 (FinalTerm semantic prompts) marks where each prompt, command
+//!   line and command output starts, and where a command ended:
+//!
+//! ```text
+//! ESC ] 133 ; A ST   prompt start        ESC ] 133 ; C ST         output start
+//! ESC ] 133 ; B ST   command line start  ESC ] 133 ; D [; code] ST command finished
+//! ```
+//!
+//! `OscScanner` finds these in the raw PTY stream before it reaches the VTE
+//! parser, reporting the byte offset just past each sequence so the caller
+//! can feed the parser up to that point and read the cursor position where
+//! the mark belongs. `CommandMarks` keeps those positions as absolute line
+//! numbers counted from the top of the scrollback.
+
+use std::path::PathBuf;
+
+/// Longest OSC payload collected. Longer ones (OSC 52 clipboard data,
+/// OSC 8 links) are none of ours and are skipped without buffering.
+const MAX_PAYLOAD: usize = 4096;
+
+/// Commands remembered per pane; older ones are dropped.
+const MAX_COMMANDS: usize = 1000;
+
+/// A shell integration event found in the output stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShellEvent {
+    /// OSC 7: the shell's working directory.
+    Cwd(PathBuf),
+    /// OSC 133;A
+    PromptStart,
+    /// OSC 133;B
+    CommandStart,
+    /// OSC 133;C
+    OutputStart,
+    /// OSC 133;D, with the exit code if the shell sent one.
+    CommandEnd(Option<i32>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ScanState {
+    #[default]
+    Ground,
+    Escape,
+    Osc,
+    /// ESC seen inside an OSC: `\` completes the ST terminator.
+    OscEscape,
+}
+
+/// Incremental OSC scanner. Sequences may be split across chunks.
+#[derive(Debug, Default)]
+pub struct OscScanner {
+    state: ScanState,
+    payload: Vec<u8>,
+    overflowed: bool,
+}
+
+impl OscScanner {
+    /// Scan `bytes`, returning each event with the offset in `bytes` just
+    /// past its terminator.
+    pub fn scan(&mut self, bytes: &[u8]) -> Vec<(usize, ShellEvent)> {
+        let mut events = Vec::new();
+        for (i, &b) in bytes.iter().enumerate() {
+            self.state = match (self.state, b) {
+                (ScanState::Ground, 0x1b) => ScanState::Escape,
+                (ScanState::Ground, _) => ScanState::Ground,
+                (ScanState::Escape, b']') => {
+                    self.payload.clear();
+                    self.overflowed = false;
+                    ScanState::Osc
+                }
+                (ScanState::Escape, 0x1b) => ScanState::Escape,
+                (ScanState::Escape, _) => ScanState::Ground,
+                (ScanState::Osc, 0x07) | (ScanState::OscEscape, b'\\') => {
+                    if let Some(event) = self.finish() {
+                        events.push((i + 1, event));
+                    }
+                    ScanState::Ground
+                }
+                (ScanState::Osc, 0x1b) => ScanState::OscEscape,
+                // CAN and SUB abort the sequence.
+                (ScanState::Osc, 0x18 | 0x1a) => ScanState::Ground,
+                (ScanState::Osc, _) => {
+                    if self.payload.len() < MAX_PAYLOAD {
+                        self.payload.push(b);
+                    } else {
+                        self.overflowed = true;
+                    }
+                    ScanState::Osc
+                }
+                // Any other escape ends the OSC (unterminated) and may start a new one.
+                (ScanState::OscEscape, b']') => {
+                    self.payload.clear();
+                    self.overflowed = false;
+                    ScanState::Osc
+                }
+                (ScanState::OscEscape, _) => ScanState::Ground,
+            };
+        }
+        events
+    }
+
+    fn finish(&mut self) -> Option<ShellEvent> {
+        if self.overflowed {
+            return None;
+        }
+        parse_osc(std::str::from_utf8(&self.payload).ok()?)
+    }
+}
+
+/// Parse an OSC payload (without `ESC ]` and the terminator).
+fn parse_osc(payload: &str) -> Option<ShellEvent> {
+    let (code, rest) = payload.split_once(';')?;
+    match code {
+        "7" => file_url_path(rest).map(ShellEvent::Cwd),
+        "133" => {
+            let mut params = rest.split(';');
+            match params.next()? {
+                "A" => Some(ShellEvent::PromptStart),
+                "B" => Some(ShellEvent::CommandStart),
+                "C" => Some(ShellEvent::OutputStart),
+                "D" => Some(ShellEvent::CommandEnd(params.next().and_then(|c| c.parse().ok()))),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The path of a `file://host/path` URL, percent-decoded. The host is not
+/// checked: a remote shell's directory usually won't exist locally, and
+/// callers check before using it.
+fn file_url_path(url: &str) -> Option<PathBuf> {
+    let rest = url.strip_prefix("file://")?;
+    let path = &rest[rest.find('/')?..];
+    let bytes = path.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        if let Some(b) = escaped {
+            out.push(b);
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8(out).ok().map(PathBuf::from)
+}
+
+/// A position in the scrollback: `line` counts from the oldest history
+/// line, so it stays put as output scrolls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AbsPoint {
+    pub line: i64,
+    pub column: usize,
+}
+
+/// One command, from its prompt to where it finished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CommandRecord {
+    prompt: AbsPoint,
+    output: Option<AbsPoint>,
+    end: Option<AbsPoint>,
+    exit_code: Option<i32>,
+}
+
+/// OSC 133 marks for a pane, oldest command first.
+///
+/// Absolute lines shift once the scrollback is full and the oldest lines
+/// are dropped, so marks in a very long session drift upwards; they are
+/// forgotten when the history is cleared.
+#[derive(Debug, Default)]
+pub struct CommandMarks {
+    commands: Vec<CommandRecord>,
+    /// History size last seen by `set_history_size`.
+    history: usize,
+}
+
+impl CommandMarks {
+    /// Record `event` at `point` (the cursor position after the sequence).
+    pub fn record(&mut self, event: &ShellEvent, point: AbsPoint) {
+        match event {
+            ShellEvent::PromptStart => {
+                // A prompt redrawn in place (resize, Ctrl+L) replaces the last one.
+                if self.commands.last().is_some_and(|c| c.output.is_none() && c.prompt.line >= point.line) {
+                    self.commands.pop();
+                }
+                self.commands.push(CommandRecord { prompt: point, output: None, end: None, exit_code: None });
+                if self.commands.len() > MAX_COMMANDS {
+                    self.commands.remove(0);
+                }
+            }
+            ShellEvent::OutputStart => {
+                if let Some(last) = self.commands.last_mut() {
+                    last.output = Some(point);
+                }
+            }
+            ShellEvent::CommandEnd(code) => {
+                if let Some(last) = self.commands.last_mut().filter(|c| c.output.is_some() && c.end.is_none()) {
+                    last.end = Some(point);
+                    last.exit_code = *code;
+                }
+            }
+            ShellEvent::CommandStart | ShellEvent::Cwd(_) => {}
+        }
+    }
+
+    /// Track the scrollback size; an emptied history (`clear`, terminal
+    /// reset) takes every mark with it.
+    pub fn set_history_size(&mut self, history: usize) {
+        if history == 0 && self.history > 0 {
+            self.commands.clear();
+        }
+        self.history = history;
+    }
+
+    /// The line of the nearest prompt above `line` (`older`) or below it.
+    pub fn prompt_near(&self, line: i64, older: bool) -> Option<i64> {
+        let prompts = self.commands.iter().map(|c| c.prompt.line);
+        if older {
+            prompts.filter(|&p| p < line).max()
+        } else {
+            prompts.filter(|&p| p > line).min()
+        }
+    }
+
+    /// Start and end (inclusive) of the newest finished command's output,
+    /// or `None` if it printed nothing.
+    pub fn last_output(&self, columns: usize) -> Option<(AbsPoint, AbsPoint)> {
+        let last = self.commands.iter().rev().find(|c| c.end.is_some())?;
+        let (start, end) = (last.output?, last.end?);
+        // The end mark sits just past the last output cell.
+        let end = if end.column > 0 {
+            AbsPoint { line: end.line, column: end.column - 1 }
+        } else {
+            AbsPoint { line: end.line - 1, column: columns.saturating_sub(1) }
+        };
+        (start <= end).then_some((start, end))
+    }
+
+    /// Exit code of the newest finished command.
+    pub fn last_exit_code(&self) -> Option<i32> {
+        self.commands.iter().rev().find(|c| c.end.is_some())?.exit_code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(line: i64, column: usize) -> AbsPoint {
+        AbsPoint { line, column }
+    }
+
+    #[test]
+    fn finds_sequences_with_either_terminator() {
+        let mut scanner = OscScanner::default();
+        let bytes = b"ab\x1b]133;A\x07$ \x1b]133;D;1\x1b\\x";
+        let events = scanner.scan(bytes);
+        assert_eq!(events, vec![(10, ShellEvent::PromptStart), (23, ShellEvent::CommandEnd(Some(1)))]);
+        assert_eq!(&bytes[..10], b"ab\x1b]133;A\x07");
+    }
+
+    #[test]
+    fn sequences_split_across_chunks() {
+        let mut scanner = OscScanner::default();
+        assert!(scanner.scan(b"\x1b]7;file://host/tmp/a%20").is_empty());
+        assert_eq!(
+            scanner.scan(b"b\x1b\\"),
+            vec![(3, ShellEvent::Cwd(PathBuf::from("/tmp/a b")))]
+        );
+    }
+
+    #[test]
+    fn ignores_other_and_oversized_osc() {
+        let mut scanner = OscScanner::default();
+        assert!(scanner.scan(b"\x1b]0;title\x07\x1b]133;Z\x07").is_empty());
+        let mut long = b"\x1b]133;A".to_vec();
+        long.extend(std::iter::repeat_n(b'x', MAX_PAYLOAD));
+        long.push(0x07);
+        assert!(scanner.scan(&long).is_empty());
+        assert_eq!(scanner.scan(b"\x1b]133;B\x07"), vec![(8, ShellEvent::CommandStart)]);
+    }
+
+    #[test]
+    fn prompts_and_last_output() {
+        let mut marks = CommandMarks::default();
+        marks.record(&ShellEvent::PromptStart, at(0, 0));
+        marks.record(&ShellEvent::OutputStart, at(1, 0));
+        marks.record(&ShellEvent::CommandEnd(Some(0)), at(4, 0));
+        marks.record(&ShellEvent::PromptStart, at(4, 0));
+        marks.record(&ShellEvent::OutputStart, at(5, 0));
+        marks.record(&ShellEvent::CommandEnd(Some(2)), at(5, 0));
+        marks.record(&ShellEvent::PromptStart, at(5, 0));
+
+        assert_eq!(marks.prompt_near(5, true), Some(4));
+        assert_eq!(marks.prompt_near(4, true), Some(0));
+        assert_eq!(marks.prompt_near(0, false), Some(4));
+        assert_eq!(marks.prompt_near(5, false), None);
+        // The newest command printed nothing; its output is empty.
+        assert_eq!(marks.last_output(80), None);
+        assert_eq!(marks.last_exit_code(), Some(2));
+    }
+
+    #[test]
+    fn last_output_spans_to_the_end_mark() {
+        let mut marks = CommandMarks::default();
+        marks.record(&ShellEvent::PromptStart, at(10, 0));
+        marks.record(&ShellEvent::OutputStart, at(11, 0));
+        marks.record(&ShellEvent::CommandEnd(None), at(14, 0));
+        assert_eq!(marks.last_output(80), Some((at(11, 0), at(13, 79))));
+
+        marks.set_history_size(20);
+        marks.set_history_size(0);
+        assert_eq!(marks.prompt_near(20, true), None);
+    }
+}
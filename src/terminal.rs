@@ -5,8 +5,13 @@
 //!   - Feeds output to `alacritty_terminal::Term` via a tokio mpsc channel
 //!   - Exposes `render_into_scene()` to draw the cell grid using vello
 //!   - Routes keyboard input from winit to the backend writer
+//!   - Optionally records its output as an asciicast file (`asciicast.rs`)
+//!   - Tracks the shell's directory and prompts from OSC 7 / OSC 133
+//!     (`shell_integration.rs`)
 //!
 //! The Cascadia Code font is embedded in the binary via `include_bytes!()`.
+//! Characters it lacks fall back to system fonts (`font_fallback.rs`), and
+//! box-drawing and block characters are drawn as shapes (`box_drawing.rs`).
 //!
 //! Architecture (data flows):
 //!
@@ -25,50 +30,75 @@
 //!
 //! Key input:
 //! ```text
-//! winit KeyEvent ──► key_event_to_pty_bytes() ──► backend writer (sync write)
+//! winit KeyEvent ──► key_encoding::encode() ──► backend writer (sync write)
 //! ```
 
-use std::io::Write;
-use std::sync::{Arc, Mutex};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
 
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use alacritty_terminal::Term;
-use alacritty_terminal::event::EventListener;
-use alacritty_terminal::grid::{Dimensions, Scroll};
+use alacritty_terminal::event::{Event, EventListener};
+use alacritty_terminal::grid::{Dimensions, Row, Scroll};
 use alacritty_terminal::index::{Column, Line, Point, Side};
 use alacritty_terminal::selection::{Selection, SelectionType};
 use alacritty_terminal::term::Config;
-use alacritty_terminal::term::cell::Flags;
-use alacritty_terminal::term::TermMode;
-use alacritty_terminal::vte::ansi::{Color as AlaColor, NamedColor, Processor, Rgb};
-use portable_pty::{CommandBuilder, PtySize, native_pty_system};
+use alacritty_terminal::term::cell::{Cell, Flags};
+use alacritty_terminal::term::color::Colors;
+use alacritty_terminal::term::{TermDamage, TermMode};
+use alacritty_terminal::term::search::Match;
+use alacritty_terminal::vte::ansi::{Color as AlaColor, CursorShape, CursorStyle, NamedColor, Processor, Rgb};
+use portable_pty::{Child, ChildKiller, CommandBuilder, PtySize, native_pty_system};
 use tokio::sync::mpsc as tokio_mpsc;
-use tracing::info;
+use tracing::{info, warn};
 use vello::Glyph;
 use vello::Scene;
-use vello::kurbo::{Affine, Rect};
+use vello::kurbo::{Affine, Circle, Rect, Stroke};
 use vello::peniko::{Color, Fill, FontData};
-use winit::keyboard::{Key, NamedKey};
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use crate::asciicast::{self, Recorder, SharedRecorder};
+use crate::box_drawing;
+use crate::color_scheme::ColorScheme;
+use crate::font_fallback::FontChain;
+use crate::key_encoding::{self, EncodingMode, KeyPress};
+use crate::shell_integration::{AbsPoint, CommandMarks, OscScanner, ShellEvent};
+use crate::terminal_links::{DetectedLink, LinkDetector, LinkTarget};
+use crate::terminal_search::{self, TerminalSearch};
 
 // Cascadia Code embedded as fallback (SIL OFL license).
 // Attribution: Copyright (c) Microsoft Corporation
 const CASCADIA_CODE_BYTES: &[u8] = include_bytes!("../assets/CascadiaCode.ttf");
 
-/// Terminal background: bisque beige (matches app style guide).
-const TERM_BG: Color = Color::new([1.0, 0.894, 0.769, 1.0]);
-/// Default foreground: black (matches app style guide).
-const TERM_FG: Color = Color::new([0.0, 0.0, 0.0, 1.0]);
-/// Cursor color: dark brown, visible on bisque.
-const TERM_CURSOR: Color = Color::new([0.40, 0.26, 0.13, 0.85]);
-
-/// Selection highlight color.
-const TERM_SELECTION: Color = Color::new([0.2, 0.5, 1.0, 0.35]);
 /// Hyperlink underline color (subtle blue).
 const TERM_HYPERLINK: Color = Color::new([0.2, 0.4, 0.8, 0.7]);
 /// Hyperlink underline thickness.
 const HYPERLINK_UNDERLINE_PX: f64 = 1.0;
 
+/// Search match highlight (amber, like the pane focus border).
+const TERM_MATCH: Color = Color::new([0.85, 0.65, 0.20, 0.35]);
+/// Highlight for the selected search match.
+const TERM_MATCH_CURRENT: Color = Color::new([0.85, 0.55, 0.10, 0.80]);
+/// Find bar background: bisque, a shade darker than the terminal.
+const FIND_BAR_BG: Color = Color::new([0.96, 0.85, 0.71, 0.97]);
+/// Find bar hairline and secondary text.
+const FIND_BAR_INK: Color = Color::new([0.0, 0.0, 0.0, 0.55]);
+/// Find bar query text and caret.
+const FIND_BAR_TEXT: Color = Color::new([0.0, 0.0, 0.0, 1.0]);
+
+/// Recording indicator dot.
+const TERM_RECORDING: Color = Color::new([0.80, 0.15, 0.10, 0.90]);
+/// Exit banner text after a failure (non-zero code or a signal).
+const EXIT_BANNER_FAILED: Color = Color::new([0.70, 0.12, 0.08, 1.0]);
+/// Badge health dot while the process runs.
+const BADGE_RUNNING: Color = Color::new([0.25, 0.55, 0.30, 1.0]);
+
 /// Horizontal padding (one cell width on each side).
 const TERM_PAD_CELLS: usize = 1;
 
@@ -134,12 +164,81 @@ impl Dimensions for TermSize {
     }
 }
 
-// --- No-op EventListener ----------------------------------------------------
+// --- EventListener -----------------------------------------------------------
 
+/// Collects replies the terminal wants written back to the application
+/// (device attributes, cursor position reports, kitty keyboard queries,
+/// OSC 4/10/11/12 color queries) and OSC 52 clipboard requests.
+/// `drain_output()` answers them on the backend writer.
 #[derive(Clone)]
-struct TermEventListener;
+struct TermEventListener(std::sync::mpsc::Sender<Event>);
+
+impl EventListener for TermEventListener {
+    fn send_event(&self, event: Event) {
+        if matches!(
+            event,
+            Event::PtyWrite(_) | Event::ColorRequest(..) | Event::ClipboardStore(..) | Event::ClipboardLoad(..)
+        ) {
+            let _ = self.0.send(event);
+        }
+    }
+}
+
+/// Build the `Term` for a pane and the receiver for its replies.
+fn new_term(size: &TermSize) -> (Term<TermEventListener>, std::sync::mpsc::Receiver<Event>) {
+    let (tx, rx) = std::sync::mpsc::channel();
+    (Term::new(term_config(DEFAULT_CURSOR_STYLE), size, TermEventListener(tx)), rx)
+}
+
+fn term_config(cursor: CursorStyle) -> Config {
+    Config {
+        // Kitty keyboard flags are only honoured when the application asks.
+        kitty_keyboard: true,
+        // Used until the application sets a style with DECSCUSR.
+        default_cursor_style: cursor,
+        ..Config::default()
+    }
+}
+
+/// Cursor style of a new pane, before design tokens are applied.
+pub const DEFAULT_CURSOR_STYLE: CursorStyle = CursorStyle { shape: CursorShape::Block, blinking: true };
+
+/// The default cursor style for the `terminal.cursor_shape` and
+/// `terminal.cursor_blink` tokens.
+pub fn default_cursor_style(shape: &str, blinking: bool) -> CursorStyle {
+    let shape = match shape {
+        "block" => CursorShape::Block,
+        "underline" => CursorShape::Underline,
+        "beam" => CursorShape::Beam,
+        other => {
+            warn!(target: "terminal", "Unknown cursor shape {:?}, using block", other);
+            CursorShape::Block
+        }
+    };
+    CursorStyle { shape, blinking }
+}
+
+// --- Pane lifecycle ----------------------------------------------------------
 
-impl EventListener for TermEventListener {}
+/// Lifecycle of the process behind a pane.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaneState {
+    Running,
+    /// Exited on its own with this code.
+    Exited(u32),
+    /// Terminated by a signal (its name, e.g. `Killed`, `Hangup`).
+    Killed(String),
+}
+
+/// What a project pane's backend last found out about its backups, for
+/// the badge (see `TerminalPane::backup_status`).
+#[derive(Debug, Default)]
+pub struct BackupStatus {
+    /// Unix time of the last snapshot; 0 for none yet.
+    pub saved: AtomicI64,
+    /// The last push of the backup branch failed.
+    pub push_failed: AtomicBool,
+}
 
 // --- PtyResize trait ---------------------------------------------------------
 
@@ -164,6 +263,53 @@ impl PtyResize for LocalPtyResize {
     }
 }
 
+/// Resize handle for panes with no backend to tell (playback, tests).
+pub(crate) struct NoResize;
+
+impl PtyResize for NoResize {
+    fn resize(&self, _rows: u16, _cols: u16, _pixel_width: u16, _pixel_height: u16) {}
+}
+
+// --- Output readers ---------------------------------------------------------
+
+/// Read a PTY on its own thread, recording each chunk as it arrives.
+fn spawn_reader(
+    mut reader: Box<dyn Read + Send>,
+    recorder: SharedRecorder,
+) -> tokio_mpsc::UnboundedReceiver<Vec<u8>> {
+    let (tx, rx) = tokio_mpsc::unbounded_channel::<Vec<u8>>();
+    std::thread::spawn(move || {
+        let mut buf = [0u8; 4096];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    asciicast::record_output(&recorder, &buf[..n]);
+                    let _ = tx.send(buf[..n].to_vec());
+                }
+            }
+        }
+    });
+    rx
+}
+
+/// Forward a pre-wired output stream through a thread that records it.
+fn tap_stream(
+    mut upstream: tokio_mpsc::UnboundedReceiver<Vec<u8>>,
+    recorder: SharedRecorder,
+) -> tokio_mpsc::UnboundedReceiver<Vec<u8>> {
+    let (tx, rx) = tokio_mpsc::unbounded_channel::<Vec<u8>>();
+    std::thread::spawn(move || {
+        while let Some(chunk) = upstream.blocking_recv() {
+            asciicast::record_output(&recorder, &chunk);
+            if tx.send(chunk).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
 // --- TerminalPane -----------------------------------------------------------
 
 /// A terminal pane that renders into a vello `Scene`.
@@ -182,14 +328,39 @@ pub struct TerminalPane {
     /// Buffered PTY output bytes from the reader thread.
     rx: tokio_mpsc::UnboundedReceiver<Vec<u8>>,
 
+    /// Replies from the terminal to the application (see `TermEventListener`).
+    replies: std::sync::mpsc::Receiver<Event>,
+
+    /// Finds OSC 7 and OSC 133 sequences in the output (`shell_integration`).
+    osc_scanner: OscScanner,
+    /// Working directory last reported by the shell (OSC 7).
+    cwd: Option<PathBuf>,
+    /// Prompt and command output positions (OSC 133).
+    commands: CommandMarks,
+    /// Let applications set the clipboard with OSC 52. Clipboard reads
+    /// are always refused.
+    clipboard_write: bool,
+
     /// Write handle to the backend (sends input to the shell/VM).
     pty_writer: Box<dyn Write + Send>,
 
     /// Resize handle (backend-agnostic).
     pty_resize: Box<dyn PtyResize + Send>,
 
-    /// Cascadia Code font data.
-    font: FontData,
+    /// The shell or command on the PTY; `None` for streams owned
+    /// elsewhere (VM console, playback).
+    child: Option<Box<dyn Child + Send + Sync>>,
+    /// Whether `child` is still running; see `poll_exit`.
+    state: PaneState,
+    /// When the process was started.
+    started: Instant,
+
+    /// Default colors and ANSI palette; OSC color changes override it.
+    scheme: Arc<ColorScheme>,
+
+    /// Terminal font (Monaco or Cascadia Code) followed by system
+    /// fallbacks for glyphs it lacks.
+    fonts: FontChain,
 
     /// Current font size in pixels.
     pub font_size: f32,
@@ -206,7 +377,8 @@ pub struct TerminalPane {
     pub cols: usize,
     pub rows: usize,
 
-    /// Cursor blink state (toggled from main loop).
+    /// Cursor blink phase (toggled from main loop). Only consulted while
+    /// the cursor style blinks.
     pub cursor_visible: bool,
 
     /// Whether a mouse drag selection is in progress.
@@ -219,14 +391,53 @@ pub struct TerminalPane {
 
     /// Viewport cell under the mouse cursor (for hyperlink hover detection).
     hover_cell: Option<(usize, usize)>,
+
+    /// Finds plain URLs and file references under the mouse.
+    links: LinkDetector,
+    /// The detected link last found at `hover_cell`.
+    hovered_link: Option<DetectedLink>,
+
+    /// Find bar state; `Some` while the bar is open.
+    search: Option<TerminalSearch>,
+
+    /// asciicast recorder, shared with the reader thread; `None` when off.
+    recorder: SharedRecorder,
+
+    /// Uncommitted IME composition, drawn at the cursor; empty when none.
+    preedit: String,
+    /// Byte offset of the IME caret within `preedit`.
+    preedit_cursor: Option<usize>,
+
+    /// Rendered lines reused across frames (see `LineCache`).
+    line_cache: RefCell<LineCache>,
+
+    /// What the pane runs (`docker`, `vm · lobster`), shown in its
+    /// top-right corner beside a health dot; empty for none.
+    badge: String,
+
+    /// The project's last auto-commit snapshot and push, shown in the
+    /// badge. The backend keeps it up to date (see `backup_status`).
+    backup: Arc<BackupStatus>,
+
+    /// The pane prepares for another and is swapped for it once its
+    /// process succeeds (see `mark_stand_in`).
+    stand_in: bool,
+
+    /// Backend cleanup to run when the pane goes away (see `on_close`).
+    on_close: Option<Box<dyn FnOnce() + Send>>,
+
+    /// Marked to receive synchronized input (see `PaneTree::toggle_sync_mark`).
+    sync_marked: bool,
 }
 
 impl TerminalPane {
     /// Spawn a new terminal pane.
     ///
     /// `width` and `height` are the pixel dimensions of the terminal area.
+    /// The shell starts in `cwd` if given, else in the app's directory,
+    /// with `env` added to its environment.
     /// Returns `None` if PTY creation or shell spawn fails.
-    pub fn spawn(width: f64, height: f64) -> Option<Self> {
+    pub fn spawn(width: f64, height: f64, cwd: Option<&Path>, env: &[(&str, &str)]) -> Option<Self> {
         // Build font (Monaco preferred, Cascadia Code fallback) and compute cell dimensions.
         let font_data = load_terminal_font();
         let (cell_width, cell_height) = compute_cell_size(&font_data, DEFAULT_FONT_SIZE);
@@ -257,45 +468,44 @@ impl TerminalPane {
         // without it thinking it's already running inside Claude Code.
         cmd.env_remove("CLAUDECODE");
         cmd.env_remove("CLAUDE_CODE_ENTRYPOINT");
+        for (k, v) in env {
+            cmd.env(*k, *v);
+        }
+        if let Some(dir) = cwd {
+            cmd.cwd(dir);
+        }
 
-        let _child = pair.slave.spawn_command(cmd).ok()?;
-        // `_child` is kept to ensure the process isn't reaped prematurely,
-        // but we don't need to supervise it for the initial implementation.
+        let child = pair.slave.spawn_command(cmd).ok()?;
 
         // Get read/write handles from the master.
         let reader = pair.master.try_clone_reader().ok()?;
         let writer = pair.master.take_writer().ok()?;
 
         // Spawn the PTY reader thread.
-        let (tx, rx) = tokio_mpsc::unbounded_channel::<Vec<u8>>();
-        {
-            let tx = tx.clone();
-            std::thread::spawn(move || {
-                let mut reader = reader;
-                let mut buf = [0u8; 4096];
-                loop {
-                    match std::io::Read::read(&mut reader, &mut buf) {
-                        Ok(0) | Err(_) => break,
-                        Ok(n) => {
-                            let _ = tx.send(buf[..n].to_vec());
-                        }
-                    }
-                }
-            });
-        }
+        let recorder = SharedRecorder::default();
+        let rx = spawn_reader(reader, recorder.clone());
 
         // Build the alacritty_terminal Term.
         let size = TermSize::new(cols, rows);
-        let term = Term::new(Config::default(), &size, TermEventListener);
+        let (term, replies) = new_term(&size);
         let term = Arc::new(Mutex::new(term));
 
         Some(Self {
             term,
             processor: Processor::new(),
             rx,
+            replies,
+            osc_scanner: OscScanner::default(),
+            cwd: None,
+            commands: CommandMarks::default(),
+            clipboard_write: true,
             pty_writer: writer,
             pty_resize: Box::new(LocalPtyResize(pair.master)),
-            font: font_data,
+            child: Some(child),
+            state: PaneState::Running,
+            started: Instant::now(),
+            scheme: Arc::default(),
+            fonts: FontChain::new(font_data),
             font_size: DEFAULT_FONT_SIZE,
             cell_width,
             cell_height,
@@ -309,6 +519,18 @@ impl TerminalPane {
             last_click_cell: (0, 0),
             click_count: 0,
             hover_cell: None,
+            links: LinkDetector::new(),
+            hovered_link: None,
+            search: None,
+            recorder,
+            preedit: String::new(),
+            preedit_cursor: None,
+            line_cache: RefCell::default(),
+            badge: String::new(),
+            backup: Arc::default(),
+            stand_in: false,
+            on_close: None,
+            sync_marked: false,
         })
     }
 
@@ -354,39 +576,34 @@ impl TerminalPane {
         command.env_remove("CLAUDECODE");
         command.env_remove("CLAUDE_CODE_ENTRYPOINT");
 
-        let _child = pair.slave.spawn_command(command).ok()?;
+        let child = pair.slave.spawn_command(command).ok()?;
 
         let reader = pair.master.try_clone_reader().ok()?;
         let writer = pair.master.take_writer().ok()?;
 
-        let (tx, rx) = tokio_mpsc::unbounded_channel::<Vec<u8>>();
-        {
-            let tx = tx.clone();
-            std::thread::spawn(move || {
-                let mut reader = reader;
-                let mut buf = [0u8; 4096];
-                loop {
-                    match std::io::Read::read(&mut reader, &mut buf) {
-                        Ok(0) | Err(_) => break,
-                        Ok(n) => {
-                            let _ = tx.send(buf[..n].to_vec());
-                        }
-                    }
-                }
-            });
-        }
+        let recorder = SharedRecorder::default();
+        let rx = spawn_reader(reader, recorder.clone());
 
         let size = TermSize::new(cols, rows);
-        let term = Term::new(Config::default(), &size, TermEventListener);
+        let (term, replies) = new_term(&size);
         let term = Arc::new(Mutex::new(term));
 
         Some(Self {
             term,
             processor: Processor::new(),
             rx,
+            replies,
+            osc_scanner: OscScanner::default(),
+            cwd: None,
+            commands: CommandMarks::default(),
+            clipboard_write: true,
             pty_writer: writer,
             pty_resize: Box::new(LocalPtyResize(pair.master)),
-            font: font_data,
+            child: Some(child),
+            state: PaneState::Running,
+            started: Instant::now(),
+            scheme: Arc::default(),
+            fonts: FontChain::new(font_data),
             font_size: DEFAULT_FONT_SIZE,
             cell_width,
             cell_height,
@@ -400,6 +617,18 @@ impl TerminalPane {
             last_click_cell: (0, 0),
             click_count: 0,
             hover_cell: None,
+            links: LinkDetector::new(),
+            hovered_link: None,
+            search: None,
+            recorder,
+            preedit: String::new(),
+            preedit_cursor: None,
+            line_cache: RefCell::default(),
+            badge: String::new(),
+            backup: Arc::default(),
+            stand_in: false,
+            on_close: None,
+            sync_marked: false,
         })
     }
 
@@ -417,17 +646,29 @@ impl TerminalPane {
         let font_data = load_terminal_font();
         let (cell_width, cell_height) = compute_cell_size(&font_data, DEFAULT_FONT_SIZE);
 
+        let recorder = SharedRecorder::default();
+        let rx = tap_stream(rx, recorder.clone());
+
         let size = TermSize::new(cols, rows);
-        let term = Term::new(Config::default(), &size, TermEventListener);
+        let (term, replies) = new_term(&size);
         let term = Arc::new(Mutex::new(term));
 
         Self {
             term,
             processor: Processor::new(),
             rx,
+            replies,
+            osc_scanner: OscScanner::default(),
+            cwd: None,
+            commands: CommandMarks::default(),
+            clipboard_write: true,
             pty_writer: writer,
             pty_resize: resizer,
-            font: font_data,
+            child: None,
+            state: PaneState::Running,
+            started: Instant::now(),
+            scheme: Arc::default(),
+            fonts: FontChain::new(font_data),
             font_size: DEFAULT_FONT_SIZE,
             cell_width,
             cell_height,
@@ -441,9 +682,27 @@ impl TerminalPane {
             last_click_cell: (0, 0),
             click_count: 0,
             hover_cell: None,
+            links: LinkDetector::new(),
+            hovered_link: None,
+            search: None,
+            recorder,
+            preedit: String::new(),
+            preedit_cursor: None,
+            line_cache: RefCell::default(),
+            badge: String::new(),
+            backup: Arc::default(),
+            stand_in: false,
+            on_close: None,
+            sync_marked: false,
         }
     }
 
+    /// Create a read-only pane that replays `output` (see
+    /// `asciicast::spawn_player`). Input and terminal replies are discarded.
+    pub fn playback(output: tokio_mpsc::UnboundedReceiver<Vec<u8>>, cols: usize, rows: usize) -> Self {
+        Self::from_streams(output, Box::new(std::io::sink()), Box::new(NoResize), cols, rows)
+    }
+
     /// Returns the terminal mono font data (Monaco preferred, Cascadia Code fallback).
     pub fn mono_font_data() -> FontData {
         load_terminal_font()
@@ -463,7 +722,63 @@ impl TerminalPane {
         }
         let mut term = self.term.lock().unwrap();
         for chunk in collected {
-            self.processor.advance(&mut *term, &chunk);
+            // Feed the parser up to each shell integration sequence so the
+            // cursor is where the mark belongs.
+            let mut fed = 0;
+            for (end, event) in self.osc_scanner.scan(&chunk) {
+                self.processor.advance(&mut *term, &chunk[fed..end]);
+                fed = end;
+                match event {
+                    ShellEvent::Cwd(dir) => self.cwd = Some(dir),
+                    // Prompts on the alternate screen never reach the scrollback.
+                    _ if term.mode().contains(TermMode::ALT_SCREEN) => {}
+                    event => {
+                        self.commands.set_history_size(term.history_size());
+                        let cursor = term.grid().cursor.point;
+                        let point = AbsPoint {
+                            line: term.history_size() as i64 + cursor.line.0 as i64,
+                            column: cursor.column.0,
+                        };
+                        self.commands.record(&event, point);
+                    }
+                }
+            }
+            self.processor.advance(&mut *term, &chunk[fed..]);
+        }
+        self.commands.set_history_size(term.history_size());
+        // New output shifts grid lines; the matches are found again
+        // before the pane is next drawn (`refresh_search_matches`).
+        if let Some(search) = &mut self.search {
+            search.mark_stale();
+        }
+        drop(term);
+        if self.hover_cell.is_some() {
+            self.refresh_hovered_link();
+        }
+
+        let mut replied = false;
+        while let Ok(event) = self.replies.try_recv() {
+            let reply = match event {
+                Event::PtyWrite(text) => text,
+                Event::ColorRequest(index, format) => {
+                    let set = self.term.lock().unwrap().colors()[index];
+                    format(set.unwrap_or_else(|| self.scheme.rgb(index)))
+                }
+                Event::ClipboardStore(_, text) => {
+                    self.store_clipboard(&text);
+                    continue;
+                }
+                Event::ClipboardLoad(..) => {
+                    info!(target: "terminal", "Refused OSC 52 clipboard read");
+                    continue;
+                }
+                _ => continue,
+            };
+            let _ = self.pty_writer.write_all(reply.as_bytes());
+            replied = true;
+        }
+        if replied {
+            let _ = self.pty_writer.flush();
         }
     }
 
@@ -498,18 +813,198 @@ impl TerminalPane {
         let new_size = TermSize::new(new_cols, new_rows);
         let mut term = self.term.lock().unwrap();
         term.resize(new_size);
+        drop(term);
+
+        if let Some(recorder) = self.recorder.lock().unwrap().as_mut() {
+            if let Err(e) = recorder.resize(new_cols, new_rows) {
+                warn!(target: "asciicast", "Failed to record resize: {}", e);
+            }
+        }
+    }
+
+    /// Set the cursor style used until the application picks one.
+    pub fn set_cursor_style(&mut self, style: CursorStyle) {
+        self.term.lock().unwrap().set_options(term_config(style));
+    }
+
+    /// Allow or refuse OSC 52 clipboard writes.
+    pub fn set_clipboard_write(&mut self, allowed: bool) {
+        self.clipboard_write = allowed;
+    }
+
+    /// Handle an OSC 52 clipboard write from the application.
+    fn store_clipboard(&self, text: &str) {
+        if !self.clipboard_write {
+            info!(target: "terminal", "Refused OSC 52 clipboard write (terminal.clipboard_write is off)");
+            return;
+        }
+        match arboard::Clipboard::new().and_then(|mut cb| cb.set_text(text.to_string())) {
+            Ok(()) => info!(target: "terminal", "Application copied {} chars to clipboard", text.len()),
+            Err(e) => warn!(target: "terminal", "Clipboard write failed: {}", e),
+        }
+    }
+
+    /// The working directory the shell last reported (OSC 7), if it
+    /// exists on this machine.
+    pub fn cwd(&self) -> Option<&Path> {
+        self.cwd.as_deref().filter(|dir| dir.is_dir())
+    }
+
+    /// Scroll the previous (`older`) or next prompt to the top of the
+    /// viewport. Needs a shell that emits OSC 133 marks; past the newest
+    /// prompt, scrolls back to the bottom.
+    pub fn jump_to_prompt(&mut self, older: bool) {
+        let mut term = self.term.lock().unwrap();
+        let history = term.history_size() as i64;
+        let current = term.grid().display_offset() as i64;
+        let target = match self.commands.prompt_near(history - current, older) {
+            Some(line) => (history - line).clamp(0, history),
+            None if !older => 0,
+            None => return,
+        };
+        term.scroll_display(Scroll::Delta((target - current) as i32));
+    }
+
+    /// Select the output of the last finished command (OSC 133 C to D)
+    /// and scroll it into view. Returns `false` if there is none.
+    pub fn select_last_output(&mut self) -> bool {
+        let mut term = self.term.lock().unwrap();
+        let Some((start, end)) = self.commands.last_output(term.columns()) else {
+            return false;
+        };
+        let history = term.history_size() as i64;
+        let to_point = |p: AbsPoint| Point::new(Line((p.line - history) as i32), Column(p.column));
+        let (start, end) = (to_point(start), to_point(end));
+        if start.line < term.topmost_line() || end.line > term.bottommost_line() {
+            return false;
+        }
+        let mut selection = Selection::new(SelectionType::Simple, start, Side::Left);
+        selection.update(end, Side::Right);
+        term.selection = Some(selection);
+        let current = term.grid().display_offset();
+        if let Some(target) =
+            terminal_search::offset_to_reveal(start.line, current, term.screen_lines(), term.history_size())
+        {
+            term.scroll_display(Scroll::Delta(target as i32 - current as i32));
+        }
+        true
+    }
+
+    /// Switch to a new color scheme (design tokens changed).
+    pub fn set_color_scheme(&mut self, scheme: Arc<ColorScheme>) {
+        self.scheme = scheme;
+        self.line_cache.borrow_mut().clear();
+    }
+
+    /// Start recording this pane to a new file under `<data>/recordings/`.
+    /// `label` goes into the file name. Returns the file path.
+    pub fn start_recording(&mut self, label: &str) -> Option<PathBuf> {
+        let path = asciicast::new_recording_path(label)?;
+        match Recorder::create(&path, self.cols, self.rows, &format!("bisque-computer {}", label)) {
+            Ok(recorder) => {
+                info!(target: "asciicast", "Recording terminal to {}", path.display());
+                *self.recorder.lock().unwrap() = Some(recorder);
+                Some(path)
+            }
+            Err(e) => {
+                warn!(target: "asciicast", "Failed to start recording {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Stop recording, returning the finished file's path.
+    pub fn stop_recording(&mut self) -> Option<PathBuf> {
+        let recorder = self.recorder.lock().unwrap().take()?;
+        info!(target: "asciicast", "Saved recording {}", recorder.path().display());
+        Some(recorder.path().to_path_buf())
+    }
+
+    /// Mark the pane as a stand-in: its process prepares what the pane it
+    /// stands in for needs (building a Docker image), and the pane tree
+    /// replaces it with that pane once the process succeeds.
+    pub fn mark_stand_in(&mut self) {
+        self.stand_in = true;
+    }
+
+    pub fn is_stand_in(&self) -> bool {
+        self.stand_in
+    }
+
+    /// Where the backend records how the project's backups are going, for
+    /// the badge. It is held weakly, so it lapses with the pane.
+    pub fn backup_status(&self) -> Weak<BackupStatus> {
+        Arc::downgrade(&self.backup)
+    }
+
+    /// Run `cleanup` when the pane is dropped, after its process has been
+    /// ended: for tearing down what the backend set up around it.
+    pub fn on_close(&mut self, cleanup: impl FnOnce() + Send + 'static) {
+        self.on_close = Some(Box::new(cleanup));
+    }
+
+    /// Set the badge drawn in the pane's corner; empty hides it.
+    pub fn set_badge(&mut self, badge: impl Into<String>) {
+        self.badge = badge.into();
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.lock().unwrap().is_some()
+    }
+
+    pub fn state(&self) -> &PaneState {
+        &self.state
+    }
+
+    /// How long ago the process was started.
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Pixel size the pane was last laid out at.
+    pub fn pixel_size(&self) -> (f64, f64) {
+        (self.pixel_width, self.pixel_height)
+    }
+
+    /// Check whether the process has ended. Returns `true` once, on the
+    /// frame it is first seen to have exited.
+    pub fn poll_exit(&mut self) -> bool {
+        if self.state != PaneState::Running {
+            return false;
+        }
+        let Some(child) = self.child.as_mut() else { return false };
+        let status = match child.try_wait() {
+            Ok(Some(status)) => status,
+            Ok(None) => return false,
+            Err(e) => {
+                warn!(target: "terminal", "Failed to poll child process: {}", e);
+                return false;
+            }
+        };
+        self.state = match status.signal() {
+            Some(signal) => PaneState::Killed(signal.to_string()),
+            None => PaneState::Exited(status.exit_code()),
+        };
+        info!(target: "terminal", "Pane process ended: {:?}", self.state);
+        true
     }
 
     /// Write a key event to the PTY.
     ///
-    /// Should be called when the terminal screen is active and a key is pressed.
-    /// Returns `true` if the key was consumed (written to the PTY).
-    pub fn write_key(&mut self, key: &Key, ctrl_held: bool) -> bool {
-        let app_cursor = {
+    /// Encodes with the modes the application has set (application cursor
+    /// and keypad, kitty keyboard flags); see `key_encoding`. Returns `true`
+    /// if anything was written.
+    pub fn write_key(&mut self, press: &KeyPress) -> bool {
+        let mode = {
             let term = self.term.lock().unwrap();
-            term.mode().contains(TermMode::APP_CURSOR)
+            let mode = *term.mode();
+            EncodingMode {
+                app_cursor: mode.contains(TermMode::APP_CURSOR),
+                app_keypad: mode.contains(TermMode::APP_KEYPAD),
+                kitty_flags: kitty_flags(mode),
+            }
         };
-        let bytes = key_event_to_pty_bytes(key, ctrl_held, app_cursor);
+        let bytes = key_encoding::encode(press, mode);
         if bytes.is_empty() {
             return false;
         }
@@ -518,6 +1013,45 @@ impl TerminalPane {
         true
     }
 
+    /// Show (or with empty `text`, clear) the IME composition.
+    pub fn set_preedit(&mut self, text: String, cursor: Option<usize>) {
+        self.preedit = text;
+        self.preedit_cursor = cursor;
+    }
+
+    pub fn is_sync_marked(&self) -> bool {
+        self.sync_marked
+    }
+
+    pub fn set_sync_marked(&mut self, marked: bool) {
+        self.sync_marked = marked;
+    }
+
+    /// Whether an IME composition is in progress.
+    pub fn has_preedit(&self) -> bool {
+        !self.preedit.is_empty()
+    }
+
+    /// Write text committed by the IME and end the composition.
+    pub fn commit_text(&mut self, text: &str) {
+        self.preedit.clear();
+        self.preedit_cursor = None;
+        let _ = self.pty_writer.write_all(text.as_bytes());
+        let _ = self.pty_writer.flush();
+    }
+
+    /// Pixel rect `(x, y, w, h)` of the cursor cell relative to the pane's
+    /// origin, for placing the IME candidate window.
+    pub fn cursor_cell_rect(&self) -> (f64, f64, f64, f64) {
+        let term = self.term.lock().unwrap();
+        let grid = term.grid();
+        let cw = self.cell_width as f64;
+        let ch = self.cell_height as f64;
+        let row = (grid.cursor.point.line.0 + grid.display_offset() as i32).max(0);
+        let x = (TERM_PAD_CELLS + grid.cursor.point.column.0) as f64 * cw;
+        (x, row as f64 * ch, cw, ch)
+    }
+
     /// Increase font size by one step (Cmd+=).
     pub fn increase_font_size(&mut self) {
         self.set_font_size(self.font_size + FONT_SIZE_STEP);
@@ -534,15 +1068,19 @@ impl TerminalPane {
     }
 
     /// Set font size, recompute cell dimensions, and resize the terminal grid.
-    fn set_font_size(&mut self, size: f32) {
+    ///
+    /// The size is clamped to the supported range.
+    pub fn set_font_size(&mut self, size: f32) {
         let size = size.clamp(MIN_FONT_SIZE, MAX_FONT_SIZE);
         if (size - self.font_size).abs() < 0.01 {
             return;
         }
         self.font_size = size;
-        let (cw, ch) = compute_cell_size(&self.font, size);
+        let (cw, ch) = compute_cell_size(self.fonts.primary(), size);
         self.cell_width = cw;
         self.cell_height = ch;
+        // Cached lines were laid out for the old cell size.
+        self.line_cache.borrow_mut().clear();
         // Re-derive cols/rows from stored pixel dimensions and trigger PTY + grid resize.
         self.resize(self.pixel_width, self.pixel_height);
     }
@@ -576,22 +1114,52 @@ impl TerminalPane {
     /// Update the hover cell position (for hyperlink underline rendering).
     pub fn update_hover(&mut self, x: f64, y: f64, offset_x: f64, offset_y: f64) {
         let (col, row, _side) = self.pixel_to_viewport(x, y, offset_x, offset_y);
-        self.hover_cell = Some((col, row));
+        if self.hover_cell != Some((col, row)) {
+            self.hover_cell = Some((col, row));
+            self.refresh_hovered_link();
+        }
     }
 
     /// Clear the hover cell (mouse left the terminal area).
     pub fn clear_hover(&mut self) {
         self.hover_cell = None;
+        self.hovered_link = None;
     }
 
-    /// Get the URI of the hyperlink under the current hover cell, if any.
-    pub fn hyperlink_at_hover(&self) -> Option<String> {
+    /// Look for a plain URL or file reference under the mouse. Cells with
+    /// an OSC 8 hyperlink are left to it.
+    fn refresh_hovered_link(&mut self) {
+        let term = self.term.lock().unwrap();
+        let Some(point) = self.hover_point(&term) else {
+            self.hovered_link = None;
+            return;
+        };
+        self.hovered_link = if term.grid()[point].hyperlink().is_some() {
+            None
+        } else {
+            self.links.link_at(&term, point, self.cwd.as_deref())
+        };
+    }
+
+    /// The hover cell as a grid point.
+    fn hover_point(&self, term: &Term<TermEventListener>) -> Option<Point> {
         let (col, row) = self.hover_cell?;
+        Some(Self::viewport_to_grid_point(col, row, term.grid().display_offset()))
+    }
+
+    /// The link under the mouse: an OSC 8 hyperlink, or a detected URL
+    /// or file reference.
+    pub fn link_at_hover(&self) -> Option<LinkTarget> {
         let term = self.term.lock().unwrap();
-        let display_offset = term.grid().display_offset();
-        let point = Self::viewport_to_grid_point(col, row, display_offset);
-        let cell = &term.grid()[point];
-        cell.hyperlink().map(|h| h.uri().to_owned())
+        let point = self.hover_point(&term)?;
+        if let Some(link) = term.grid()[point].hyperlink() {
+            return Some(LinkTarget::Url(link.uri().to_owned()));
+        }
+        // Scrolling moves the text, not the mouse; check it is still there.
+        self.hovered_link
+            .as_ref()
+            .filter(|link| link.bounds.contains(&point))
+            .map(|link| link.target.clone())
     }
 
     /// Get the hyperlink ID at the hover cell, to detect contiguous hyperlink spans.
@@ -813,6 +1381,92 @@ impl TerminalPane {
         term.scroll_display(Scroll::Delta(delta_lines));
     }
 
+    /// Open the find bar, keeping the previous query if it was open before.
+    pub fn open_search(&mut self) {
+        if self.search.is_none() {
+            self.search = Some(TerminalSearch::new());
+        }
+    }
+
+    pub fn close_search(&mut self) {
+        self.search = None;
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.search.is_some()
+    }
+
+    /// Handle a key press while the find bar is open.
+    ///
+    /// Typing edits the query (matches update incrementally), Enter moves to
+    /// the next (older) match and Shift+Enter to the previous one, Alt+R
+    /// toggles regex mode, Escape closes the bar.
+    pub fn search_key(&mut self, key: &Key, mods: ModifiersState) {
+        let Some(search) = &mut self.search else { return };
+        match key {
+            Key::Named(NamedKey::Escape) => {
+                self.search = None;
+                return;
+            }
+            Key::Named(NamedKey::Enter) => {
+                self.search_step(!mods.shift_key());
+                return;
+            }
+            Key::Named(NamedKey::Backspace) => search.pop(),
+            Key::Named(NamedKey::Space) => search.push_str(" "),
+            Key::Character(c) if mods.alt_key() && c.eq_ignore_ascii_case("r") => search.toggle_regex(),
+            Key::Character(c) if !mods.control_key() && !mods.super_key() => search.push_str(c),
+            _ => return,
+        }
+        self.refresh_search();
+    }
+
+    /// Select the next (`older`) or previous match and scroll it into view.
+    /// Opens the find bar if it is closed.
+    pub fn search_step(&mut self, older: bool) {
+        match &mut self.search {
+            Some(search) => search.step(older),
+            None => self.open_search(),
+        }
+        self.reveal_current_match();
+    }
+
+    /// Re-run the query after an edit and jump to the selected match.
+    fn refresh_search(&mut self) {
+        let Some(search) = &mut self.search else { return };
+        if !search.is_dirty() {
+            return;
+        }
+        {
+            let term = self.term.lock().unwrap();
+            search.refresh(&term);
+        }
+        self.reveal_current_match();
+    }
+
+    /// Find the matches again if output arrived since the last scan. The
+    /// pane tree calls this once a frame for the panes on screen, so a
+    /// chatty process costs one scan per frame, and hidden panes none.
+    pub fn refresh_search_matches(&mut self) {
+        let Some(search) = &mut self.search else { return };
+        if search.is_stale() {
+            search.refresh(&self.term.lock().unwrap());
+        }
+    }
+
+    fn reveal_current_match(&mut self) {
+        let Some(line) = self.search.as_ref().and_then(|s| s.current_match()).map(|m| m.start().line) else {
+            return;
+        };
+        let mut term = self.term.lock().unwrap();
+        let current = term.grid().display_offset();
+        if let Some(target) =
+            terminal_search::offset_to_reveal(line, current, term.screen_lines(), term.history_size())
+        {
+            term.scroll_display(Scroll::Delta(target as i32 - current as i32));
+        }
+    }
+
     /// Get the currently selected text, if any.
     pub fn selected_text(&self) -> Option<String> {
         let term = self.term.lock().unwrap();
@@ -863,17 +1517,28 @@ impl TerminalPane {
     /// Render the terminal cell grid into a vello `Scene`.
     ///
     /// `offset_x` and `offset_y` are the top-left pixel position of the terminal
-    /// area within the window.
-    pub fn render_into_scene(&self, scene: &mut Scene, offset_x: f64, offset_y: f64, width: f64, height: f64) {
-        // Background fill.
-        let bg_rect = Rect::new(offset_x, offset_y, offset_x + width, offset_y + height);
-        scene.fill(Fill::NonZero, Affine::IDENTITY, TERM_BG, None, &bg_rect);
-
-        let term = self.term.lock().unwrap();
+    /// area within the window. Unfocused panes draw a hollow, steady cursor.
+    pub fn render_into_scene(
+        &self,
+        scene: &mut Scene,
+        offset_x: f64,
+        offset_y: f64,
+        width: f64,
+        height: f64,
+        focused: bool,
+    ) {
+        let mut term = self.term.lock().unwrap();
+        let damaged = take_damage(&mut term, self.rows);
+        let term = &*term;
         let content = term.renderable_content();
         let colors = content.colors;
         let display_offset = content.display_offset;
 
+        // Background fill.
+        let bg_rect = Rect::new(offset_x, offset_y, offset_x + width, offset_y + height);
+        let background = self.default_color(colors, NamedColor::Background);
+        scene.fill(Fill::NonZero, Affine::IDENTITY, background, None, &bg_rect);
+
         let cw = self.cell_width as f64;
         let ch = self.cell_height as f64;
 
@@ -884,48 +1549,93 @@ impl TerminalPane {
         // The first visible grid line is Line(-(display_offset as i32)).
         let first_visible_line = -(display_offset as i32);
 
-        // Determine the hyperlink ID under the hover cell (if any) for underline highlighting.
-        let hovered_hyperlink_id: Option<String> = self.hover_cell.and_then(|(hcol, hrow)| {
-            let point = Self::viewport_to_grid_point(hcol, hrow, display_offset);
-            let grid_cell = &term.grid()[point];
-            grid_cell.hyperlink().map(|h| h.id().to_owned())
-        });
-
-        let mut fg_glyphs: Vec<(Color, Vec<Glyph>)> = Vec::new();
-
-        for cell in content.display_iter {
-            let col = cell.point.column.0;
-            // Convert grid-absolute line to viewport row.
-            let viewport_row = (cell.point.line.0 - first_visible_line) as usize;
-
-            if col >= self.cols || viewport_row >= self.rows {
+        // Re-key the rows that changed and build lines not seen before.
+        let mut cache = self.line_cache.borrow_mut();
+        // Damage is tracked in screen lines, so it only maps onto viewport
+        // rows when the view is at the bottom.
+        let full = damaged.is_none()
+            || display_offset != 0
+            || cache.display_offset != display_offset
+            || cache.rows.len() != self.rows;
+        cache.rows.resize(self.rows, 0);
+        cache.display_offset = display_offset;
+        cache.built = 0;
+        for viewport_row in 0..self.rows {
+            if !full && !damaged.as_ref().is_some_and(|rows| rows[viewport_row]) {
                 continue;
             }
+            let row = &term.grid()[Line(first_visible_line + viewport_row as i32)];
+            let key = line_key(row, self.cols, colors, &self.scheme);
+            cache.rows[viewport_row] = key;
+            if !cache.lines.contains_key(&key) {
+                let line = self.build_line(row, colors);
+                cache.lines.insert(key, line);
+                cache.built += 1;
+            }
+        }
+        let LineCache { rows, lines, .. } = &mut *cache;
+        lines.retain(|key, _| rows.contains(key));
 
-            let cell_x = offset_x + col as f64 * cw;
-            let cell_y = offset_y + viewport_row as f64 * ch;
-
-            let (bg_color, fg_color) = resolve_cell_colors(&cell.cell, colors);
+        let row_origin = |viewport_row: usize| Affine::translate((offset_x, offset_y + viewport_row as f64 * ch));
+        for (viewport_row, key) in cache.rows.iter().enumerate() {
+            scene.append(&cache.lines[key].background, Some(row_origin(viewport_row)));
+        }
 
-            let bg_default = TERM_BG;
-            if bg_color != bg_default {
-                let rect = Rect::new(cell_x, cell_y, cell_x + cw, cell_y + ch);
-                scene.fill(Fill::NonZero, Affine::IDENTITY, bg_color, None, &rect);
-            }
+        // Determine the hyperlink ID under the hover cell (if any) for underline highlighting.
+        let hover_point = self.hover_point(&term);
+        let hovered_hyperlink_id: Option<String> =
+            hover_point.and_then(|point| term.grid()[point].hyperlink().map(|h| h.id().to_owned()));
+        // Otherwise the detected URL or file reference under it.
+        let hovered_link = self
+            .hovered_link
+            .as_ref()
+            .map(|link| &link.bounds)
+            .filter(|bounds| hover_point.is_some_and(|point| bounds.contains(&point)));
+
+        // Matches that intersect the viewport.
+        let last_visible_line = first_visible_line + self.rows as i32 - 1;
+        let visible_matches: Vec<_> = self.search.as_ref().map_or_else(Vec::new, |s| {
+            s.matches()
+                .iter()
+                .filter(|m| m.end().line.0 >= first_visible_line && m.start().line.0 <= last_visible_line)
+                .cloned()
+                .collect()
+        });
+        let current_match = self.search.as_ref().and_then(|s| s.current_match()).cloned();
+
+        // Selection, search and hyperlink highlights sit between the cached
+        // backgrounds and text. They change independently of line content,
+        // so they are drawn per frame, and only when there is one to draw.
+        if content.selection.is_some()
+            || !visible_matches.is_empty()
+            || hovered_hyperlink_id.is_some()
+            || hovered_link.is_some()
+        {
+            for viewport_row in 0..self.rows {
+                for col in 0..self.cols {
+                    let point = Self::viewport_to_grid_point(col, viewport_row, display_offset);
+                    let cell_x = offset_x + col as f64 * cw;
+                    let cell_y = offset_y + viewport_row as f64 * ch;
+                    let cell_rect = Rect::new(cell_x, cell_y, cell_x + cw, cell_y + ch);
+
+                    // Selection uses grid-absolute points (the SelectionRange
+                    // coordinate space).
+                    if content.selection.as_ref().is_some_and(|sel| sel.contains(point)) {
+                        scene.fill(Fill::NonZero, Affine::IDENTITY, self.scheme.selection, None, &cell_rect);
+                    }
 
-            // Draw selection highlight using the cell's grid-absolute point
-            // (matches SelectionRange coordinate space).
-            if let Some(ref sel) = content.selection {
-                if sel.contains(cell.point) {
-                    let rect = Rect::new(cell_x, cell_y, cell_x + cw, cell_y + ch);
-                    scene.fill(Fill::NonZero, Affine::IDENTITY, TERM_SELECTION, None, &rect);
-                }
-            }
+                    // Search matches: every match tinted, the selected one stronger.
+                    if let Some(color) = match_highlight(&visible_matches, current_match.as_ref(), point) {
+                        scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &cell_rect);
+                    }
 
-            // Draw hyperlink underline if this cell's hyperlink matches the hovered one.
-            if let Some(ref hovered_id) = hovered_hyperlink_id {
-                if let Some(link) = cell.cell.hyperlink() {
-                    if link.id() == hovered_id {
+                    // Underline the hovered link: cells sharing its hyperlink
+                    // id, or the span of the detected URL / file reference.
+                    let underlined = hovered_link.is_some_and(|bounds| bounds.contains(&point))
+                        || hovered_hyperlink_id.as_ref().is_some_and(|hovered_id| {
+                            term.grid()[point].hyperlink().is_some_and(|link| link.id() == hovered_id)
+                        });
+                    if underlined {
                         let underline_y = cell_y + ch - 2.0;
                         let underline_rect = Rect::new(
                             cell_x, underline_y,
@@ -935,65 +1645,396 @@ impl TerminalPane {
                     }
                 }
             }
+        }
+
+        for (viewport_row, key) in cache.rows.iter().enumerate() {
+            scene.append(&cache.lines[key].foreground, Some(row_origin(viewport_row)));
+        }
+        drop(cache);
+
+        // Draw cursor, or the IME composition in its place.
+        let cursor = content.cursor;
+        let col = cursor.point.column.0;
+        // cursor.point.line is an i32 Line value relative to the scroll
+        // history. For the viewport we convert via display_offset.
+        let viewport_row = cursor.point.line.0 + content.display_offset as i32;
+        // Skip when the cursor is outside the visible area.
+        if viewport_row >= 0 && viewport_row < self.rows as i32 && col < self.cols {
+            let cx = offset_x + col as f64 * cw;
+            let cy = offset_y + viewport_row as f64 * ch;
+            // Blinking applies to the focused pane only; `cursor_visible`
+            // is the blink phase.
+            let blink_off = focused && term.cursor_style().blinking && !self.cursor_visible;
+            if !self.preedit.is_empty() {
+                self.render_preedit(scene, cx, cy, colors);
+            } else if cursor.shape != CursorShape::Hidden && !blink_off {
+                let shape = if focused { cursor.shape } else { CursorShape::HollowBlock };
+                let wide = term.grid()[cursor.point].flags.contains(Flags::WIDE_CHAR);
+                let cursor_w = if wide { 2.0 * cw } else { cw };
+                let cursor_color = self.default_color(colors, NamedColor::Cursor);
+                draw_cursor(scene, shape, Rect::new(cx, cy, cx + cursor_w, cy + ch), cursor_color);
+            }
+        }
 
-            let ch_val = cell.cell.c;
-            if ch_val == ' ' || ch_val == '\0' || cell.cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+        if let Some(search) = &self.search {
+            self.render_find_bar(scene, search, offset_x - pad_px, offset_y, width, height);
+        } else if self.state != PaneState::Running {
+            self.render_exit_banner(scene, offset_x - pad_px, offset_y, width, height);
+        }
+
+        if self.is_recording() {
+            // Recording dot in the top-right corner of the pane.
+            let r = (self.cell_height as f64 * 0.18).max(3.0);
+            let center = (offset_x - pad_px + width - cw * 0.5 - r, offset_y + ch * 0.5);
+            scene.fill(Fill::NonZero, Affine::IDENTITY, TERM_RECORDING, None, &Circle::new(center, r));
+        }
+        if !self.badge.is_empty() {
+            // Left of where the recording dot goes.
+            self.render_badge(scene, offset_x - pad_px + width - cw * 2.0, offset_y);
+        }
+    }
+
+    /// Draw the badge ending at `right`: a dot for the process's health
+    /// (green running, ink after a clean exit, red after a failure) and
+    /// the badge text, with the time of the last auto-commit snapshot, on
+    /// a small bisque tab.
+    fn render_badge(&self, scene: &mut Scene, right: f64, top: f64) {
+        let size = (self.font_size as f64 * 0.6).max(10.0);
+        let r = size * 0.25;
+        let pad = size * 0.5;
+        let saved = chrono::DateTime::from_timestamp(self.backup.saved.load(Ordering::Relaxed), 0).filter(|t| t.timestamp() > 0);
+        let push_failed = self.backup.push_failed.load(Ordering::Relaxed);
+        let mut text = self.badge.clone();
+        if let Some(time) = saved {
+            text.push_str(&format!(" · saved {}", time.with_timezone(&chrono::Local).format("%H:%M")));
+        }
+        if push_failed {
+            text.push_str(" · push failed");
+        }
+        let text_w = text.chars().count() as f64 * size * 0.6;
+        let left = right - pad - r * 2.0 - pad * 0.8 - text_w - pad;
+        let bottom = top + size * 1.6;
+        scene.fill(Fill::NonZero, Affine::IDENTITY, FIND_BAR_BG, None, &Rect::new(left, top, right, bottom));
+
+        let health = match &self.state {
+            PaneState::Running if push_failed => EXIT_BANNER_FAILED,
+            PaneState::Running => BADGE_RUNNING,
+            PaneState::Exited(0) => FIND_BAR_INK,
+            PaneState::Exited(_) | PaneState::Killed(_) => EXIT_BANNER_FAILED,
+        };
+        let mid = (top + bottom) / 2.0;
+        scene.fill(Fill::NonZero, Affine::IDENTITY, health, None, &Circle::new((left + pad + r, mid), r));
+        let text_x = left + pad + r * 2.0 + pad * 0.8;
+        crate::dashboard::draw_text_pub(scene, text_x, mid + size * 0.35, &text, FIND_BAR_INK, size, Some(self.fonts.primary()));
+    }
+
+    /// Draw one grid line with its top-left cell at the origin.
+    fn build_line(&self, row: &Row<Cell>, colors: &Colors) -> CachedLine {
+        let cw = self.cell_width as f64;
+        let ch = self.cell_height as f64;
+        let default_bg = self.default_color(colors, NamedColor::Background);
+        let mut background = Scene::new();
+        let mut foreground = Scene::new();
+
+        // Glyph batches keyed by (font in the chain, color).
+        let mut fg_glyphs: Vec<((usize, Color), Vec<Glyph>)> = Vec::new();
+
+        for col in 0..self.cols.min(row.len()) {
+            let cell = &row[Column(col)];
+            let cell_x = col as f64 * cw;
+            let (bg_color, fg_color) = resolve_cell_colors(cell, colors, &self.scheme);
+
+            if bg_color != default_bg {
+                let rect = Rect::new(cell_x, 0.0, cell_x + cw, ch);
+                background.fill(Fill::NonZero, Affine::IDENTITY, bg_color, None, &rect);
+            }
+
+            let ch_val = cell.c;
+            if ch_val == ' ' || ch_val == '\0' || cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
                 continue;
             }
 
-            let font_ref = skrifa::FontRef::from_index(self.font.data.as_ref(), self.font.index);
-            if let Ok(font_ref) = font_ref {
-                use skrifa::MetadataProvider;
-                let charmap = font_ref.charmap();
-                let gid = charmap.map(ch_val).unwrap_or_default();
-                let baseline_y = cell_y + ch as f64 * 0.8;
-                let glyph = Glyph {
-                    id: gid.to_u32(),
-                    x: cell_x as f32,
-                    y: baseline_y as f32,
-                };
-                if let Some(batch) = fg_glyphs.iter_mut().find(|(c, _)| *c == fg_color) {
-                    batch.1.push(glyph);
-                } else {
-                    fg_glyphs.push((fg_color, vec![glyph]));
-                }
+            // Lines and blocks are drawn from the cell rect so they join up.
+            if box_drawing::draw(&mut foreground, ch_val, cell_x, 0.0, cw, ch, fg_color) {
+                continue;
+            }
+
+            let glyph = self.fonts.glyph(ch_val);
+            // A wide character owns its spacer cell too. Fallback glyphs
+            // (CJK, emoji) are centered, since their advances don't match
+            // the terminal font's cell width.
+            let span = if cell.flags.contains(Flags::WIDE_CHAR) { 2.0 * cw } else { cw };
+            let glyph_x = if glyph.font == 0 {
+                cell_x
+            } else {
+                cell_x + ((span - self.fonts.advance(glyph, self.font_size) as f64) / 2.0).max(0.0)
+            };
+            let positioned = Glyph {
+                id: glyph.id,
+                x: glyph_x as f32,
+                y: (ch * 0.8) as f32,
+            };
+            let key = (glyph.font, fg_color);
+            if let Some(batch) = fg_glyphs.iter_mut().find(|(k, _)| *k == key) {
+                batch.1.push(positioned);
+            } else {
+                fg_glyphs.push((key, vec![positioned]));
             }
         }
 
-        // Flush all glyph batches.
-        for (color, glyphs) in fg_glyphs {
-            scene
-                .draw_glyphs(&self.font)
+        for ((font, color), glyphs) in fg_glyphs {
+            foreground
+                .draw_glyphs(self.fonts.font(font))
                 .font_size(self.font_size)
                 .brush(&color)
                 .draw(Fill::NonZero, glyphs.into_iter());
         }
 
-        // Draw cursor.
-        if self.cursor_visible {
-            let cursor = content.cursor;
-            if cursor.shape != alacritty_terminal::vte::ansi::CursorShape::Hidden {
-                let col = cursor.point.column.0;
-                let row = {
-                    // cursor.point.line is an i32 Line value relative to the scroll
-                    // history. For the viewport we convert via display_offset.
-                    let line_i32 = cursor.point.line.0;
-                    let viewport_row = line_i32 + content.display_offset as i32;
-                    if viewport_row < 0 || viewport_row >= self.rows as i32 {
-                        // Cursor is outside the visible area.
-                        return;
-                    }
-                    viewport_row as usize
-                };
-                if col < self.cols {
-                    let cx = offset_x + col as f64 * cw;
-                    let cy = offset_y + row as f64 * ch;
-                    let cursor_rect = Rect::new(cx, cy, cx + cw, cy + ch);
-                    scene.fill(Fill::NonZero, Affine::IDENTITY, TERM_CURSOR, None, &cursor_rect);
+        CachedLine { background, foreground }
+    }
+
+    /// Draw the IME composition over the cells from `(x, y)`: underlined
+    /// text on the terminal background, with a thin caret where the IME
+    /// puts it.
+    fn render_preedit(&self, scene: &mut Scene, x: f64, y: f64, colors: &Colors) {
+        let cw = self.cell_width as f64;
+        let ch = self.cell_height as f64;
+        let fg = self.default_color(colors, NamedColor::Foreground);
+        let cells: usize = self.preedit.chars().map(char_cells).sum();
+        let right = x + cells as f64 * cw;
+        let bg = self.default_color(colors, NamedColor::Background);
+        scene.fill(Fill::NonZero, Affine::IDENTITY, bg, None, &Rect::new(x, y, right, y + ch));
+        scene.fill(Fill::NonZero, Affine::IDENTITY, fg, None, &Rect::new(x, y + ch - 2.0, right, y + ch - 1.0));
+
+        let mut cell = 0;
+        for c in self.preedit.chars() {
+            let glyph = self.fonts.glyph(c);
+            let positioned = Glyph {
+                id: glyph.id,
+                x: (x + cell as f64 * cw) as f32,
+                y: (y + ch * 0.8) as f32,
+            };
+            scene
+                .draw_glyphs(self.fonts.font(glyph.font))
+                .font_size(self.font_size)
+                .brush(&fg)
+                .draw(Fill::NonZero, std::iter::once(positioned));
+            cell += char_cells(c);
+        }
+
+        if let Some(before) = self.preedit_cursor.and_then(|i| self.preedit.get(..i)) {
+            let caret_x = x + before.chars().map(char_cells).sum::<usize>() as f64 * cw;
+            let cursor_color = self.default_color(colors, NamedColor::Cursor);
+            scene.fill(Fill::NonZero, Affine::IDENTITY, cursor_color, None, &Rect::new(caret_x, y, caret_x + 1.5, y + ch));
+        }
+    }
+
+    /// Default foreground, background or cursor color: the application's
+    /// OSC 10/11/12 override if it set one, else the scheme's.
+    fn default_color(&self, colors: &Colors, named: NamedColor) -> Color {
+        colors[named].map_or_else(|| self.scheme.named(named), rgb_to_color)
+    }
+
+    /// Draw the exit banner along the bottom edge of the pane:
+    /// `Process exited with code 1      Enter to restart`.
+    fn render_exit_banner(&self, scene: &mut Scene, x: f64, y: f64, width: f64, height: f64) {
+        let size = (self.font_size as f64 * 0.75).max(12.0);
+        let bar_h = size * 2.0;
+        let top = y + height - bar_h;
+        scene.fill(Fill::NonZero, Affine::IDENTITY, FIND_BAR_BG, None, &Rect::new(x, top, x + width, y + height));
+        scene.fill(Fill::NonZero, Affine::IDENTITY, FIND_BAR_INK, None, &Rect::new(x, top, x + width, top + 0.5));
+
+        let (message, color) = match &self.state {
+            PaneState::Running => return,
+            PaneState::Exited(0) => ("Process exited".to_string(), FIND_BAR_TEXT),
+            PaneState::Exited(code) => (format!("Process exited with code {}", code), EXIT_BANNER_FAILED),
+            PaneState::Killed(signal) => (format!("Process killed ({})", signal), EXIT_BANNER_FAILED),
+        };
+        let pad = self.cell_width as f64;
+        let baseline = top + bar_h * 0.68;
+        let font = Some(self.fonts.primary());
+        crate::dashboard::draw_text_pub(scene, x + pad, baseline, &message, color, size, font);
+        let hint = "Enter to restart";
+        let hx = x + width - pad - hint.chars().count() as f64 * size * 0.6;
+        crate::dashboard::draw_text_pub(scene, hx, baseline, hint, FIND_BAR_INK, size, font);
+    }
+
+    /// Draw the find bar along the bottom edge of the pane:
+    /// `Find  query▏      Regex  3 of 17`.
+    fn render_find_bar(
+        &self,
+        scene: &mut Scene,
+        search: &TerminalSearch,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    ) {
+        let size = (self.font_size as f64 * 0.75).max(12.0);
+        let bar_h = size * 2.0;
+        let top = y + height - bar_h;
+        scene.fill(Fill::NonZero, Affine::IDENTITY, FIND_BAR_BG, None, &Rect::new(x, top, x + width, y + height));
+        scene.fill(Fill::NonZero, Affine::IDENTITY, FIND_BAR_INK, None, &Rect::new(x, top, x + width, top + 0.5));
+
+        let pad = self.cell_width as f64;
+        let baseline = top + bar_h * 0.68;
+        let font = Some(self.fonts.primary());
+        let char_w = size * 0.6;
+
+        crate::dashboard::draw_text_pub(scene, x + pad, baseline, "Find", FIND_BAR_INK, size, font);
+        let qx = x + pad + char_w * 6.0;
+        crate::dashboard::draw_text_pub(scene, qx, baseline, search.query(), FIND_BAR_TEXT, size, font);
+        let caret_x = qx + search.query().chars().count() as f64 * char_w;
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            FIND_BAR_TEXT,
+            None,
+            &Rect::new(caret_x, baseline - size * 0.8, caret_x + 1.5, baseline + size * 0.2),
+        );
+
+        let mode = if search.is_regex() { "Regex" } else { "Text" };
+        let status = format!("{}  {}", mode, search.status());
+        let sx = x + width - pad - status.chars().count() as f64 * char_w;
+        crate::dashboard::draw_text_pub(scene, sx, baseline, &status, FIND_BAR_INK, size, font);
+    }
+}
+
+/// Closing a pane ends its process rather than leaving it running
+/// without a terminal.
+impl Drop for TerminalPane {
+    fn drop(&mut self) {
+        if self.state == PaneState::Running {
+            if let Some(child) = self.child.as_mut() {
+                let _ = child.kill();
+                let _ = child.try_wait();
+            }
+        }
+        if let Some(cleanup) = self.on_close.take() {
+            cleanup();
+        }
+    }
+}
+
+/// Draw the cursor over `cell` (two cells wide on a wide character).
+fn draw_cursor(scene: &mut Scene, shape: CursorShape, cell: Rect, color: Color) {
+    let thickness = (cell.height() * 0.08).max(1.5);
+    let rect = match shape {
+        CursorShape::Underline => Rect::new(cell.x0, cell.y1 - thickness, cell.x1, cell.y1),
+        CursorShape::Beam => Rect::new(cell.x0, cell.y0, cell.x0 + thickness, cell.y1),
+        CursorShape::HollowBlock => {
+            let width = thickness * 0.75;
+            let outline = cell.inset(-width / 2.0);
+            scene.stroke(&Stroke::new(width), Affine::IDENTITY, color, None, &outline);
+            return;
+        }
+        _ => cell,
+    };
+    scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &rect);
+}
+
+/// Cells a character takes on screen: 2 for East Asian wide characters
+/// and emoji, otherwise 1.
+fn char_cells(c: char) -> usize {
+    match c as u32 {
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Line cache
+// ---------------------------------------------------------------------------
+
+/// One rendered grid line, drawn with its top-left cell at the origin.
+struct CachedLine {
+    /// Cell backgrounds that differ from the terminal background.
+    background: Scene,
+    /// Glyph runs and drawn box/block characters.
+    foreground: Scene,
+}
+
+/// Rendered lines, keyed by content rather than position.
+///
+/// Output that scrolls the screen moves lines without changing them, so
+/// keying on content lets every moved line be reused. Rows alacritty
+/// reports undamaged keep last frame's key without being re-hashed.
+#[derive(Default)]
+struct LineCache {
+    /// Content key shown at each viewport row in the last frame.
+    rows: Vec<u64>,
+    /// Built lines; only those on screen are kept.
+    lines: HashMap<u64, CachedLine>,
+    /// Scroll position of the last frame (scrolling moves every row).
+    display_offset: usize,
+    /// Lines built during the last frame.
+    built: usize,
+}
+
+impl LineCache {
+    fn clear(&mut self) {
+        self.rows.clear();
+        self.lines.clear();
+    }
+}
+
+/// Viewport rows changed since the last call, or `None` if everything
+/// should be treated as changed. Resets the terminal's damage state.
+fn take_damage(term: &mut Term<TermEventListener>, rows: usize) -> Option<Vec<bool>> {
+    let damaged = match term.damage() {
+        TermDamage::Full => None,
+        TermDamage::Partial(lines) => {
+            let mut damaged = vec![false; rows];
+            for bounds in lines {
+                if let Some(row) = damaged.get_mut(bounds.line) {
+                    *row = true;
                 }
             }
+            Some(damaged)
+        }
+    };
+    term.reset_damage();
+    damaged
+}
+
+/// Hash of everything `build_line` draws for `row`.
+fn line_key(row: &Row<Cell>, cols: usize, colors: &Colors, scheme: &ColorScheme) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for col in 0..cols.min(row.len()) {
+        let cell = &row[Column(col)];
+        let (bg, fg) = resolve_cell_colors(cell, colors, scheme);
+        cell.c.hash(&mut hasher);
+        (cell.flags & (Flags::WIDE_CHAR | Flags::WIDE_CHAR_SPACER)).bits().hash(&mut hasher);
+        for component in bg.components.iter().chain(&fg.components) {
+            component.to_bits().hash(&mut hasher);
         }
     }
+    hasher.finish()
+}
+
+/// Highlight color for the cell at `point`, if it lies inside a search match.
+fn match_highlight(
+    visible: &[Match],
+    current: Option<&Match>,
+    point: Point,
+) -> Option<Color> {
+    if current.is_some_and(|m| m.contains(&point)) {
+        return Some(TERM_MATCH_CURRENT);
+    }
+    visible.iter().any(|m| m.contains(&point)).then_some(TERM_MATCH)
 }
 
 // ---------------------------------------------------------------------------
@@ -1002,17 +2043,19 @@ impl TerminalPane {
 
 /// Resolve terminal cell fg/bg colors to vello `Color` values.
 ///
-/// Handles `Named`, `Indexed`, and `Spec` (true-color) variants.
-/// Falls back to sensible defaults for colors not present in the palette.
-fn resolve_cell_colors(
-    cell: &alacritty_terminal::term::cell::Cell,
-    colors: &alacritty_terminal::term::color::Colors,
-) -> (Color, Color) {
+/// Handles `Named`, `Indexed`, and `Spec` (true-color) variants. Colors
+/// the application set (OSC 4/10/11/12) win; the rest come from `scheme`.
+fn resolve_cell_colors(cell: &Cell, colors: &Colors, scheme: &ColorScheme) -> (Color, Color) {
     let flags = cell.flags;
     let inverted = flags.contains(Flags::INVERSE);
 
-    let mut fg = resolve_color(&cell.fg, colors, /* is_fg */ true);
-    let mut bg = resolve_color(&cell.bg, colors, /* is_fg */ false);
+    let fg_spec = match cell.fg {
+        AlaColor::Named(named) if brighten(flags, scheme) && (named as usize) < 8 => AlaColor::Named(named.to_bright()),
+        AlaColor::Indexed(idx) if brighten(flags, scheme) && idx < 8 => AlaColor::Indexed(idx + 8),
+        spec => spec,
+    };
+    let mut fg = resolve_color(&fg_spec, colors, scheme);
+    let mut bg = resolve_color(&cell.bg, colors, scheme);
 
     if inverted {
         std::mem::swap(&mut fg, &mut bg);
@@ -1021,23 +2064,16 @@ fn resolve_cell_colors(
     (bg, fg)
 }
 
-fn resolve_color(color: &AlaColor, colors: &alacritty_terminal::term::color::Colors, is_fg: bool) -> Color {
+/// Whether a cell's normal-intensity colors are drawn bright.
+fn brighten(flags: Flags, scheme: &ColorScheme) -> bool {
+    scheme.bold_is_bright && flags.contains(Flags::BOLD)
+}
+
+fn resolve_color(color: &AlaColor, colors: &Colors, scheme: &ColorScheme) -> Color {
     match color {
         AlaColor::Spec(rgb) => rgb_to_color(*rgb),
-        AlaColor::Named(named) => {
-            // Try the palette first; fall back to hardcoded ANSI defaults.
-            if let Some(rgb) = colors[*named] {
-                return rgb_to_color(rgb);
-            }
-            named_color_fallback(*named, is_fg)
-        }
-        AlaColor::Indexed(idx) => {
-            if let Some(rgb) = colors[*idx as usize] {
-                return rgb_to_color(rgb);
-            }
-            // 256-color palette fallback.
-            indexed_color_fallback(*idx)
-        }
+        AlaColor::Named(named) => colors[*named].map_or_else(|| scheme.named(*named), rgb_to_color),
+        AlaColor::Indexed(idx) => colors[*idx as usize].map_or_else(|| scheme.indexed(*idx), rgb_to_color),
     }
 }
 
@@ -1050,128 +2086,22 @@ fn rgb_to_color(rgb: Rgb) -> Color {
     ])
 }
 
-/// Hardcoded ANSI 16-color palette (xterm defaults).
-fn named_color_fallback(named: NamedColor, is_fg: bool) -> Color {
-    match named {
-        NamedColor::Black => Color::new([0.0, 0.0, 0.0, 1.0]),
-        NamedColor::Red => Color::new([0.80, 0.11, 0.11, 1.0]),
-        NamedColor::Green => Color::new([0.13, 0.69, 0.30, 1.0]),
-        NamedColor::Yellow => Color::new([0.80, 0.69, 0.11, 1.0]),
-        NamedColor::Blue => Color::new([0.20, 0.40, 0.90, 1.0]),
-        NamedColor::Magenta => Color::new([0.67, 0.11, 0.80, 1.0]),
-        NamedColor::Cyan => Color::new([0.11, 0.69, 0.80, 1.0]),
-        NamedColor::White => Color::new([0.75, 0.75, 0.75, 1.0]),
-        NamedColor::BrightBlack => Color::new([0.10, 0.12, 0.13, 1.0]),
-        NamedColor::BrightRed => Color::new([0.94, 0.30, 0.30, 1.0]),
-        NamedColor::BrightGreen => Color::new([0.30, 0.87, 0.30, 1.0]),
-        NamedColor::BrightYellow => Color::new([0.94, 0.94, 0.20, 1.0]),
-        NamedColor::BrightBlue => Color::new([0.42, 0.59, 0.94, 1.0]),
-        NamedColor::BrightMagenta => Color::new([0.87, 0.30, 0.94, 1.0]),
-        NamedColor::BrightCyan => Color::new([0.30, 0.87, 0.94, 1.0]),
-        NamedColor::BrightWhite => Color::new([0.94, 0.94, 0.94, 1.0]),
-        NamedColor::Foreground => {
-            if is_fg { TERM_FG } else { TERM_BG }
-        }
-        NamedColor::Background => {
-            if is_fg { TERM_FG } else { TERM_BG }
-        }
-        NamedColor::Cursor => TERM_CURSOR,
-        _ => if is_fg { TERM_FG } else { TERM_BG },
-    }
-}
-
-/// Generate a color from the xterm 256-color cube for indices 16..=255.
-fn indexed_color_fallback(idx: u8) -> Color {
-    if idx < 16 {
-        // Named colors range — shouldn't reach here normally.
-        return TERM_FG;
-    }
-    if idx < 232 {
-        // 6x6x6 color cube: indices 16..=231
-        let idx = (idx - 16) as u32;
-        let b = idx % 6;
-        let g = (idx / 6) % 6;
-        let r = idx / 36;
-        let to_f = |v: u32| -> f32 { if v == 0 { 0.0 } else { (55 + v * 40) as f32 / 255.0 } };
-        return Color::new([to_f(r), to_f(g), to_f(b), 1.0]);
-    }
-    // Grayscale ramp: indices 232..=255
-    let level = (idx - 232) as f32;
-    let v = (8.0 + level * 10.0) / 255.0;
-    Color::new([v, v, v, 1.0])
-}
-
 // ---------------------------------------------------------------------------
-// Key → PTY bytes
+// Key encoding modes
 // ---------------------------------------------------------------------------
 
-/// Convert a winit keyboard event to the byte sequence to send to the PTY.
-///
-/// `app_cursor`: when true, arrow keys send SS3 sequences (\x1bO) instead of CSI (\x1b[).
-/// Returns an empty `Vec` if the key has no terminal meaning.
-pub fn key_event_to_pty_bytes(key: &Key, ctrl_held: bool, app_cursor: bool) -> Vec<u8> {
-    match key {
-        Key::Character(c) => {
-            let s = c.as_str();
-            if ctrl_held {
-                // Control characters: Ctrl+A..Z → \x01..\x1a
-                if let Some(ch) = s.chars().next() {
-                    let ch = ch.to_ascii_lowercase();
-                    if ch >= 'a' && ch <= 'z' {
-                        return vec![ch as u8 - b'a' + 1];
-                    }
-                    // Special ctrl sequences
-                    match ch {
-                        '[' => return vec![0x1b],     // Ctrl+[ = ESC
-                        '\\' => return vec![0x1c],
-                        ']' => return vec![0x1d],
-                        '^' => return vec![0x1e],
-                        '_' => return vec![0x1f],
-                        ' ' => return vec![0x00],     // Ctrl+Space = NUL
-                        _ => {}
-                    }
-                }
-            }
-            s.as_bytes().to_vec()
-        }
-        Key::Named(named) => match named {
-            NamedKey::Enter => vec![b'\r'],
-            NamedKey::Backspace => vec![0x7f],
-            NamedKey::Tab => vec![b'\t'],
-            NamedKey::Escape => vec![0x1b],
-            NamedKey::Delete => b"\x1b[3~".to_vec(),
-            // Arrow keys: SS3 in app cursor mode, CSI otherwise.
-            NamedKey::ArrowUp    => if app_cursor { b"\x1bOA".to_vec() } else { b"\x1b[A".to_vec() },
-            NamedKey::ArrowDown  => if app_cursor { b"\x1bOB".to_vec() } else { b"\x1b[B".to_vec() },
-            NamedKey::ArrowRight => if app_cursor { b"\x1bOC".to_vec() } else { b"\x1b[C".to_vec() },
-            NamedKey::ArrowLeft  => if app_cursor { b"\x1bOD".to_vec() } else { b"\x1b[D".to_vec() },
-            // Home/End: SS3 in app cursor mode.
-            NamedKey::Home => if app_cursor { b"\x1bOH".to_vec() } else { b"\x1b[H".to_vec() },
-            NamedKey::End  => if app_cursor { b"\x1bOF".to_vec() } else { b"\x1b[F".to_vec() },
-            // Navigation keys.
-            NamedKey::PageUp => b"\x1b[5~".to_vec(),
-            NamedKey::PageDown => b"\x1b[6~".to_vec(),
-            // Space (winit may emit space as Named(Space) instead of Character(" ")).
-            NamedKey::Space => vec![b' '],
-            // Insert key.
-            NamedKey::Insert => b"\x1b[2~".to_vec(),
-            // Function keys F1-F12 (standard xterm sequences).
-            NamedKey::F1 => b"\x1bOP".to_vec(),
-            NamedKey::F2 => b"\x1bOQ".to_vec(),
-            NamedKey::F3 => b"\x1bOR".to_vec(),
-            NamedKey::F4 => b"\x1bOS".to_vec(),
-            NamedKey::F5 => b"\x1b[15~".to_vec(),
-            NamedKey::F6 => b"\x1b[17~".to_vec(),
-            NamedKey::F7 => b"\x1b[18~".to_vec(),
-            NamedKey::F8 => b"\x1b[19~".to_vec(),
-            NamedKey::F9 => b"\x1b[20~".to_vec(),
-            NamedKey::F10 => b"\x1b[21~".to_vec(),
-            NamedKey::F11 => b"\x1b[23~".to_vec(),
-            NamedKey::F12 => b"\x1b[24~".to_vec(),
-            _ => vec![],
-        },
-        _ => vec![],
-    }
+/// Kitty keyboard flags currently pushed by the application.
+fn kitty_flags(mode: TermMode) -> u8 {
+    [
+        (TermMode::DISAMBIGUATE_ESC_CODES, key_encoding::kitty::DISAMBIGUATE),
+        (TermMode::REPORT_EVENT_TYPES, key_encoding::kitty::REPORT_EVENT_TYPES),
+        (TermMode::REPORT_ALTERNATE_KEYS, key_encoding::kitty::REPORT_ALTERNATE_KEYS),
+        (TermMode::REPORT_ALL_KEYS_AS_ESC, key_encoding::kitty::REPORT_ALL_KEYS),
+        (TermMode::REPORT_ASSOCIATED_TEXT, key_encoding::kitty::REPORT_TEXT),
+    ]
+    .iter()
+    .filter(|(flag, _)| mode.contains(*flag))
+    .fold(0, |acc, (_, bit)| acc | bit)
 }
 
 // ---------------------------------------------------------------------------
@@ -1199,3 +2129,181 @@ fn compute_cell_size(font_data: &FontData, font_size: f32) -> (f32, f32) {
     // Fallback: rough monospace estimates.
     (font_size * 0.6, font_size * 1.4)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A pane with no backend; tests feed it through the parser directly.
+    fn test_pane(cols: usize, rows: usize) -> TerminalPane {
+        let (_tx, rx) = tokio_mpsc::unbounded_channel();
+        TerminalPane::playback(rx, cols, rows)
+    }
+
+    fn feed(pane: &mut TerminalPane, bytes: &[u8]) {
+        let mut term = pane.term.lock().unwrap();
+        pane.processor.advance(&mut *term, bytes);
+    }
+
+    /// Render a frame, returning how many lines had to be built.
+    fn render(pane: &TerminalPane) -> usize {
+        let mut scene = Scene::new();
+        pane.render_into_scene(&mut scene, 0.0, 0.0, pane.pixel_width, pane.pixel_height, true);
+        pane.line_cache.borrow().built
+    }
+
+    /// Send `bytes` through `drain_output`, as PTY output would arrive.
+    fn drain(pane: &mut TerminalPane, bytes: &[u8]) {
+        let (tx, rx) = tokio_mpsc::unbounded_channel();
+        pane.rx = rx;
+        tx.send(bytes.to_vec()).unwrap();
+        pane.drain_output();
+    }
+
+    fn display_offset(pane: &TerminalPane) -> usize {
+        pane.term.lock().unwrap().grid().display_offset()
+    }
+
+    #[test]
+    fn prompt_marks_drive_jumps_and_output_selection() {
+        let mut pane = test_pane(20, 4);
+        for i in 0..3 {
+            let command = format!("\x1b]133;A\x07$ cmd\r\n\x1b]133;C\x07out{}\r\n\x1b]133;D;0\x07", i);
+            drain(&mut pane, command.as_bytes());
+        }
+        // The marker splits one chunk; the cwd report arrives in two.
+        drain(&mut pane, b"\x1b]133;A\x07$ \x1b]7;file://host/t");
+        drain(&mut pane, b"mp\x07");
+        assert_eq!(pane.cwd, Some(PathBuf::from("/tmp")));
+
+        // Prompts sit on lines 0, 2, 4 and 6; the screen shows 3..=6.
+        pane.jump_to_prompt(true);
+        assert_eq!(display_offset(&pane), 1);
+        pane.jump_to_prompt(true);
+        assert_eq!(display_offset(&pane), 3);
+        pane.jump_to_prompt(false);
+        assert_eq!(display_offset(&pane), 1);
+
+        assert!(pane.select_last_output());
+        assert_eq!(pane.selected_text().as_deref(), Some("out2"));
+    }
+
+    fn link_at(pane: &mut TerminalPane, col: usize, row: usize) -> Option<LinkTarget> {
+        pane.hover_cell = Some((col, row));
+        pane.refresh_hovered_link();
+        pane.link_at_hover()
+    }
+
+    #[test]
+    fn detects_urls_and_file_references() {
+        let mut pane = test_pane(40, 3);
+        feed(&mut pane, b"see (https://example.com/a_b).\r\nat src/main.rs:12:3: oops\r\nport localhost:8080");
+        assert_eq!(link_at(&mut pane, 10, 0), Some(LinkTarget::Url("https://example.com/a_b".into())));
+        // The closing parenthesis and full stop are not part of the URL.
+        assert_eq!(link_at(&mut pane, 29, 0), None);
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(
+            link_at(&mut pane, 5, 1),
+            Some(LinkTarget::File { path: cwd.join("src/main.rs"), line: Some(12), column: Some(3) })
+        );
+        assert_eq!(link_at(&mut pane, 8, 2), None);
+    }
+
+    #[test]
+    fn reports_exit_code() {
+        let mut pane = TerminalPane::spawn_command(400.0, 200.0, "sh", &["-c", "exit 3"], &[]).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !pane.poll_exit() {
+            assert!(Instant::now() < deadline, "child did not exit");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(pane.state(), &PaneState::Exited(3));
+        assert!(!pane.poll_exit());
+    }
+
+    #[test]
+    fn unchanged_lines_are_not_rebuilt() {
+        let mut pane = test_pane(40, 6);
+        feed(&mut pane, b"one\r\ntwo\r\nthree");
+        // Three text lines plus one shared blank line.
+        assert_eq!(render(&pane), 4);
+        assert_eq!(render(&pane), 0);
+        feed(&mut pane, b"!");
+        assert_eq!(render(&pane), 1);
+    }
+
+    #[test]
+    fn scrolled_lines_are_reused() {
+        let mut pane = test_pane(40, 4);
+        feed(&mut pane, b"a\r\nb\r\nc\r\nd");
+        assert_eq!(render(&pane), 4);
+        // Everything moves up a row; only the new bottom line is built.
+        feed(&mut pane, b"\r\ne");
+        assert_eq!(render(&pane), 1);
+    }
+
+    #[test]
+    fn colors_are_part_of_the_key() {
+        let mut pane = test_pane(40, 2);
+        feed(&mut pane, b"same\r\n\x1b[31msame\x1b[0m");
+        assert_eq!(render(&pane), 2);
+    }
+
+    /// Streams a few MB of colored, box-drawn output through `drain_output`
+    /// and renders a frame after each drain, as the app does.
+    ///
+    /// Run with: cargo test --release render_benchmark -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn render_benchmark() {
+        let mut chunk = Vec::new();
+        for i in 0..200 {
+            chunk.extend_from_slice(
+                format!(
+                    "\x1b[1;34m│\x1b[0m \x1b[32m+\x1b[0m line {:>4}: \x1b[33mfn\x1b[0m render_into_scene(&self) {{ 测试 ✓ }}\r\n",
+                    i
+                )
+                .as_bytes(),
+            );
+        }
+        let total_bytes = 8 << 20;
+        let chunks = total_bytes / chunk.len();
+
+        let (tx, rx) = tokio_mpsc::unbounded_channel();
+        let mut pane = TerminalPane::playback(rx, 160, 50);
+        for _ in 0..chunks {
+            tx.send(chunk.clone()).unwrap();
+        }
+        drop(tx);
+
+        let mut scene = Scene::new();
+        let mut frames = 0;
+        let mut built = 0;
+        let start = Instant::now();
+        while !(pane.rx.is_closed() && pane.rx.is_empty()) {
+            pane.drain_output();
+            scene.reset();
+            pane.render_into_scene(&mut scene, 0.0, 0.0, pane.pixel_width, pane.pixel_height, true);
+            built += pane.line_cache.borrow().built;
+            frames += 1;
+        }
+        let streaming = start.elapsed();
+        let mb = (chunks * chunk.len()) as f64 / (1 << 20) as f64;
+        println!(
+            "streaming: {:.1} MB in {:.2?} ({:.1} MB/s), {} frames, {:.1} lines built per frame",
+            mb,
+            streaming,
+            mb / streaming.as_secs_f64(),
+            frames,
+            built as f64 / frames.max(1) as f64
+        );
+
+        let idle_frames = 1000;
+        let start = Instant::now();
+        for _ in 0..idle_frames {
+            scene.reset();
+            pane.render_into_scene(&mut scene, 0.0, 0.0, pane.pixel_width, pane.pixel_height, true);
+        }
+        println!("idle: {:.2?} per frame", start.elapsed() / idle_frames);
+    }
+}
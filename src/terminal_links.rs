@@ -0,0 +1,215 @@
+//! Links in terminal output that the application did not mark up.
+//!
+//! OSC 8 hyperlinks carry their own URI (see `TerminalPane::link_at_hover`).
+//! Everything else is found by pattern under the mouse: URLs with a known
+//! scheme, and file references such as `src/main.rs:42:7` — compiler
+//! errors, stack traces and Claude's edits all print these.
+//!
+//! A file reference must name a file that exists, relative to the shell's
+//! working directory (OSC 7) when it is known; that keeps ordinary words
+//! followed by a colon and a number (`localhost:8080`) from lighting up.
+//! Modifier+click opens URLs with the system opener and file references
+//! with the `terminal.editor` command.
+
+use std::path::{Path, PathBuf};
+
+use alacritty_terminal::Term;
+use alacritty_terminal::event::EventListener;
+use alacritty_terminal::index::{Boundary, Direction, Point};
+use alacritty_terminal::term::search::{Match, RegexIter, RegexSearch};
+use tracing::{info, warn};
+
+/// URLs with a scheme, as in Alacritty's default hint.
+const URL_PATTERN: &str = r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file:|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`]+"#;
+
+/// A path, optionally followed by `:line` and `:line:column`.
+const FILE_PATTERN: &str = r"[\w.~/@+-]+(:[0-9]+){0,2}";
+
+/// What a link points at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkTarget {
+    Url(String),
+    File {
+        path: PathBuf,
+        line: Option<u32>,
+        column: Option<u32>,
+    },
+}
+
+/// A link found in the grid, with the cells it covers.
+#[derive(Debug, Clone)]
+pub struct DetectedLink {
+    pub bounds: Match,
+    pub target: LinkTarget,
+}
+
+pub struct LinkDetector {
+    url: RegexSearch,
+    file: RegexSearch,
+}
+
+impl LinkDetector {
+    pub fn new() -> Self {
+        Self {
+            url: RegexSearch::new(URL_PATTERN).expect("URL pattern compiles"),
+            file: RegexSearch::new(FILE_PATTERN).expect("file pattern compiles"),
+        }
+    }
+
+    /// The URL or file reference covering `point`, searching the whole
+    /// (possibly wrapped) line. Relative paths resolve against `cwd`, or
+    /// the app's own directory when the shell has not reported one.
+    pub fn link_at<T: EventListener>(&mut self, term: &Term<T>, point: Point, cwd: Option<&Path>) -> Option<DetectedLink> {
+        let start = term.line_search_left(point);
+        let end = term.line_search_right(point);
+
+        if let Some(found) = match_at(term, &mut self.url, start, end, point) {
+            let text = term.bounds_to_string(*found.start(), *found.end());
+            let url = trim_url(&text);
+            let bounds = trim_bounds(term, &found, text.chars().count() - url.chars().count());
+            return bounds.contains(&point).then(|| DetectedLink { bounds, target: LinkTarget::Url(url.to_string()) });
+        }
+
+        let found = match_at(term, &mut self.file, start, end, point)?;
+        let text = term.bounds_to_string(*found.start(), *found.end());
+        let reference = text.trim_end_matches('.');
+        let (path, line, column) = parse_file_reference(reference);
+        if line.is_none() && !path.contains('/') {
+            return None;
+        }
+        let path = resolve_path(path, cwd)?;
+        let bounds = trim_bounds(term, &found, text.len() - reference.len());
+        bounds.contains(&point).then(|| DetectedLink { bounds, target: LinkTarget::File { path, line, column } })
+    }
+}
+
+/// The match of `regex` between `start` and `end` that contains `point`.
+fn match_at<T: EventListener>(term: &Term<T>, regex: &mut RegexSearch, start: Point, end: Point, point: Point) -> Option<Match> {
+    RegexIter::new(start, end, Direction::Right, term, regex)
+        .take_while(|m| *m.start() <= point)
+        .find(|m| m.contains(&point))
+}
+
+/// `bounds` with the last `cut` cells removed.
+fn trim_bounds<T: EventListener>(term: &Term<T>, bounds: &Match, cut: usize) -> Match {
+    *bounds.start()..=bounds.end().sub(term, Boundary::Grid, cut)
+}
+
+/// Drop sentence punctuation after a URL, and a closing parenthesis that
+/// belongs to the surrounding text rather than the URL.
+fn trim_url(url: &str) -> &str {
+    let mut url = url;
+    loop {
+        let trimmed = url.trim_end_matches(['.', ',', ':', ';', '!', '?', '\'']);
+        let trimmed = match trimmed.strip_suffix(')') {
+            Some(inner) if inner.matches('(').count() < trimmed.matches(')').count() => inner,
+            _ => trimmed,
+        };
+        if trimmed.len() == url.len() {
+            return url;
+        }
+        url = trimmed;
+    }
+}
+
+/// Split `path:line:column` into its parts.
+fn parse_file_reference(text: &str) -> (&str, Option<u32>, Option<u32>) {
+    let mut path = text;
+    let mut numbers = Vec::new();
+    while numbers.len() < 2 {
+        match path.rsplit_once(':') {
+            Some((head, n)) if !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()) => {
+                numbers.push(n.parse().ok());
+                path = head;
+            }
+            _ => break,
+        }
+    }
+    numbers.reverse();
+    (path, numbers.first().copied().flatten(), numbers.get(1).copied().flatten())
+}
+
+/// `path` made absolute, if it exists.
+fn resolve_path(path: &str, cwd: Option<&Path>) -> Option<PathBuf> {
+    let path = crate::color_scheme::expand_home(path);
+    let path = if path.is_absolute() {
+        path
+    } else {
+        match cwd {
+            Some(dir) => dir.join(path),
+            None => std::env::current_dir().ok()?.join(path),
+        }
+    };
+    path.exists().then_some(path)
+}
+
+/// Open a link: URLs with the system opener, file references with the
+/// `editor` command (or the system opener when it is empty).
+pub fn open(target: &LinkTarget, editor: &str) {
+    match target {
+        LinkTarget::Url(url) => system_open(url),
+        LinkTarget::File { path, line, column } if !editor.trim().is_empty() => {
+            let argv = editor_command(editor, path, *line, *column);
+            info!(target: "terminal", "Opening {} with {}", path.display(), argv[0]);
+            if let Err(e) = std::process::Command::new(&argv[0]).args(&argv[1..]).spawn() {
+                warn!(target: "terminal", "Failed to run editor {:?}: {}", argv[0], e);
+            }
+        }
+        LinkTarget::File { path, .. } => system_open(&path.to_string_lossy()),
+    }
+}
+
+fn system_open(target: &str) {
+    let opener = if cfg!(target_os = "macos") { "open" } else { "xdg-open" };
+    info!(target: "terminal", "Opening link: {}", target);
+    if let Err(e) = std::process::Command::new(opener).arg(target).spawn() {
+        warn!(target: "terminal", "Failed to run {}: {}", opener, e);
+    }
+}
+
+/// Expand the `terminal.editor` template. `{file}`, `{line}` and
+/// `{column}` are substituted (line and column default to 1); without
+/// `{file}` the path is appended. Arguments split on whitespace.
+fn editor_command(template: &str, path: &Path, line: Option<u32>, column: Option<u32>) -> Vec<String> {
+    let file = path.to_string_lossy();
+    let line = line.unwrap_or(1).to_string();
+    let column = column.unwrap_or(1).to_string();
+    let mut argv: Vec<String> = template
+        .split_whitespace()
+        .map(|arg| arg.replace("{file}", &file).replace("{line}", &line).replace("{column}", &column))
+        .collect();
+    if !template.contains("{file}") {
+        argv.push(file.into_owned());
+    }
+    argv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trims_trailing_punctuation() {
+        assert_eq!(trim_url("https://example.com/a."), "https://example.com/a");
+        assert_eq!(trim_url("https://example.com/a),"), "https://example.com/a");
+        assert_eq!(trim_url("https://en.wikipedia.org/wiki/Rust_(language)"), "https://en.wikipedia.org/wiki/Rust_(language)");
+    }
+
+    #[test]
+    fn parses_line_and_column() {
+        assert_eq!(parse_file_reference("src/main.rs:42:7"), ("src/main.rs", Some(42), Some(7)));
+        assert_eq!(parse_file_reference("src/main.rs:42"), ("src/main.rs", Some(42), None));
+        assert_eq!(parse_file_reference("src/main.rs"), ("src/main.rs", None, None));
+        assert_eq!(parse_file_reference("a:1:2:3"), ("a:1", Some(2), Some(3)));
+    }
+
+    #[test]
+    fn editor_template() {
+        let path = Path::new("/tmp/a b.rs");
+        assert_eq!(
+            editor_command("code --goto {file}:{line}:{column}", path, Some(3), None),
+            ["code", "--goto", "/tmp/a b.rs:3:1"]
+        );
+        assert_eq!(editor_command("zed", path, None, None), ["zed", "/tmp/a b.rs"]);
+    }
+}
@@ -0,0 +1,328 @@
+//! Scrollback search for a terminal pane.
+//!
+//! `TerminalSearch` holds the find-bar state for one pane: the query, whether
+//! it is a regex or a literal, and every match across the scrollback history
+//! and the visible screen. Matching is done by `alacritty_terminal`'s
+//! `RegexSearch`, which is case-insensitive unless the query contains an
+//! uppercase letter. Literal queries are escaped before compiling.
+//!
+//! Matches are kept oldest first. As in iTerm2, search runs back through
+//! history: a new query selects the newest match at or above the bottom of
+//! the viewport, and "next" moves up toward older output.
+
+use alacritty_terminal::Term;
+use alacritty_terminal::event::EventListener;
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Direction, Line, Point};
+use alacritty_terminal::term::search::{Match, RegexIter, RegexSearch};
+
+/// Upper bound on collected matches, so a one-letter query over a long
+/// history stays cheap.
+const MAX_MATCHES: usize = 5000;
+
+/// Characters with a meaning in regex syntax.
+const REGEX_META: &[char] = &[
+    '\\', '.', '+', '*', '?', '(', ')', '|', '[', ']', '{', '}', '^', '$', '#', '&', '-', '~',
+];
+
+/// Escape `text` so it matches literally when compiled as a regex.
+pub fn escape_literal(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if REGEX_META.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+pub struct TerminalSearch {
+    query: String,
+    regex_mode: bool,
+    compiled: Option<RegexSearch>,
+    error: Option<String>,
+    matches: Vec<Match>,
+    /// Index into `matches` of the selected match.
+    current: Option<usize>,
+    /// Query or mode changed since the last `refresh`.
+    dirty: bool,
+    /// Terminal content changed since the last `refresh`.
+    stale: bool,
+}
+
+impl TerminalSearch {
+    pub fn new() -> Self {
+        Self {
+            query: String::new(),
+            regex_mode: false,
+            compiled: None,
+            error: None,
+            matches: Vec::new(),
+            current: None,
+            dirty: false,
+            stale: false,
+        }
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn is_regex(&self) -> bool {
+        self.regex_mode
+    }
+
+    pub fn push_str(&mut self, s: &str) {
+        self.query.push_str(s);
+        self.dirty = true;
+    }
+
+    pub fn pop(&mut self) {
+        if self.query.pop().is_some() {
+            self.dirty = true;
+        }
+    }
+
+    pub fn toggle_regex(&mut self) {
+        self.regex_mode = !self.regex_mode;
+        self.dirty = true;
+    }
+
+    /// Whether the query changed since the last refresh.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Note new output: the matches' positions no longer hold. Rescanning
+    /// is left to the next `refresh`, so a burst of output costs one scan.
+    pub fn mark_stale(&mut self) {
+        self.stale = true;
+    }
+
+    /// Whether output arrived since the last refresh.
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /// Recompile the query if needed and re-collect matches from `term`.
+    ///
+    /// After a query change the selection resets to the newest match at or
+    /// above the viewport bottom. When only the terminal content changed
+    /// (new output), the selected index is kept, clamped to the new count.
+    pub fn refresh<T: EventListener>(&mut self, term: &Term<T>) {
+        let query_changed = self.dirty;
+        self.stale = false;
+        if query_changed {
+            self.dirty = false;
+            self.error = None;
+            self.compiled = None;
+            if !self.query.is_empty() {
+                let pattern = if self.regex_mode {
+                    self.query.clone()
+                } else {
+                    escape_literal(&self.query)
+                };
+                match RegexSearch::new(&pattern) {
+                    Ok(regex) => self.compiled = Some(regex),
+                    Err(e) => self.error = Some(e.to_string()),
+                }
+            }
+        }
+
+        self.matches.clear();
+        if let Some(regex) = self.compiled.as_mut() {
+            let start = Point::new(term.topmost_line(), Column(0));
+            let end = Point::new(term.bottommost_line(), term.last_column());
+            self.matches
+                .extend(RegexIter::new(start, end, Direction::Right, term, regex).take(MAX_MATCHES));
+        }
+
+        if self.matches.is_empty() {
+            self.current = None;
+        } else if query_changed || self.current.is_none() {
+            let viewport_bottom = Line(term.screen_lines() as i32 - 1 - term.grid().display_offset() as i32);
+            self.current = Some(
+                self.matches
+                    .iter()
+                    .rposition(|m| m.start().line <= viewport_bottom)
+                    .unwrap_or(self.matches.len() - 1),
+            );
+        } else {
+            self.current = self.current.map(|i| i.min(self.matches.len() - 1));
+        }
+    }
+
+    /// Move the selection; `older` steps up through history. Wraps around.
+    pub fn step(&mut self, older: bool) {
+        let n = self.matches.len();
+        if n == 0 {
+            return;
+        }
+        self.current = Some(match self.current {
+            Some(i) if older => (i + n - 1) % n,
+            Some(i) => (i + 1) % n,
+            None => n - 1,
+        });
+    }
+
+    pub fn matches(&self) -> &[Match] {
+        &self.matches
+    }
+
+    pub fn current_match(&self) -> Option<&Match> {
+        self.current.and_then(|i| self.matches.get(i))
+    }
+
+    /// Short status for the find bar: "3 of 17", "No matches", or the
+    /// regex compile error.
+    pub fn status(&self) -> String {
+        if let Some(err) = &self.error {
+            let first = err.lines().next().unwrap_or("invalid pattern");
+            return format!("Invalid regex: {}", first);
+        }
+        if self.query.is_empty() {
+            return String::new();
+        }
+        match (self.current, self.matches.len()) {
+            (_, 0) => "No matches".into(),
+            (Some(i), n) if n >= MAX_MATCHES => format!("{} of {}+", n - i, n),
+            // Numbered from the bottom, so "1 of n" is the newest match.
+            (Some(i), n) => format!("{} of {}", n - i, n),
+            (None, n) => format!("{} matches", n),
+        }
+    }
+}
+
+/// Display offset that brings grid `line` into a viewport of `screen_lines`
+/// rows, or `None` if it is already visible. Off-screen matches land near
+/// the middle of the viewport.
+pub fn offset_to_reveal(line: Line, display_offset: usize, screen_lines: usize, history: usize) -> Option<usize> {
+    let top = -(display_offset as i32);
+    let bottom = top + screen_lines as i32 - 1;
+    if line.0 >= top && line.0 <= bottom {
+        return None;
+    }
+    let centered = -line.0 + (screen_lines / 2) as i32;
+    Some(centered.clamp(0, history as i32) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::term::Config;
+    use alacritty_terminal::vte::ansi::Processor;
+
+    struct Size(usize, usize);
+
+    impl Dimensions for Size {
+        fn total_lines(&self) -> usize {
+            self.1
+        }
+        fn screen_lines(&self) -> usize {
+            self.1
+        }
+        fn columns(&self) -> usize {
+            self.0
+        }
+    }
+
+    /// A 20x4 terminal fed with `lines`, so earlier lines end up in history.
+    fn term_with(lines: &[&str]) -> Term<VoidListener> {
+        let mut term = Term::new(Config::default(), &Size(20, 4), VoidListener);
+        let mut parser: Processor = Processor::new();
+        for line in lines {
+            parser.advance(&mut term, format!("{}\r\n", line).as_bytes());
+        }
+        term
+    }
+
+    #[test]
+    fn escape_makes_metacharacters_literal() {
+        assert_eq!(escape_literal("a.b*c"), "a\\.b\\*c");
+        assert_eq!(escape_literal("plain words"), "plain words");
+        assert_eq!(escape_literal("[x](y)"), "\\[x\\]\\(y\\)");
+    }
+
+    #[test]
+    fn finds_matches_in_scrollback() {
+        let term = term_with(&["error one", "ok", "ok", "ok", "ok", "error two", "ok"]);
+        let mut search = TerminalSearch::new();
+        search.push_str("error");
+        search.refresh(&term);
+        assert!(!search.is_stale());
+        assert_eq!(search.matches().len(), 2);
+        // The older match is above the visible screen.
+        assert!(search.matches()[0].start().line < Line(0));
+        // Newest match is selected first.
+        assert_eq!(search.status(), "1 of 2");
+    }
+
+    #[test]
+    fn new_output_waits_for_the_next_refresh() {
+        let mut term = term_with(&["error one", "ok"]);
+        let mut search = TerminalSearch::new();
+        search.push_str("error");
+        search.refresh(&term);
+        assert_eq!(search.matches().len(), 1);
+
+        let mut parser: Processor = Processor::new();
+        parser.advance(&mut term, b"error two\r\n");
+        search.mark_stale();
+        assert!(search.is_stale() && !search.is_dirty());
+        assert_eq!(search.matches().len(), 1);
+        search.refresh(&term);
+        assert!(!search.is_stale());
+        assert_eq!(search.matches().len(), 2);
+    }
+
+    #[test]
+    fn literal_and_regex_modes_differ() {
+        let term = term_with(&["a.c", "abc"]);
+        let mut search = TerminalSearch::new();
+        search.push_str("a.c");
+        search.refresh(&term);
+        assert_eq!(search.matches().len(), 1);
+
+        search.toggle_regex();
+        search.refresh(&term);
+        assert_eq!(search.matches().len(), 2);
+    }
+
+    #[test]
+    fn invalid_regex_reports_error() {
+        let term = term_with(&["abc"]);
+        let mut search = TerminalSearch::new();
+        search.toggle_regex();
+        search.push_str("(unclosed");
+        search.refresh(&term);
+        assert!(search.matches().is_empty());
+        assert!(search.status().starts_with("Invalid regex"));
+    }
+
+    #[test]
+    fn step_wraps_in_both_directions() {
+        let term = term_with(&["x", "x", "x"]);
+        let mut search = TerminalSearch::new();
+        search.push_str("x");
+        search.refresh(&term);
+        assert_eq!(search.matches().len(), 3);
+        assert_eq!(search.status(), "1 of 3");
+        search.step(true);
+        assert_eq!(search.status(), "2 of 3");
+        search.step(false);
+        search.step(false);
+        assert_eq!(search.status(), "3 of 3");
+    }
+
+    #[test]
+    fn reveal_centers_offscreen_lines() {
+        // Visible already: no scroll.
+        assert_eq!(offset_to_reveal(Line(2), 0, 10, 100), None);
+        // 30 lines up into history: center it.
+        assert_eq!(offset_to_reveal(Line(-30), 0, 10, 100), Some(35));
+        // Clamped to the available history.
+        assert_eq!(offset_to_reveal(Line(-98), 0, 10, 50), Some(50));
+    }
+}
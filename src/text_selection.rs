@@ -183,6 +183,11 @@ impl SelectableText {
         self.is_dragging = false;
     }
 
+    /// Laid-out height of the text in pixels (computes the layout if needed).
+    pub fn layout_height(&mut self, ctx: &mut ParleyCtx) -> f64 {
+        self.editor.layout(&mut ctx.font_cx, &mut ctx.layout_cx).height() as f64
+    }
+
     /// Return the currently selected text, if any.
     pub fn selected_text(&self) -> Option<&str> {
         self.editor.selected_text()
@@ -260,6 +260,22 @@ cursor_blink_ms = 500   # Cursor blink interval
 [terminal]
 font_size = 28.0    # Terminal font size in pixels
 pad_cells = 1       # Terminal horizontal padding in cell widths
+foreground = "#000000"    # Default text color
+background = "#ffe4c4"    # Default background (bisque)
+cursor = "#664221d9"      # Cursor color, #rrggbbaa
+selection = "#3380ff59"   # Selection highlight, #rrggbbaa
+# ANSI colors 0-7, then bright 8-15
+palette = [
+    "#000000", "#cc1c1c", "#21b04d", "#ccb01c", "#3366e6", "#ab1ccc", "#1cb0cc", "#bfbfbf",
+    "#1a1f21", "#f04d4d", "#4dde4d", "#f0f033", "#6b96f0", "#de4df0", "#4ddef0", "#f0f0f0",
+]
+bold_is_bright = false    # Bold text in colors 0-7 uses the bright variant
+scheme = ""               # Import an .itermcolors, Alacritty .toml or base16 .yaml file
+cursor_shape = "block"    # block, underline or beam (apps may override)
+cursor_blink = true       # Blink at animation.cursor_blink_ms
+clipboard_write = true    # Let programs copy to the clipboard (OSC 52)
+editor = ""               # Opens clicked file:line refs, e.g. "code --goto {file}:{line}:{column}"
+on_exit = "keep"          # When a pane's process exits: keep, close or restart
 "#
     .to_string()
 }
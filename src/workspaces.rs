@@ -0,0 +1,411 @@
+//! Terminal workspaces: several named pane trees on the terminal screen.
+//!
+//! Each workspace is its own `PaneTree` with its own backend, so separate
+//! projects (a local shell in one, a Claude container in another) keep
+//! separate layouts. One shows at a time. With two or more, a tab strip of
+//! their names runs along the top of the screen; clicking a name or the
+//! next/previous workspace commands switch between them.
+
+use tracing::info;
+use vello::kurbo::{Affine, Point, Rect};
+use vello::peniko::{Color, Fill, FontData};
+use vello::Scene;
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use crate::pane_tree::{PaneTree, TerminalBackend};
+use crate::session::WorkspaceState;
+
+/// Height of the tab strip while it shows.
+pub const TAB_STRIP_HEIGHT: f64 = 28.0;
+const TAB_TEXT_SIZE: f64 = 13.0;
+/// Space either side of a tab's name.
+const TAB_PAD_X: f64 = 14.0;
+/// Rough advance per character, as a fraction of the text size; tabs are
+/// laid out before any text is shaped.
+const TAB_CHAR_WIDTH: f64 = 0.6;
+
+const TAB_STRIP_BG: Color = Color::new([0.96, 0.87, 0.75, 1.0]);
+const TAB_RULE: Color = Color::new([0.0, 0.0, 0.0, 0.15]);
+const TAB_INK: Color = Color::new([0.0, 0.0, 0.0, 0.45]);
+const TAB_ACTIVE_INK: Color = Color::new([0.0, 0.0, 0.0, 1.0]);
+
+pub struct Workspace {
+    pub name: String,
+    /// Backend of the workspace's first pane, and of a fresh pane if all
+    /// of them close.
+    pub backend: TerminalBackend,
+    /// `None` when every pane has closed or the first failed to spawn.
+    pub tree: Option<PaneTree>,
+}
+
+/// The workspaces in tab order; never empty.
+pub struct Workspaces {
+    list: Vec<Workspace>,
+    active: usize,
+    /// Name typed so far while renaming the active workspace.
+    renaming: Option<String>,
+    /// Record Claude panes in every workspace, including ones opened later.
+    record_claude: bool,
+}
+
+impl Workspaces {
+    pub fn new(first: Workspace) -> Self {
+        Self { list: vec![first], active: 0, renaming: None, record_claude: false }
+    }
+
+    /// Rebuild saved workspaces, spawning their panes. A workspace whose
+    /// panes had all closed starts again with a single pane. `None` if
+    /// nothing was saved.
+    pub fn restore(saved: &[WorkspaceState], active: usize, width: f64, height: f64) -> Option<Self> {
+        let list: Vec<Workspace> = saved
+            .iter()
+            .map(|ws| {
+                let tree = match &ws.panes {
+                    Some(layout) => PaneTree::from_layout(layout, &ws.focus_path, width, height),
+                    None => PaneTree::with_backend(width, height, ws.backend.clone()),
+                };
+                Workspace { name: ws.name.clone(), backend: ws.backend.clone(), tree }
+            })
+            .collect();
+        if list.is_empty() {
+            return None;
+        }
+        let active = active.min(list.len() - 1);
+        Some(Self { list, active, renaming: None, record_claude: false })
+    }
+
+    /// The workspaces for the session file, and the index of the active one.
+    pub fn snapshot(&self) -> (Vec<WorkspaceState>, usize) {
+        let list = self
+            .list
+            .iter()
+            .map(|ws| WorkspaceState {
+                name: ws.name.clone(),
+                backend: ws.backend.clone(),
+                panes: ws.tree.as_ref().and_then(|t| t.layout()),
+                focus_path: ws.tree.as_ref().map_or_else(Vec::new, |t| t.focus_path().to_vec()),
+            })
+            .collect();
+        (list, self.active)
+    }
+
+    pub fn active(&self) -> Option<&PaneTree> {
+        self.list[self.active].tree.as_ref()
+    }
+
+    pub fn active_mut(&mut self) -> Option<&mut PaneTree> {
+        self.list[self.active].tree.as_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Every workspace's pane tree, showing or not.
+    pub fn trees_mut(&mut self) -> impl Iterator<Item = &mut PaneTree> {
+        self.list.iter_mut().filter_map(|ws| ws.tree.as_mut())
+    }
+
+    pub fn set_record_claude(&mut self, on: bool) {
+        self.record_claude = on;
+        for tree in self.trees_mut() {
+            tree.set_record_claude(on);
+        }
+    }
+
+    /// Height the tab strip takes at the top of the terminal screen: none
+    /// for a lone workspace unless it is being renamed.
+    pub fn strip_height(&self) -> f64 {
+        if self.list.len() > 1 || self.renaming.is_some() { TAB_STRIP_HEIGHT } else { 0.0 }
+    }
+
+    /// The first of `Workspace 1`, `Workspace 2`, ... not already taken.
+    pub fn unused_name(&self) -> String {
+        (1..)
+            .map(default_name)
+            .find(|name| self.list.iter().all(|ws| ws.name != *name))
+            .expect("some name is free")
+    }
+
+    /// Open `workspace` after the active one and show it. An active
+    /// workspace with no panes left is replaced instead.
+    pub fn add(&mut self, mut workspace: Workspace) {
+        if let Some(tree) = &mut workspace.tree {
+            tree.set_record_claude(self.record_claude);
+        }
+        info!(target: "terminal", "Opened workspace {:?} ({})", workspace.name, workspace.backend.label());
+        if self.list[self.active].tree.is_none() {
+            self.list[self.active] = workspace;
+        } else {
+            self.active += 1;
+            self.list.insert(self.active, workspace);
+        }
+        self.renaming = None;
+    }
+
+    /// Close the active workspace and its panes. The last workspace stays,
+    /// without panes.
+    pub fn close_active(&mut self) {
+        self.remove(self.active);
+    }
+
+    /// Close the panes of every workspace, as when quitting.
+    pub fn close_all_panes(&mut self) {
+        for workspace in &mut self.list {
+            workspace.tree = None;
+        }
+    }
+
+    fn remove(&mut self, index: usize) {
+        self.renaming = None;
+        if self.list.len() == 1 {
+            self.list[0].tree = None;
+            return;
+        }
+        let closed = self.list.remove(index);
+        info!(target: "terminal", "Closed workspace {:?}", closed.name);
+        if self.active > index || self.active == self.list.len() {
+            self.active -= 1;
+        }
+    }
+
+    /// Show workspace `index`. Returns `false` if it already shows or
+    /// does not exist.
+    pub fn activate(&mut self, index: usize) -> bool {
+        if index >= self.list.len() || index == self.active {
+            return false;
+        }
+        self.active = index;
+        self.renaming = None;
+        true
+    }
+
+    /// Show the next (or previous) workspace, wrapping around.
+    pub fn cycle(&mut self, forward: bool) {
+        let n = self.list.len();
+        let next = if forward { (self.active + 1) % n } else { (self.active + n - 1) % n };
+        self.activate(next);
+    }
+
+    /// Move the active workspace one place along the tab strip.
+    pub fn move_active(&mut self, forward: bool) {
+        let target = if forward { self.active + 1 } else { self.active.wrapping_sub(1) };
+        if target < self.list.len() {
+            self.list.swap(self.active, target);
+            self.active = target;
+        }
+    }
+
+    /// Move the active workspace's focused pane to the next workspace and
+    /// show that one. With a single workspace the pane gets a new workspace
+    /// of its own; a workspace left without panes closes. Follow with a
+    /// relayout.
+    pub fn move_focused_pane(&mut self, width: f64, height: f64) {
+        let source = self.active;
+        let single = self.list.len() == 1;
+        let Some(tree) = self.list[source].tree.as_mut() else { return };
+        if single && tree.pane_count() < 2 {
+            return;
+        }
+        let Some((term, spec)) = tree.detach_focused() else { return };
+        let emptied = tree.pane_count() == 0;
+
+        if single {
+            let name = self.unused_name();
+            let backend = spec.backend.clone();
+            self.add(Workspace { name, backend, tree: Some(PaneTree::from_pane(term, spec)) });
+            return;
+        }
+        let target = (source + 1) % self.list.len();
+        if let Some(tree) = self.list[target].tree.as_mut() {
+            tree.insert_pane(term, spec, width, height);
+        } else {
+            let mut tree = PaneTree::from_pane(term, spec);
+            tree.set_record_claude(self.record_claude);
+            self.list[target].tree = Some(tree);
+        }
+        self.active = target;
+        self.renaming = None;
+        if emptied {
+            self.remove(source);
+        }
+    }
+
+    /// Drain every workspace's PTY output and apply exit handling, so
+    /// background workspaces keep up too. A workspace whose last pane
+    /// closed goes away. Returns `true` if one did.
+    pub fn drain_all_output(&mut self) -> bool {
+        let mut emptied = Vec::new();
+        for (index, ws) in self.list.iter_mut().enumerate() {
+            if let Some(tree) = &mut ws.tree {
+                tree.drain_all_output();
+                if !tree.handle_exits() {
+                    emptied.push(index);
+                }
+            }
+        }
+        for &index in emptied.iter().rev() {
+            self.remove(index);
+        }
+        !emptied.is_empty()
+    }
+
+    // --- Renaming ------------------------------------------------------------
+
+    /// Start editing the active workspace's name in its tab.
+    pub fn start_rename(&mut self) {
+        self.renaming = Some(self.list[self.active].name.clone());
+    }
+
+    pub fn is_renaming(&self) -> bool {
+        self.renaming.is_some()
+    }
+
+    /// Edit the name being typed: Enter keeps it (unless blank), Escape
+    /// cancels.
+    pub fn rename_key(&mut self, key: &Key, mods: ModifiersState) {
+        let Some(name) = &mut self.renaming else { return };
+        match key {
+            Key::Named(NamedKey::Escape) => self.renaming = None,
+            Key::Named(NamedKey::Enter) => {
+                let name = self.renaming.take().unwrap_or_default();
+                let name = name.trim();
+                if !name.is_empty() {
+                    info!(target: "terminal", "Renamed workspace {:?} to {:?}", self.list[self.active].name, name);
+                    self.list[self.active].name = name.to_string();
+                }
+            }
+            Key::Named(NamedKey::Backspace) => {
+                name.pop();
+            }
+            Key::Named(NamedKey::Space) => name.push(' '),
+            Key::Character(c) if !mods.control_key() && !mods.super_key() => name.push_str(c),
+            _ => {}
+        }
+    }
+
+    // --- Tab strip -----------------------------------------------------------
+
+    /// Each tab's text and rectangle, left to right.
+    fn tabs(&self) -> Vec<(String, Rect)> {
+        let mut x = 0.0;
+        self.list
+            .iter()
+            .enumerate()
+            .map(|(i, ws)| {
+                let label = match &self.renaming {
+                    Some(name) if i == self.active => format!("{}▏", name),
+                    _ => ws.name.clone(),
+                };
+                let w = label.chars().count() as f64 * TAB_TEXT_SIZE * TAB_CHAR_WIDTH + TAB_PAD_X * 2.0;
+                let rect = Rect::new(x, 0.0, x + w, TAB_STRIP_HEIGHT);
+                x += w;
+                (label, rect)
+            })
+            .collect()
+    }
+
+    /// The tab under `(x, y)`, in terminal screen coordinates.
+    pub fn tab_at(&self, x: f64, y: f64) -> Option<usize> {
+        if y >= self.strip_height() {
+            return None;
+        }
+        self.tabs().iter().position(|(_, rect)| rect.contains(Point::new(x, y)))
+    }
+
+    /// Draw the tab strip across the top of the terminal screen: the names
+    /// in a row, the active one in full ink and underlined.
+    pub fn render_strip(&self, scene: &mut Scene, width: f64, font: Option<&FontData>) {
+        let h = self.strip_height();
+        if h == 0.0 {
+            return;
+        }
+        scene.fill(Fill::NonZero, Affine::IDENTITY, TAB_STRIP_BG, None, &Rect::new(0.0, 0.0, width, h));
+        scene.fill(Fill::NonZero, Affine::IDENTITY, TAB_RULE, None, &Rect::new(0.0, h - 1.0, width, h));
+
+        let baseline = h * 0.5 + TAB_TEXT_SIZE * 0.35;
+        for (i, (label, rect)) in self.tabs().iter().enumerate() {
+            let active = i == self.active;
+            let ink = if active { TAB_ACTIVE_INK } else { TAB_INK };
+            crate::dashboard::draw_text_pub(scene, rect.x0 + TAB_PAD_X, baseline, label, ink, TAB_TEXT_SIZE, font);
+            if active {
+                let underline = Rect::new(rect.x0 + TAB_PAD_X, h - 3.0, rect.x1 - TAB_PAD_X, h - 1.0);
+                scene.fill(Fill::NonZero, Affine::IDENTITY, TAB_ACTIVE_INK, None, &underline);
+            }
+        }
+    }
+}
+
+/// Name of the `n`th workspace until it is renamed.
+pub fn default_name(n: usize) -> String {
+    format!("Workspace {}", n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspaces(names: &[&str], active: usize) -> Workspaces {
+        let list = names
+            .iter()
+            .map(|name| Workspace { name: name.to_string(), backend: TerminalBackend::Local, tree: None })
+            .collect();
+        Workspaces { list, active, renaming: None, record_claude: false }
+    }
+
+    fn names(ws: &Workspaces) -> Vec<&str> {
+        ws.list.iter().map(|w| w.name.as_str()).collect()
+    }
+
+    #[test]
+    fn reorder_cycle_and_close() {
+        let mut ws = workspaces(&["a", "b", "c"], 1);
+        ws.move_active(true);
+        assert_eq!(names(&ws), ["a", "c", "b"]);
+        assert_eq!(ws.active, 2);
+        ws.move_active(true);
+        assert_eq!(ws.active, 2);
+
+        ws.cycle(true);
+        assert_eq!(ws.active, 0);
+        ws.cycle(false);
+        assert_eq!(ws.active, 2);
+
+        ws.close_active();
+        assert_eq!(names(&ws), ["a", "c"]);
+        assert_eq!(ws.active, 1);
+        ws.remove(0);
+        assert_eq!(ws.active, 0);
+        ws.close_active();
+        assert_eq!(names(&ws), ["c"]);
+        assert_eq!(ws.strip_height(), 0.0);
+    }
+
+    #[test]
+    fn rename_in_place() {
+        let mut ws = workspaces(&["Workspace 1", "b"], 0);
+        assert_eq!(ws.unused_name(), "Workspace 2");
+        ws.start_rename();
+        for _ in 0.."Workspace 1".len() {
+            ws.rename_key(&Key::Named(NamedKey::Backspace), ModifiersState::empty());
+        }
+        ws.rename_key(&Key::Character("api".into()), ModifiersState::empty());
+        assert_eq!(ws.tabs()[0].0, "api▏");
+        ws.rename_key(&Key::Named(NamedKey::Enter), ModifiersState::empty());
+        assert_eq!(names(&ws), ["api", "b"]);
+
+        ws.start_rename();
+        ws.rename_key(&Key::Character("x".into()), ModifiersState::empty());
+        ws.rename_key(&Key::Named(NamedKey::Escape), ModifiersState::empty());
+        assert_eq!(names(&ws), ["api", "b"]);
+    }
+
+    #[test]
+    fn tabs_hit_test_left_to_right() {
+        let ws = workspaces(&["one", "two"], 0);
+        let second = ws.tabs()[1].1;
+        assert_eq!(ws.tab_at(second.x0 + 1.0, 10.0), Some(1));
+        assert_eq!(ws.tab_at(1.0, 10.0), Some(0));
+        assert_eq!(ws.tab_at(1.0, TAB_STRIP_HEIGHT + 1.0), None);
+    }
+}
@@ -10,7 +10,7 @@ use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use futures_util::{SinkExt, StreamExt};
-use tokio::sync::mpsc;
+use tokio::sync::{Notify, mpsc};
 use tokio_tungstenite::connect_async;
 use tokio_tungstenite::tungstenite::Message;
 
@@ -25,15 +25,24 @@ pub type SharedInstances = Arc<Mutex<Vec<LobsterInstance>>>;
 pub struct OutboundSender {
     /// One sender per registered client instance.
     senders: Arc<Vec<mpsc::UnboundedSender<String>>>,
+    /// Wakes every client loop to drop its connection and dial again.
+    reconnect: Arc<Notify>,
 }
 
 impl OutboundSender {
-    fn new(senders: Vec<mpsc::UnboundedSender<String>>) -> Self {
+    fn new(senders: Vec<mpsc::UnboundedSender<String>>, reconnect: Arc<Notify>) -> Self {
         Self {
             senders: Arc::new(senders),
+            reconnect,
         }
     }
 
+    /// Ask every client to reconnect now: live connections are closed and
+    /// clients waiting out the retry delay dial immediately.
+    pub fn reconnect_all(&self) {
+        self.reconnect.notify_waiters();
+    }
+
     /// Broadcast a JSON payload to all connected instances.
     ///
     /// Silently drops sends to disconnected clients (their receivers were dropped).
@@ -61,28 +70,31 @@ pub fn spawn_clients(
 
     // Build one mpsc channel per client instance for outbound fan-out.
     let mut per_client_senders: Vec<mpsc::UnboundedSender<String>> = Vec::new();
+    let reconnect = Arc::new(Notify::new());
 
     for (index, url) in urls.into_iter().enumerate() {
         let (tx, rx) = mpsc::unbounded_channel::<String>();
         per_client_senders.push(tx);
 
         let shared_clone = Arc::clone(&shared);
-        runtime.spawn(client_loop(shared_clone, index, url, rx));
+        runtime.spawn(client_loop(shared_clone, index, url, rx, Arc::clone(&reconnect)));
     }
 
-    let outbound = OutboundSender::new(per_client_senders);
+    let outbound = OutboundSender::new(per_client_senders, reconnect);
     (shared, outbound)
 }
 
 /// Reconnecting client loop for a single Lobster instance.
 ///
 /// Accepts outbound messages via `outbound_rx` and forwards them to the server
-/// when connected.
+/// when connected. A `reconnect` notification closes the current connection
+/// (or cuts the retry delay short) and dials again.
 async fn client_loop(
     shared: SharedInstances,
     index: usize,
     url: String,
     mut outbound_rx: mpsc::UnboundedReceiver<String>,
+    reconnect: Arc<Notify>,
 ) {
     loop {
         // Update status to Connecting
@@ -137,6 +149,12 @@ async fn client_loop(
                                 break;
                             }
                         }
+
+                        // User asked to reconnect
+                        _ = reconnect.notified() => {
+                            let _ = write.send(Message::Close(None)).await;
+                            break;
+                        }
                     }
                 }
 
@@ -158,8 +176,11 @@ async fn client_loop(
             }
         }
 
-        // Wait before reconnecting
-        tokio::time::sleep(Duration::from_secs(3)).await;
+        // Wait before reconnecting (unless asked to reconnect right away)
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(3)) => {}
+            _ = reconnect.notified() => {}
+        }
     }
 }
 